@@ -1,4 +1,14 @@
+#[allow(clippy::module_inception)]
 pub mod date;
+pub mod calendar;
+pub mod clock;
+pub mod cron;
 pub mod rcf3339;
+pub mod rfc2822;
 pub mod iso8601;
+pub mod partial;
 pub mod posix;
+pub mod rrule;
+pub mod stopwatch;
+pub mod timezone;
+mod format;
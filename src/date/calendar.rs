@@ -0,0 +1,352 @@
+use std::collections::BTreeSet;
+use crate::date::date::{Date, Weekday, SECONDS_PER_DAY};
+
+/// A business calendar: which weekdays are weekend days, and a list of holidays.
+///
+/// Used by `Date::add_business_days` and `Date::business_days_between`.
+/// Holidays are given as dates, or as yearly rules with a `HolidayCalendar`.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::date::calendar::Calendar;
+/// use stdt::date::iso8601::Iso8601;
+/// let christmas = Iso8601::parse("2023-12-25T00:00:00").unwrap().date;
+/// let calendar = Calendar::new().with_holidays([christmas]);
+/// assert!(!calendar.is_business_day(&christmas));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar {
+    weekend: u8,
+    holidays: BTreeSet<i64>,
+    rules: HolidayCalendar,
+}
+
+/// A yearly holiday rule for a `HolidayCalendar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HolidayRule {
+    /// The same day every year, e.g. `Fixed { month: 12, day: 25 }`. A
+    /// February 29th rule only applies in leap years.
+    Fixed { month: u8, day: u8 },
+    /// The `nth` `weekday` of `month`, counting from the end when negative:
+    /// `nth: 4` is the fourth, `nth: -1` the last. A fifth weekday only
+    /// applies in the years that have one.
+    NthWeekday { month: u8, nth: i8, weekday: Weekday },
+    /// `offset` days from Western (Gregorian) Easter Sunday, e.g. `-2` for
+    /// Good Friday or `1` for Easter Monday. The offset may be at most a year
+    /// (±365 days), so the holiday can fall in the previous or next year.
+    Easter { offset: i16 },
+}
+
+/// A set of yearly holiday rules, for public holidays that recur every year.
+///
+/// Add it to a `Calendar` with `Calendar::with_holiday_calendar` to use it in
+/// business-day arithmetic.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::date::calendar::{HolidayCalendar, HolidayRule};
+/// use stdt::date::date::Weekday;
+/// use stdt::date::iso8601::Iso8601;
+/// let holidays = HolidayCalendar::new()
+///     .with_rule(HolidayRule::Fixed { month: 12, day: 25 }).unwrap()
+///     .with_rule(HolidayRule::NthWeekday { month: 11, nth: 4, weekday: Weekday::Thursday }).unwrap()
+///     .with_rule(HolidayRule::Easter { offset: -2 }).unwrap();
+/// assert!(holidays.is_holiday(&Iso8601::parse("2023-11-23T12:00:00").unwrap().date));
+/// assert!(holidays.is_holiday(&Iso8601::parse("2024-03-29T00:00:00").unwrap().date));
+/// assert!(!holidays.is_holiday(&Iso8601::parse("2024-11-23T00:00:00").unwrap().date));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HolidayCalendar {
+    rules: Vec<HolidayRule>,
+}
+
+impl Calendar {
+    /// Creates a calendar with Saturday and Sunday as the weekend and no holidays.
+    pub fn new() -> Self {
+        Calendar {
+            weekend: Self::mask(&[Weekday::Saturday, Weekday::Sunday]),
+            holidays: BTreeSet::new(),
+            rules: HolidayCalendar::new(),
+        }
+    }
+
+    /// Replaces the weekend days (e.g. Friday and Saturday).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if every day of the week is a weekend day.
+    pub fn with_weekend(mut self, days: &[Weekday]) -> Result<Self, String> {
+        let weekend = Self::mask(days);
+        if weekend == 0x7f {
+            return Err("A calendar needs at least one working weekday".into());
+        }
+        self.weekend = weekend;
+        Ok(self)
+    }
+
+    /// Adds holidays; only the calendar day of each date is used.
+    pub fn with_holidays(mut self, dates: impl IntoIterator<Item = Date>) -> Self {
+        self.holidays.extend(dates.into_iter().map(|date| Date::days_from_civil(date.year, date.month, date.day)));
+        self
+    }
+
+    /// Adds the yearly holidays of `holidays`, keeping those already added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::calendar::{Calendar, HolidayCalendar, HolidayRule};
+    /// use stdt::date::iso8601::Iso8601;
+    /// let easter = HolidayCalendar::new()
+    ///     .with_rule(HolidayRule::Easter { offset: -2 }).unwrap()
+    ///     .with_rule(HolidayRule::Easter { offset: 1 }).unwrap();
+    /// let calendar = Calendar::new().with_holiday_calendar(easter);
+    /// // Thursday before Easter 2024 + 1 business day skips Good Friday to Easter Monday.
+    /// let thursday = Iso8601::parse("2024-03-28T09:00:00").unwrap().date;
+    /// assert_eq!(thursday.add_business_days(1, &calendar).day, 2);
+    /// ```
+    pub fn with_holiday_calendar(mut self, holidays: HolidayCalendar) -> Self {
+        self.rules.rules.extend(holidays.rules);
+        self
+    }
+
+    /// Returns `true` if `date` is neither a weekend day nor a holiday.
+    pub fn is_business_day(&self, date: &Date) -> bool {
+        self.is_business_day_number(Date::days_from_civil(date.year, date.month, date.day))
+    }
+
+    // --- Internal Helpers ---
+
+    fn mask(days: &[Weekday]) -> u8 {
+        days.iter().fold(0, |mask, day| mask | 1 << (day.number_from_monday() - 1))
+    }
+
+    fn is_weekend_number(&self, days: i64) -> bool {
+        self.weekend & 1 << (Date::iso_weekday_from_days(days) - 1) != 0
+    }
+
+    /// Same as `is_business_day`, for a day counted from 1970-01-01.
+    pub(crate) fn is_business_day_number(&self, days: i64) -> bool {
+        !self.is_weekend_number(days) && !self.holidays.contains(&days) && !self.rules.is_holiday_number(days)
+    }
+
+    /// Counts the business days among the day numbers `first..=last`.
+    pub(crate) fn count_business_days(&self, first: i64, last: i64) -> i64 {
+        if first > last {
+            return 0;
+        }
+        let total = last - first + 1;
+        let per_week = 7 - self.weekend.count_ones() as i64;
+        let remainder = (first + total / 7 * 7..=last).filter(|&d| !self.is_weekend_number(d)).count() as i64;
+        let holidays = self.holidays.range(first..=last).filter(|&&d| !self.is_weekend_number(d)).count() as i64;
+        let (first_year, _, _) = Date::civil_from_days(first);
+        let (last_year, _, _) = Date::civil_from_days(last);
+        // Rules may land on a weekend, a listed holiday, or each other's day.
+        let rule_days: BTreeSet<i64> = (first_year..=last_year)
+            .flat_map(|year| self.rules.days_in(year))
+            .filter(|d| (first..=last).contains(d) && !self.is_weekend_number(*d) && !self.holidays.contains(d))
+            .collect();
+        total / 7 * per_week + remainder - holidays - rule_days.len() as i64
+    }
+}
+
+impl HolidayCalendar {
+    /// Creates a holiday calendar without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a yearly holiday rule.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the month is not 1-12, the day does not
+    /// exist in that month, `nth` is 0 or beyond ±5, or an Easter offset is
+    /// beyond ±365.
+    pub fn with_rule(mut self, rule: HolidayRule) -> Result<Self, String> {
+        let valid = match rule {
+            HolidayRule::Fixed { month, day } => (1..=12).contains(&month) && (1..=Date::month_length(2000, month)).contains(&day),
+            HolidayRule::NthWeekday { month, nth, .. } => (1..=12).contains(&month) && nth != 0 && (-5..=5).contains(&nth),
+            HolidayRule::Easter { offset } => (-365..=365).contains(&offset),
+        };
+        if !valid {
+            return Err(format!("Invalid holiday rule: {:?}", rule));
+        }
+        self.rules.push(rule);
+        Ok(self)
+    }
+
+    /// Returns `true` if a rule falls on the calendar day of `date`.
+    pub fn is_holiday(&self, date: &Date) -> bool {
+        self.is_holiday_number(Date::days_from_civil(date.year, date.month, date.day))
+    }
+
+    /// Returns the holidays of `year` at midnight, in order and without repeats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::calendar::{HolidayCalendar, HolidayRule};
+    /// use stdt::date::date::Weekday;
+    /// let holidays = HolidayCalendar::new()
+    ///     .with_rule(HolidayRule::NthWeekday { month: 5, nth: -1, weekday: Weekday::Monday }).unwrap()
+    ///     .with_rule(HolidayRule::Fixed { month: 1, day: 1 }).unwrap();
+    /// let days: Vec<(u8, u8)> = holidays.holidays_in(2024).iter().map(|d| (d.month, d.day)).collect();
+    /// assert_eq!(days, [(1, 1), (5, 27)]);
+    /// ```
+    pub fn holidays_in(&self, year: i32) -> Vec<Date> {
+        let days: BTreeSet<i64> = self.days_in(year).collect();
+        days.into_iter().map(|d| Date::from_seconds(d * SECONDS_PER_DAY)).collect()
+    }
+
+    // --- Internal Helpers ---
+
+    fn is_holiday_number(&self, days: i64) -> bool {
+        let (year, _, _) = Date::civil_from_days(days);
+        self.days_in(year).any(|d| d == days)
+    }
+
+    /// The day numbers of the rules in `year`, unordered and possibly repeated.
+    fn days_in(&self, year: i32) -> impl Iterator<Item = i64> + '_ {
+        let yearly = self.rules.iter().filter_map(move |rule| match *rule {
+            HolidayRule::Fixed { month, day } => {
+                (day <= Date::month_length(year, month)).then(|| Date::days_from_civil(year, month, day))
+            }
+            HolidayRule::NthWeekday { month, nth, weekday } => nth_weekday(year, month, nth, weekday),
+            HolidayRule::Easter { .. } => None,
+        });
+        // An offset of up to a year can move the holiday of the previous or
+        // next Easter into `year`.
+        let easter = self.rules.iter()
+            .filter_map(|rule| match *rule {
+                HolidayRule::Easter { offset } => Some(offset as i64),
+                _ => None,
+            })
+            .flat_map(move |offset| (year.saturating_sub(1)..=year.saturating_add(1)).map(move |y| easter_sunday(y) + offset))
+            .filter(move |&d| Date::civil_from_days(d).0 == year);
+        yearly.chain(easter)
+    }
+}
+
+/// Day number of the `nth` `weekday` of a month (negative counts from the end).
+fn nth_weekday(year: i32, month: u8, nth: i8, weekday: Weekday) -> Option<i64> {
+    let first = Date::days_from_civil(year, month, 1);
+    let last = first + Date::month_length(year, month) as i64 - 1;
+    let target = weekday.number_from_monday() as i64;
+    let day = if nth > 0 {
+        first + (target - Date::iso_weekday_from_days(first) as i64).rem_euclid(7) + 7 * (nth as i64 - 1)
+    } else {
+        last - (Date::iso_weekday_from_days(last) as i64 - target).rem_euclid(7) + 7 * (nth as i64 + 1)
+    };
+    (first..=last).contains(&day).then_some(day)
+}
+
+/// Day number of Western Easter Sunday in `year` (the anonymous Gregorian algorithm).
+fn easter_sunday(year: i32) -> i64 {
+    let y = year as i64;
+    let a = y.rem_euclid(19);
+    let (b, c) = (y.div_euclid(100), y.rem_euclid(100));
+    let (d, e) = (b.div_euclid(4), b.rem_euclid(4));
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15).rem_euclid(30);
+    let (i, k) = (c / 4, c % 4);
+    let l = (32 + 2 * e + 2 * i - h - k).rem_euclid(7);
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    Date::days_from_civil(year, month as u8, day as u8)
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_matches_day_by_day() {
+        let holidays = [(2023, 12, 25), (2023, 12, 26), (2024, 1, 1), (2024, 1, 6)]
+            .map(|(year, month, day)| Date { year, month, day, hour: 0, minute: 0, second: 0 });
+        let calendar = Calendar::new()
+            .with_weekend(&[Weekday::Friday, Weekday::Sunday]).unwrap()
+            .with_holidays(holidays);
+        let start = Date::days_from_civil(2023, 12, 1);
+        for first in start..start + 20 {
+            for last in first - 1..first + 60 {
+                let expected = (first..=last).filter(|&d| calendar.is_business_day_number(d)).count() as i64;
+                assert_eq!(calendar.count_business_days(first, last), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_with_holiday_rules() {
+        let rules = HolidayCalendar::new()
+            .with_rule(HolidayRule::Fixed { month: 1, day: 1 }).unwrap()
+            .with_rule(HolidayRule::Fixed { month: 2, day: 29 }).unwrap()
+            .with_rule(HolidayRule::NthWeekday { month: 3, nth: 5, weekday: Weekday::Friday }).unwrap()
+            .with_rule(HolidayRule::Easter { offset: -2 }).unwrap()
+            .with_rule(HolidayRule::Easter { offset: 39 }).unwrap();
+        let calendar = Calendar::new()
+            .with_holidays([Date { year: 2024, month: 3, day: 29, hour: 0, minute: 0, second: 0 }])
+            .with_holiday_calendar(rules);
+        let start = Date::days_from_civil(2023, 12, 20);
+        for first in (start..start + 200).step_by(7) {
+            for last in first - 1..first + 500 {
+                let expected = (first..=last).filter(|&d| calendar.is_business_day_number(d)).count() as i64;
+                assert_eq!(calendar.count_business_days(first, last), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_holiday_rules() {
+        let day = |d: i64| Date::civil_from_days(d);
+        let easters = [(2000, 4, 23), (2008, 3, 23), (2019, 4, 21), (2024, 3, 31), (2025, 4, 20), (2038, 4, 25)];
+        for (year, month, date) in easters {
+            assert_eq!(day(easter_sunday(year)), (year, month, date));
+        }
+        assert_eq!(nth_weekday(2024, 9, 1, Weekday::Monday).map(day), Some((2024, 9, 2)));
+        assert_eq!(nth_weekday(2024, 2, -1, Weekday::Thursday).map(day), Some((2024, 2, 29)));
+        assert_eq!(nth_weekday(2024, 2, 5, Weekday::Thursday).map(day), Some((2024, 2, 29)));
+        assert_eq!(nth_weekday(2023, 2, 5, Weekday::Thursday), None);
+
+        for rule in [HolidayRule::Fixed { month: 4, day: 31 }, HolidayRule::Fixed { month: 0, day: 1 },
+                     HolidayRule::NthWeekday { month: 1, nth: 0, weekday: Weekday::Monday },
+                     HolidayRule::NthWeekday { month: 1, nth: -6, weekday: Weekday::Monday },
+                     HolidayRule::Easter { offset: 366 }, HolidayRule::Easter { offset: -366 }] {
+            assert!(HolidayCalendar::new().with_rule(rule).is_err());
+        }
+    }
+
+    #[test]
+    fn test_easter_offset_into_neighbouring_year() {
+        // Easter 2024 is March 31st; 100 days earlier is December 22nd, 2023.
+        let before = HolidayCalendar::new().with_rule(HolidayRule::Easter { offset: -100 }).unwrap();
+        let dec22 = Date { year: 2023, month: 12, day: 22, hour: 0, minute: 0, second: 0 };
+        assert!(before.is_holiday(&dec22));
+        assert!(before.holidays_in(2023).contains(&dec22));
+        // Easter 2023 is April 9th; 300 days later is February 3rd, 2024.
+        let after = HolidayCalendar::new().with_rule(HolidayRule::Easter { offset: 300 }).unwrap();
+        let feb3 = Date { year: 2024, month: 2, day: 3, hour: 0, minute: 0, second: 0 };
+        assert!(after.is_holiday(&feb3));
+        assert!(after.holidays_in(2024).contains(&feb3));
+    }
+
+    #[test]
+    fn test_weekend_must_leave_a_working_day() {
+        let all = [Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday, Weekday::Thursday,
+                   Weekday::Friday, Weekday::Saturday, Weekday::Sunday];
+        assert!(Calendar::new().with_weekend(&all).is_err());
+        assert!(Calendar::new().with_weekend(&all[1..]).is_ok());
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::date::posix::Posix;
+
+/// A source of the current time.
+///
+/// Code that reads the time through a `&dyn Clock` (e.g. `Posix::now_with`)
+/// can be tested deterministically with a `MockClock`, and uses the
+/// `SystemClock` in production.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Posix;
+}
+
+/// The system clock, as read by `Posix::now`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Posix {
+        Posix::now()
+    }
+}
+
+/// A clock that only moves when told to, for tests.
+///
+/// It can be shared between threads; `set` and `advance` take `&self`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stdt::date::clock::{Clock, MockClock};
+/// use stdt::date::posix::Posix;
+/// let clock = MockClock::new(Posix::parse("1700749800").unwrap());
+/// clock.advance(Duration::from_millis(1_500));
+/// assert_eq!(clock.now().to_millis(), 1_700_749_801_500);
+/// assert_eq!(Posix::now_with(&clock), clock.now());
+/// ```
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<Posix>,
+}
+
+impl MockClock {
+    /// Creates a clock stopped at `start`.
+    pub fn new(start: Posix) -> Self {
+        MockClock { now: Mutex::new(start) }
+    }
+
+    /// Moves the clock to `now`, forward or back.
+    pub fn set(&self, now: Posix) {
+        *self.lock() = now;
+    }
+
+    /// Moves the clock forward by `duration`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the time moves past the last supported year (`Date::MAX_YEAR`).
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.lock();
+        let nanos = now.timestamp() as i128 * 1_000_000_000 + now.nanos as i128 + duration.as_nanos() as i128;
+        let mut moved = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()
+            .and_then(|secs| Posix::from_timestamp(secs).ok())
+            .expect("MockClock advanced past the supported years");
+        moved.nanos = nanos.rem_euclid(1_000_000_000) as u32;
+        *now = moved;
+    }
+
+    // --- Internal Helpers ---
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Posix> {
+        // A panic while holding the lock cannot leave a `Posix` half-written.
+        self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Posix {
+        *self.lock()
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::iso8601::Iso8601;
+    use crate::date::rcf3339::Rfc3339;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new(Posix::from_millis(-1).unwrap());
+        clock.advance(Duration::from_micros(999_500));
+        assert_eq!(clock.now().to_micros(), 998_500);
+        clock.advance(Duration::from_secs(86_400));
+        assert_eq!(Iso8601::now_utc_with(&clock).to_iso8601(), "1970-01-02T00:00:00Z");
+        assert_eq!(Rfc3339::now_utc_with(&clock).to_rfc3339(), "1970-01-02T00:00:00Z");
+
+        clock.set(Posix::from_timestamp(-86_400).unwrap());
+        assert_eq!(clock.now().timestamp(), -86_400);
+    }
+
+    #[test]
+    fn test_system_clock_moves() {
+        let clock: &dyn Clock = &SystemClock;
+        let first = clock.now();
+        assert!(clock.now().to_micros() >= first.to_micros());
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+use crate::date::date::Date;
+
+/// A parsed cron expression: `minute hour day-of-month month day-of-week`.
+///
+/// Each field accepts `*`, values, ranges (`1-5`), lists (`1,15`), and steps
+/// (`*/5`, `10-50/20`, `5/15`). Months accept `JAN`-`DEC` and weekdays
+/// `SUN`-`SAT` (case-insensitive); weekday `0` and `7` are both Sunday. The
+/// macros `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight`,
+/// and `@hourly` are accepted as well.
+///
+/// As in Vixie cron, when both the day of the month and the day of the week
+/// are restricted, a day matching either one fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    expr: String,
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// Iterator over the firing times of a `Cron`, created by [`Cron::occurrences`].
+#[derive(Debug, Clone)]
+pub struct Occurrences {
+    cron: Cron,
+    last: Option<Date>,
+}
+
+const MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+impl Cron {
+    /// Parses a five-field cron expression (e.g., "*/5 0 * * MON-FRI").
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if there are not five fields, or a field has a
+    /// malformed or out-of-range value, range, or step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::cron::Cron;
+    /// assert!(Cron::parse("*/5 0 * * MON-FRI").is_ok());
+    /// assert!(Cron::parse("0 12 1 JAN,JUL *").is_ok());
+    /// assert!(Cron::parse("60 * * * *").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let expanded = match s.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Expected 5 fields, found {}: {}", fields.len(), s));
+        }
+
+        // Weekday 7 is folded onto Sunday (bit 0).
+        let weekdays = parse_field(fields[4], 0, 7, &WEEKDAYS)?;
+        Ok(Cron {
+            expr: s.trim().to_string(),
+            minutes: parse_field(fields[0], 0, 59, &[])?,
+            hours: parse_field(fields[1], 0, 23, &[])? as u32,
+            days: parse_field(fields[2], 1, 31, &[])? as u32,
+            months: parse_field(fields[3], 1, 12, &MONTHS)? as u16,
+            weekdays: ((weekdays | weekdays >> 7) & 0x7f) as u8,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    /// Returns the first firing time strictly after `date`, or `None` if the
+    /// expression never fires (e.g. February 30th).
+    ///
+    /// Firing times are at whole minutes, so the seconds of `date` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::cron::Cron;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let cron = Cron::parse("*/5 0 * * MON-FRI").unwrap();
+    /// let friday = Iso8601::parse("2023-11-24T00:57:00").unwrap().date;
+    /// let next = cron.next_after(&friday).unwrap();
+    /// assert_eq!(Iso8601 { date: next, offset: None }.to_iso8601(), "2023-11-27T00:00:00");
+    /// ```
+    pub fn next_after(&self, date: &Date) -> Option<Date> {
+        let mut days = Date::days_from_civil(date.year, date.month, date.day);
+        // Minutes into the day from which to search; 1440 moves on to the next day.
+        let mut from = date.hour as u32 * 60 + date.minute as u32 + 1;
+
+        // The calendar repeats every 400 years, so searching further cannot help.
+        let (last, _, _) = Date::civil_from_days(days);
+        let last = last + 400;
+        loop {
+            let (year, month, day) = Date::civil_from_days(days);
+            if year > last {
+                return None;
+            }
+            if self.months & (1 << month) == 0 {
+                // Skip to the first day of the next month.
+                days += (Date::month_length(year, month) - day) as i64 + 1;
+                from = 0;
+                continue;
+            }
+            if self.matches_day(days, day)
+                && let Some(minute) = (from..1440).find(|m| self.hours & (1 << (m / 60)) != 0 && self.minutes & (1 << (m % 60)) != 0)
+            {
+                return Some(Date { year, month, day, hour: (minute / 60) as u8, minute: (minute % 60) as u8, second: 0 });
+            }
+            days += 1;
+            from = 0;
+        }
+    }
+
+    /// Returns the firing times strictly after `date`, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::cron::Cron;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let cron = Cron::parse("30 9 1,15 * *").unwrap();
+    /// let start = Iso8601::parse("2024-01-10T00:00:00").unwrap().date;
+    /// let days: Vec<u8> = cron.occurrences(start).take(3).map(|date| date.day).collect();
+    /// assert_eq!(days, [15, 1, 15]);
+    /// ```
+    pub fn occurrences(&self, after: Date) -> Occurrences {
+        Occurrences { cron: self.clone(), last: Some(after) }
+    }
+
+    // --- Internal Helpers ---
+
+    fn matches_day(&self, days: i64, day: u8) -> bool {
+        let weekday = Date::iso_weekday_from_days(days) % 7; // Sunday = 0
+        let day_hit = self.days & (1 << day) != 0;
+        let weekday_hit = self.weekdays & (1 << weekday) != 0;
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day_hit || weekday_hit,
+            _ => day_hit && weekday_hit,
+        }
+    }
+}
+
+impl Iterator for Occurrences {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Date> {
+        self.last = self.cron.next_after(&self.last?);
+        self.last
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+/// Parses one field into a bit set of the values `min..=max` it selects.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let n = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(i) => i as u32 + min,
+            None => s.parse::<u32>().ok()
+                .filter(|_| s.bytes().all(|b| b.is_ascii_digit()))
+                .ok_or_else(|| format!("Invalid value: {}", s))?,
+        };
+        if !(min..=max).contains(&n) {
+            return Err(format!("Value out of range {}-{}: {}", min, max, s));
+        }
+        Ok(n)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step.parse::<u32>().ok()
+                    .filter(|n| *n > 0 && step.bytes().all(|b| b.is_ascii_digit()))
+                    .ok_or_else(|| format!("Invalid step: {}", part))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((a, b)) => (value(a)?, value(b)?),
+            // `5/15` runs from 5 to the end of the range.
+            None if part.contains('/') => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if start > end {
+            return Err(format!("Invalid range: {}", part));
+        }
+        for n in (start..=end).step_by(step as usize) {
+            bits |= 1 << n;
+        }
+    }
+    Ok(bits)
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::iso8601::Iso8601;
+
+    fn date(s: &str) -> Date {
+        Iso8601::parse(s).unwrap().date
+    }
+
+    fn next(expr: &str, after: &str) -> Option<String> {
+        Cron::parse(expr).unwrap()
+            .next_after(&date(after))
+            .map(|date| Iso8601 { date, offset: None }.to_iso8601())
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(next("* * * * *", "2023-12-31T23:59:30").unwrap(), "2024-01-01T00:00:00");
+        assert_eq!(next("*/15 * * * *", "2023-11-23T14:30:00").unwrap(), "2023-11-23T14:45:00");
+        assert_eq!(next("0 9-17/4 * * *", "2023-11-23T13:00:00").unwrap(), "2023-11-23T17:00:00");
+        assert_eq!(next("0 0 29 2 *", "2024-03-01T00:00:00").unwrap(), "2028-02-29T00:00:00");
+        assert_eq!(next("5/20 * * * *", "2023-11-23T14:46:00").unwrap(), "2023-11-23T15:05:00");
+        assert_eq!(next("@monthly", "2023-11-23T14:30:00").unwrap(), "2023-12-01T00:00:00");
+        assert_eq!(next("0 0 * * 7", "2023-11-23T14:30:00").unwrap(), "2023-11-26T00:00:00");
+        assert_eq!(next("0 0 30 FEB *", "2023-11-23T14:30:00"), None);
+    }
+
+    #[test]
+    fn test_day_of_month_or_weekday() {
+        // The 13th or any Friday.
+        let cron = Cron::parse("0 0 13 * FRI").unwrap();
+        let days: Vec<u8> = cron.occurrences(date("2023-10-01T00:00:00")).take(4).map(|d| d.day).collect();
+        assert_eq!(days, [6, 13, 20, 27]);
+        // Only Fridays when the day of the month is `*`.
+        let cron = Cron::parse("0 0 * * fri").unwrap();
+        assert_eq!(cron.next_after(&date("2023-10-13T00:00:00")).unwrap().day, 20);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for raw in ["", "* * * *", "* * * * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "* * * 13 *",
+                    "* * * * 8", "*/0 * * * *", "5-1 * * * *", "a * * * *", "* * * FOO *", "1,,2 * * * *"] {
+            assert!(Cron::parse(raw).is_err(), "{}", raw);
+        }
+        assert_eq!(Cron::parse(" @daily ").unwrap().to_string(), "@daily");
+    }
+}
@@ -1,8 +1,21 @@
+use std::error::Error;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::date::format;
+use crate::date::iso8601::{Iso8601, IsoDuration};
+use crate::date::posix::Posix;
+use crate::date::rcf3339::Rfc3339;
+use crate::date::rfc2822::Rfc2822;
+use crate::date::timezone::TimeZone;
+use crate::date::calendar::Calendar;
+
 /// A lightweight date structure representing a specific moment in time.
 /// 
 /// This struct holds basic date and time components (year, month, day, hour, minute, second)
 /// and an optional offset string.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Dates order chronologically: the fields are compared from `year` down to `second`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Date {
     pub year: i32,
     pub month: u8,
@@ -11,3 +24,1174 @@ pub struct Date {
     pub minute: u8,
     pub second: u8,
 }
+
+/// A day of the week, in ISO 8601 order (weeks start on Monday).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// Returns the English name of the day (e.g. `Thursday`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+            Weekday::Sunday => "Sunday",
+        }
+    }
+
+    /// Returns the three-letter abbreviation of the day (e.g. `Thu`).
+    pub fn short_name(&self) -> &'static str {
+        &self.name()[..3]
+    }
+
+    /// Returns the ISO day number, from 1 (Monday) to 7 (Sunday).
+    pub fn number_from_monday(&self) -> u8 {
+        *self as u8 + 1
+    }
+
+    /// Internal helper to build a `Weekday` from its ISO day number (1-7).
+    pub(crate) fn from_iso(n: u8) -> Weekday {
+        match n {
+            1 => Weekday::Monday,
+            2 => Weekday::Tuesday,
+            3 => Weekday::Wednesday,
+            4 => Weekday::Thursday,
+            5 => Weekday::Friday,
+            6 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// How a leap second (`:60`) is resolved when a date is converted to a timestamp
+/// or another representation.
+///
+/// The calendar types keep `:60` as parsed; the policy only applies on conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LeapSecond {
+    /// Treat `23:59:60` as `23:59:59`.
+    Clamp,
+    /// Treat `23:59:60` as the first second of the next minute, `00:00:00`.
+    /// This is what the infallible conversions (`to_utc`, `add`, timestamps) do.
+    #[default]
+    Carry,
+    /// Refuse to convert a date with a leap second.
+    Reject,
+}
+
+/// How adding months or years handles a day that does not exist in the target
+/// month (e.g. January 31st + 1 month).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MonthEnd {
+    /// Use the last day of the target month: Jan 31 + 1 month = Feb 28 (29).
+    #[default]
+    ClampToMonthEnd,
+    /// Carry the extra days into the next month: Jan 31 + 1 month = Mar 3 (2).
+    Overflow,
+    /// Refuse to produce a date.
+    Reject,
+}
+
+/// A calendar unit for `Date::truncate_to` and `Date::round_to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Minute,
+    Hour,
+    Day,
+    /// Weeks starting on Monday, as in ISO 8601.
+    Week,
+    Month,
+    Year,
+}
+
+/// The format recognized by `Date::parse_any`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetectedFormat {
+    /// `2023-11-23T14:30:00Z`, with an optional offset and fractional seconds.
+    Rfc3339,
+    /// ISO 8601 basic format: `20231123T143000`, with an optional offset.
+    Iso8601Basic,
+    /// `2023-11-23 14:30:00`, as written by SQL databases and many loggers.
+    SpaceSeparated,
+    /// Seconds since the epoch: `1700749800`.
+    EpochSeconds,
+    /// Milliseconds since the epoch (13 digits): `1700749800000`.
+    EpochMillis,
+    /// `Thu, 23 Nov 2023 14:30:00 +0000`, as used in email and HTTP headers.
+    Rfc2822,
+    /// `11/23/2023` or `23/11/2023`, with an optional time; see `ParseOptions::slash_dates`.
+    SlashDate,
+    /// `20231123`, a date without a time; see `ParseOptions::compact_dates`.
+    CompactDate,
+}
+
+/// Opt-in readings of legacy and ambiguous formats for `Date::parse_any_with`.
+///
+/// Everything is off by default, so the default options accept exactly what
+/// `Date::parse_any` does.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::date::date::{Date, DayOrder, ParseOptions};
+/// let opts = ParseOptions { slash_dates: Some(DayOrder::DayFirst), ..Default::default() };
+/// let (date, _) = Date::parse_any_with("03/04/2024", opts).unwrap();
+/// assert_eq!((date.month, date.day), (4, 3));
+/// assert!(Date::parse_any("03/04/2024").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ParseOptions {
+    /// Accept slash dates (`a/b/yyyy`, optionally followed by ` HH:MM[:SS]`)
+    /// with this field order. When only one order gives a valid month, as in
+    /// `23/11/2023`, that order is used instead. Defaults to `None`, which
+    /// rejects slash dates.
+    pub slash_dates: Option<DayOrder>,
+    /// Accept two-digit years in slash dates: years below the pivot are in
+    /// the 2000s, the others in the 1900s (with `Some(50)`, `49` is 2049 and
+    /// `50` is 1950). Defaults to `None`, which rejects two-digit years.
+    pub two_digit_year_pivot: Option<u8>,
+    /// Accept `YYYYMMDD` date-only strings, read as midnight, instead of as
+    /// epoch seconds. Defaults to `false`.
+    pub compact_dates: bool,
+}
+
+/// The order of the day and month in a slash date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DayOrder {
+    /// `MM/DD/YYYY`, as in the United States.
+    MonthFirst,
+    /// `DD/MM/YYYY`, as in most of Europe.
+    DayFirst,
+}
+
+/// The error returned by the checked timestamp conversions when a value falls
+/// outside the supported range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RangeError {
+    /// A timestamp, in seconds, whose date is outside `Date::MIN_YEAR..=Date::MAX_YEAR`.
+    Timestamp(i64),
+    /// A date whose timestamp in `unit` (e.g. `"milliseconds"`) does not fit in an `i64`.
+    Overflow { year: i32, unit: &'static str },
+}
+
+impl Error for RangeError {}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeError::Timestamp(ts) => write!(f, "Timestamp out of range: {}", ts),
+            RangeError::Overflow { year, unit } => write!(f, "Year {} is out of range for a timestamp in {}", year, unit),
+        }
+    }
+}
+
+pub(crate) const SECONDS_PER_DAY: i64 = 86400;
+
+impl Date {
+    /// The earliest supported year. One `i32` year is kept free at each end,
+    /// so computations that reach into the neighbouring year (ISO week years,
+    /// rounding to the next week or year) cannot overflow.
+    pub const MIN_YEAR: i32 = i32::MIN + 1;
+    /// The latest supported year.
+    pub const MAX_YEAR: i32 = i32::MAX - 1;
+
+    /// Parses `s` according to a strftime-style `pattern`, for timestamps in
+    /// custom log or CSV formats.
+    ///
+    /// The specifiers are those supported by `strftime` (e.g. `%Y %m %d %H %M %S`,
+    /// `%b` month names, `%j` day of the year, `%z` offsets, `%s` timestamps).
+    /// Numeric fields accept one digit up to their full width, and names match
+    /// case-insensitively. Fields missing from the pattern default to
+    /// 1970-01-01T00:00:00. When the pattern has `%z` the result is converted to UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `s` does not match the pattern, has trailing
+    /// characters, or describes an invalid date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::Date;
+    /// let date = Date::parse_with("%d/%m/%Y %H:%M", "23/11/2023 14:30").unwrap();
+    /// assert_eq!((date.year, date.month, date.day, date.hour), (2023, 11, 23, 14));
+    ///
+    /// let date = Date::parse_with("[%d/%b/%Y:%T %z]", "[10/Oct/2000:13:55:36 -0700]").unwrap();
+    /// assert_eq!((date.day, date.hour), (10, 20));
+    /// ```
+    pub fn parse_with(pattern: &str, s: &str) -> Result<Date, String> {
+        format::strptime(pattern, s)
+    }
+
+    /// Parses `s` in whichever common format it is written, and reports the
+    /// format found. Useful for ingesting logs from mixed sources.
+    ///
+    /// The format is picked from the shape of the string; see `DetectedFormat`
+    /// for the accepted ones. All-digit strings are epoch timestamps, read as
+    /// milliseconds when they have 13 digits. Dates with an offset are
+    /// converted to UTC; the others are returned as written.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` with the error of the detected format's parser
+    /// if `s` does not parse in that format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::{Date, DetectedFormat};
+    /// let (date, format) = Date::parse_any("2023-11-23T16:30:00+02:00").unwrap();
+    /// assert_eq!(format, DetectedFormat::Rfc3339);
+    /// assert_eq!(date.hour, 14);
+    ///
+    /// for s in ["20231123T143000Z", "2023-11-23 14:30:00", "1700749800", "1700749800000",
+    ///           "Thu, 23 Nov 2023 15:30:00 +0100"] {
+    ///     assert_eq!(Date::parse_any(s).unwrap().0, date);
+    /// }
+    /// assert_eq!(Date::parse_any("1700749800000").unwrap().1, DetectedFormat::EpochMillis);
+    /// ```
+    pub fn parse_any(s: &str) -> Result<(Date, DetectedFormat), String> {
+        let s = s.trim();
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            let ts = s.parse::<i64>().map_err(|_| format!("Timestamp out of range: {}", s))?;
+            return match digits.len() {
+                13 => Ok((Posix::from_millis(ts)?.date, DetectedFormat::EpochMillis)),
+                _ => Ok((Posix::from_timestamp(ts)?.date, DetectedFormat::EpochSeconds)),
+            };
+        }
+
+        let bytes = s.as_bytes();
+        let basic = bytes.len() > 8 && bytes[..8].iter().all(u8::is_ascii_digit) && bytes[8] == b'T';
+        match (bytes.get(4), bytes.get(10)) {
+            (Some(b'-'), Some(b' ')) if s.len() == 19 => {
+                Ok((Self::parse_with("%F %T", s)?, DetectedFormat::SpaceSeparated))
+            }
+            (Some(b'-'), Some(b'T' | b't' | b' ')) => Ok((Rfc3339::parse(s)?.to_utc().date, DetectedFormat::Rfc3339)),
+            _ if basic => Ok((Iso8601::parse(s)?.to_utc().date, DetectedFormat::Iso8601Basic)),
+            _ => Ok((Rfc2822::parse(s)?.to_utc().date, DetectedFormat::Rfc2822)),
+        }
+    }
+
+    /// Same as `parse_any`, also accepting the legacy formats enabled in `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `s` is not in a recognized format, or is an
+    /// invalid date in the format detected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::{Date, DayOrder, DetectedFormat, ParseOptions};
+    /// let opts = ParseOptions {
+    ///     slash_dates: Some(DayOrder::MonthFirst),
+    ///     two_digit_year_pivot: Some(50),
+    ///     compact_dates: true,
+    /// };
+    /// let (date, format) = Date::parse_any_with("11/23/23 14:30", opts).unwrap();
+    /// assert_eq!(format, DetectedFormat::SlashDate);
+    /// assert_eq!((date.year, date.month, date.day, date.hour), (2023, 11, 23, 14));
+    /// // 23 cannot be a month, so this is read day first.
+    /// assert_eq!(Date::parse_any_with("23/11/1999", opts).unwrap().0.day, 23);
+    ///
+    /// let (date, format) = Date::parse_any_with("20231123", opts).unwrap();
+    /// assert_eq!((format, date.day, date.hour), (DetectedFormat::CompactDate, 23, 0));
+    /// ```
+    pub fn parse_any_with(s: &str, options: ParseOptions) -> Result<(Date, DetectedFormat), String> {
+        let s = s.trim();
+        if let Some(order) = options.slash_dates
+            && s.contains('/')
+        {
+            return Ok((Self::parse_slash_date(s, order, options.two_digit_year_pivot)?, DetectedFormat::SlashDate));
+        }
+        if options.compact_dates && s.len() == 8 && s.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok((Self::parse_with("%Y%m%d", s)?, DetectedFormat::CompactDate));
+        }
+        Self::parse_any(s)
+    }
+
+    /// Applies a leap-second `policy`, returning a date whose `second` is at most 59.
+    ///
+    /// Dates without a leap second are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the date has a leap second and `policy` is
+    /// `LeapSecond::Reject`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::{Date, LeapSecond};
+    /// let date = Date::parse_with("%F %T", "2016-12-31 23:59:60").unwrap();
+    /// assert_eq!(date.resolve_leap_second(LeapSecond::Clamp).unwrap().second, 59);
+    /// assert_eq!(date.resolve_leap_second(LeapSecond::Carry).unwrap().year, 2017);
+    /// assert!(date.resolve_leap_second(LeapSecond::Reject).is_err());
+    /// ```
+    pub fn resolve_leap_second(self, policy: LeapSecond) -> Result<Date, String> {
+        if self.second < 60 {
+            return Ok(self);
+        }
+        match policy {
+            LeapSecond::Clamp => Ok(Date { second: 59, ..self }),
+            LeapSecond::Carry => Ok(Self::from_seconds(self.to_seconds())),
+            LeapSecond::Reject => Err("Leap seconds are not allowed".into()),
+        }
+    }
+
+    /// Returns the calendar duration from `earlier` to `self`.
+    ///
+    /// The result counts whole months first, then the remaining days and time,
+    /// so adding it to `earlier` with `Iso8601::add` gives back `self`. If
+    /// `earlier` is actually the later date, the two are swapped; use
+    /// [`Date::signed_duration_since`] to keep the direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let start = Iso8601::parse("2023-11-23T14:30:00").unwrap().date;
+    /// let deadline = Iso8601::parse("2024-02-29T09:00:00").unwrap().date;
+    /// assert_eq!(deadline.duration_since(&start).to_string(), "P3M5DT18H30M");
+    /// ```
+    pub fn duration_since(&self, earlier: &Date) -> IsoDuration {
+        self.signed_duration_since(earlier).1
+    }
+
+    /// Returns the calendar duration between `other` and `self` together with
+    /// its sign: `1` if `self` is later, `-1` if it is earlier, `0` if equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let now = Iso8601::parse("2024-01-01T00:00:00").unwrap().date;
+    /// let launch = Iso8601::parse("2024-01-02T06:00:00").unwrap().date;
+    /// let (sign, dur) = now.signed_duration_since(&launch);
+    /// assert_eq!((sign, dur.to_string()), (-1, "P1DT6H".to_string()));
+    /// ```
+    pub fn signed_duration_since(&self, other: &Date) -> (i8, IsoDuration) {
+        let (sign, from, to) = match self.to_seconds().cmp(&other.to_seconds()) {
+            std::cmp::Ordering::Less => (-1, *self, *other),
+            std::cmp::Ordering::Equal => return (0, IsoDuration::default()),
+            std::cmp::Ordering::Greater => (1, *other, *self),
+        };
+        let mut months = (to.year as i64 - from.year as i64) * 12 + (to.month as i64 - from.month as i64);
+        if from.add_months(months).to_seconds() > to.to_seconds() {
+            months -= 1;
+        }
+        let rest = to.to_seconds() - from.add_months(months).to_seconds();
+        let dur = IsoDuration {
+            years: (months / 12) as u32,
+            months: (months % 12) as u32,
+            days: (rest / SECONDS_PER_DAY) as u32,
+            hours: (rest % SECONDS_PER_DAY / 3600) as u32,
+            minutes: (rest % 3600 / 60) as u32,
+            seconds: (rest % 60) as u32,
+            nanos: 0,
+        };
+        (sign, dur)
+    }
+
+    /// Returns the exact number of seconds elapsed from `self` to `other`,
+    /// negative if `other` is earlier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let a = Iso8601::parse("2024-02-28T23:00:00").unwrap().date;
+    /// let b = Iso8601::parse("2024-03-01T00:00:00").unwrap().date;
+    /// assert_eq!(a.seconds_between(&b), 25 * 3600);
+    /// assert_eq!(b.seconds_between(&a), -25 * 3600);
+    /// ```
+    pub fn seconds_between(&self, other: &Date) -> i64 {
+        other.to_seconds() - self.to_seconds()
+    }
+
+    /// Returns the Unix timestamp of the date read as UTC: seconds since
+    /// 1970-01-01T00:00:00, negative before it. A leap second (`:60`) counts
+    /// as the first second of the next minute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// assert_eq!(Iso8601::parse("2023-11-23T14:30:00").unwrap().date.to_unix(), 1_700_749_800);
+    /// assert_eq!(Iso8601::parse("1969-12-31T23:59:59").unwrap().date.to_unix(), -1);
+    /// ```
+    pub fn to_unix(&self) -> i64 {
+        self.to_seconds()
+    }
+
+    /// Builds the UTC date of a Unix timestamp, the inverse of `to_unix`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RangeError::Timestamp` if the year falls outside
+    /// `Date::MIN_YEAR..=Date::MAX_YEAR`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::{Date, RangeError};
+    /// assert_eq!(Date::from_unix(1_700_749_800).unwrap().year, 2023);
+    /// assert_eq!(Date::from_unix(i64::MAX), Err(RangeError::Timestamp(i64::MAX)));
+    /// ```
+    pub fn from_unix(secs: i64) -> Result<Date, RangeError> {
+        let min = Self::days_from_civil(Self::MIN_YEAR, 1, 1) * SECONDS_PER_DAY;
+        let max = (Self::days_from_civil(Self::MAX_YEAR, 12, 31) + 1) * SECONDS_PER_DAY;
+        if !(min..max).contains(&secs) {
+            return Err(RangeError::Timestamp(secs));
+        }
+        Ok(Self::from_seconds(secs))
+    }
+
+    /// Returns `true` if `self` is strictly earlier than `other`.
+    pub fn is_before(&self, other: &Date) -> bool {
+        self < other
+    }
+
+    /// Returns `true` if `self` is strictly later than `other`.
+    pub fn is_after(&self, other: &Date) -> bool {
+        self > other
+    }
+
+    /// Returns `true` if `self` lies between `start` and `end`, both included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let start = Iso8601::parse("2024-01-01T00:00:00").unwrap().date;
+    /// let end = Iso8601::parse("2024-12-31T23:59:59").unwrap().date;
+    /// let date = Iso8601::parse("2024-06-15T12:00:00").unwrap().date;
+    /// assert!(date.is_between(&start, &end));
+    /// assert!(end.is_between(&start, &end));
+    /// assert!(!start.is_between(&date, &end));
+    /// ```
+    pub fn is_between(&self, start: &Date, end: &Date) -> bool {
+        start <= self && self <= end
+    }
+
+    /// Returns the ISO 8601 week-numbering year and week number (1-53).
+    ///
+    /// Weeks start on Monday, and week 1 is the week containing January 4th,
+    /// so the first days of January can belong to the last week of the
+    /// previous year and the last days of December to week 1 of the next.
+    /// The same values are written by the `%G` and `%V` strftime specifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let date = Iso8601::parse("2021-01-03T00:00:00").unwrap();
+    /// assert_eq!(date.date.iso_week(), (2020, 53));
+    /// assert_eq!(date.strftime("%G-W%V"), "2020-W53");
+    /// ```
+    pub fn iso_week(&self) -> (i32, u8) {
+        let (year, week, _) = self.iso_week_date();
+        (year, week)
+    }
+
+    /// Returns `true` if the date falls in a leap year (366 days, with February 29th).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// assert!(Iso8601::parse("2024-06-01T00:00:00").unwrap().date.is_leap_year());
+    /// assert!(!Iso8601::parse("2100-06-01T00:00:00").unwrap().date.is_leap_year());
+    /// ```
+    pub fn is_leap_year(&self) -> bool {
+        Self::is_leap(self.year)
+    }
+
+    /// Returns the number of days (28-31) in the month of the date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// assert_eq!(Iso8601::parse("2024-02-10T00:00:00").unwrap().date.days_in_month(), 29);
+    /// assert_eq!(Iso8601::parse("2023-02-10T00:00:00").unwrap().date.days_in_month(), 28);
+    /// ```
+    pub fn days_in_month(&self) -> u8 {
+        Self::month_length(self.year, self.month)
+    }
+
+    /// Returns the day of the year, from 1 (January 1st) to 365 or 366.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let date = Iso8601::parse("2024-03-01T00:00:00").unwrap().date;
+    /// assert_eq!(date.day_of_year(), 61);
+    /// ```
+    pub fn day_of_year(&self) -> u16 {
+        (Self::days_from_civil(self.year, self.month, self.day) - Self::days_from_civil(self.year, 1, 1) + 1) as u16
+    }
+
+    /// Returns the day of the week.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::Weekday;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let date = Iso8601::parse("2023-11-23T14:30:00").unwrap().date;
+    /// assert_eq!(date.weekday(), Weekday::Thursday);
+    /// assert!(!date.is_weekend());
+    /// ```
+    pub fn weekday(&self) -> Weekday {
+        Weekday::from_iso(Self::iso_weekday_from_days(Self::days_from_civil(self.year, self.month, self.day)))
+    }
+
+    /// Returns midnight of the first day of the week containing the date,
+    /// for weeks starting on `first_day`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::Weekday;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let date = Iso8601::parse("2023-11-23T14:30:00").unwrap().date; // Thursday
+    /// let monday = Iso8601 { date: date.start_of_week(Weekday::Monday), offset: None };
+    /// assert_eq!(monday.to_iso8601(), "2023-11-20T00:00:00");
+    /// assert_eq!(date.start_of_week(Weekday::Sunday).day, 19);
+    /// assert_eq!(date.start_of_week(Weekday::Thursday).day, 23);
+    /// ```
+    pub fn start_of_week(&self, first_day: Weekday) -> Date {
+        let days = Self::days_from_civil(self.year, self.month, self.day);
+        let back = (self.weekday().number_from_monday() as i64 - first_day.number_from_monday() as i64).rem_euclid(7);
+        let (year, month, day) = Self::civil_from_days(days - back);
+        Date { year, month, day, hour: 0, minute: 0, second: 0 }
+    }
+
+    /// Returns the start of the `unit` containing the date, e.g. midnight for
+    /// `Unit::Day` or the first of the month for `Unit::Month`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::Unit;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let date = Iso8601::parse("2023-11-23T14:35:10").unwrap().date;
+    /// let truncate = |unit| Iso8601 { date: date.truncate_to(unit), offset: None }.to_iso8601();
+    /// assert_eq!(truncate(Unit::Hour), "2023-11-23T14:00:00");
+    /// assert_eq!(truncate(Unit::Week), "2023-11-20T00:00:00");
+    /// assert_eq!(truncate(Unit::Year), "2023-01-01T00:00:00");
+    /// ```
+    pub fn truncate_to(&self, unit: Unit) -> Date {
+        let midnight = Date { hour: 0, minute: 0, second: 0, ..*self };
+        match unit {
+            Unit::Minute => Date { second: 0, ..*self },
+            Unit::Hour => Date { minute: 0, second: 0, ..*self },
+            Unit::Day => midnight,
+            Unit::Week => self.start_of_week(Weekday::Monday),
+            Unit::Month => Date { day: 1, ..midnight },
+            Unit::Year => Date { month: 1, day: 1, ..midnight },
+        }
+    }
+
+    /// Returns the start of the nearest `unit`: the start of the one
+    /// containing the date, or of the next one from its midpoint on.
+    ///
+    /// Months and years are split at their actual midpoint, so June 16th
+    /// 00:00:00 rounds to July but June 15th 23:59:59 to June.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::Unit;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let date = Iso8601::parse("2023-12-31T14:30:00").unwrap().date;
+    /// let round = |unit| Iso8601 { date: date.round_to(unit), offset: None }.to_iso8601();
+    /// assert_eq!(round(Unit::Hour), "2023-12-31T15:00:00");
+    /// assert_eq!(round(Unit::Day), "2024-01-01T00:00:00");
+    /// assert_eq!(round(Unit::Month), "2024-01-01T00:00:00");
+    /// ```
+    pub fn round_to(&self, unit: Unit) -> Date {
+        let start = self.truncate_to(unit);
+        let next = match unit {
+            Unit::Minute => Self::from_seconds(start.to_seconds() + 60),
+            Unit::Hour => Self::from_seconds(start.to_seconds() + 3600),
+            Unit::Day => Self::from_seconds(start.to_seconds() + SECONDS_PER_DAY),
+            Unit::Week => Self::from_seconds(start.to_seconds() + 7 * SECONDS_PER_DAY),
+            Unit::Month => start.add_months(1),
+            Unit::Year => start.add_months(12),
+        };
+        let (elapsed, length) = (self.to_seconds() - start.to_seconds(), next.to_seconds() - start.to_seconds());
+        if elapsed * 2 >= length { next } else { start }
+    }
+
+    /// Returns `true` if the date falls on a Saturday or Sunday.
+    pub fn is_weekend(&self) -> bool {
+        matches!(self.weekday(), Weekday::Saturday | Weekday::Sunday)
+    }
+
+    /// Converts this UTC date to the local time of `tz`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// use stdt::date::timezone::TimeZone;
+    /// let tz = TimeZone::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+    /// let utc = Iso8601::parse("2024-07-04T16:00:00Z").unwrap().date;
+    /// assert_eq!(utc.to_zone(&tz).hour, 12);
+    /// ```
+    pub fn to_zone(&self, tz: &TimeZone) -> Date {
+        let utc = self.to_seconds();
+        Date::from_seconds(utc + tz.offset_at_seconds(utc).total_seconds())
+    }
+
+    /// Converts the local time `local` of `tz` to UTC.
+    ///
+    /// When clocks go back, a repeated local time resolves to the earlier
+    /// instant. When clocks go forward, a skipped local time is read with the
+    /// offset before the change (02:30 becomes 03:30 local).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::Date;
+    /// use stdt::date::iso8601::Iso8601;
+    /// use stdt::date::timezone::TimeZone;
+    /// let tz = TimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    /// let local = Iso8601::parse("2024-07-15T14:00:00").unwrap().date;
+    /// assert_eq!(Date::from_zone(&local, &tz).hour, 12);
+    /// ```
+    pub fn from_zone(local: &Date, tz: &TimeZone) -> Date {
+        Date::from_seconds(tz.utc_seconds(local.to_seconds()))
+    }
+
+    /// Moves the date by `n` business days of `calendar` (back when negative),
+    /// keeping the time of day. Weekend days and holidays are skipped, so
+    /// Friday + 1 business day is Monday. With `n` = 0 the date is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::calendar::Calendar;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let calendar = Calendar::new();
+    /// let friday = Iso8601::parse("2023-11-24T17:00:00").unwrap().date;
+    /// let due = friday.add_business_days(3, &calendar);
+    /// assert_eq!(Iso8601 { date: due, offset: None }.to_iso8601(), "2023-11-29T17:00:00");
+    /// assert_eq!(due.add_business_days(-3, &calendar), friday);
+    /// ```
+    pub fn add_business_days(&self, n: i64, calendar: &Calendar) -> Date {
+        let mut days = Self::days_from_civil(self.year, self.month, self.day);
+        let step = n.signum();
+        let mut remaining = n.abs();
+        while remaining > 0 {
+            days += step;
+            if calendar.is_business_day_number(days) {
+                remaining -= 1;
+            }
+        }
+        let (year, month, day) = Self::civil_from_days(days);
+        Date { year, month, day, ..*self }
+    }
+
+    /// Returns the number of business days of `calendar` passed moving from
+    /// `self` to `other`: those after `self`'s day up to and including
+    /// `other`'s, or, negated, those before `self`'s day down to and including
+    /// `other`'s. The time of day is ignored.
+    ///
+    /// This is the inverse of `add_business_days`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::calendar::Calendar;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let calendar = Calendar::new();
+    /// let friday = Iso8601::parse("2023-11-24T17:00:00").unwrap().date;
+    /// let next_friday = Iso8601::parse("2023-12-01T09:00:00").unwrap().date;
+    /// assert_eq!(friday.business_days_between(&next_friday, &calendar), 5);
+    /// assert_eq!(next_friday.business_days_between(&friday, &calendar), -5);
+    /// ```
+    pub fn business_days_between(&self, other: &Date, calendar: &Calendar) -> i64 {
+        let from = Self::days_from_civil(self.year, self.month, self.day);
+        let to = Self::days_from_civil(other.year, other.month, other.day);
+        if from <= to {
+            calendar.count_business_days(from + 1, to)
+        } else {
+            -calendar.count_business_days(to, from - 1)
+        }
+    }
+
+    /// Internal helper reading `a/b/year[ HH:MM[:SS]]` for `parse_any_with`.
+    fn parse_slash_date(s: &str, order: DayOrder, pivot: Option<u8>) -> Result<Date, String> {
+        let (day_part, time_part) = s.split_once(' ').unwrap_or((s, ""));
+        let parts: Vec<&str> = day_part.split('/').collect();
+        if parts.len() != 3 {
+            return Err(format!("Expected a date as a/b/year: {}", s));
+        }
+        let parse_num = |str_slice: &str, widths: std::ops::RangeInclusive<usize>| str_slice.parse::<u32>()
+            .ok()
+            .filter(|_| widths.contains(&str_slice.len()) && str_slice.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(|| format!("Invalid number in date: {}", s));
+
+        let (a, b) = (parse_num(parts[0], 1..=2)? as u8, parse_num(parts[1], 1..=2)? as u8);
+        let year = match (parts[2].len(), pivot) {
+            (4, _) => parse_num(parts[2], 4..=4)? as i32,
+            (2, Some(pivot)) => match parse_num(parts[2], 2..=2)? as i32 {
+                y if y < pivot as i32 => 2000 + y,
+                y => 1900 + y,
+            },
+            (2, None) => return Err(format!("Two-digit years are not enabled: {}", s)),
+            _ => return Err(format!("Invalid year in date: {}", s)),
+        };
+        let (month, day) = match order {
+            _ if a > 12 && b <= 12 => (b, a),
+            _ if b > 12 && a <= 12 => (a, b),
+            DayOrder::MonthFirst => (a, b),
+            DayOrder::DayFirst => (b, a),
+        };
+
+        let time = match time_part.len() {
+            0 => Date::from_seconds(0),
+            1..=5 => Self::parse_with("%H:%M", time_part)?,
+            _ => Self::parse_with("%T", time_part)?,
+        };
+        let date = Date { year, month, day, ..time };
+        if !date.is_valid() {
+            return Err(format!("Semantically invalid date: {}", s));
+        }
+        Ok(date)
+    }
+
+    // --- Internal Calendar Helpers ---
+
+    pub(crate) fn is_leap(y: i32) -> bool {
+        (y % 4 == 0 && y % 100 != 0) || (y % 400 == 0)
+    }
+
+    pub(crate) fn month_length(y: i32, m: u8) -> u8 {
+        match m {
+            4 | 6 | 9 | 11 => 30,
+            2 => if Self::is_leap(y) { 29 } else { 28 },
+            _ => 31,
+        }
+    }
+
+    /// Returns `true` if every field is in range, allowing a leap second (`:60`).
+    pub(crate) fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && (1..=Self::month_length(self.year, self.month)).contains(&self.day)
+            && self.hour <= 23 && self.minute <= 59 && self.second <= 60
+    }
+
+    /// Number of days from 1970-01-01 to the given calendar day (negative before it).
+    ///
+    /// Constant time, using Howard Hinnant's `days_from_civil` algorithm over
+    /// 400-year eras; valid for every `i32` year.
+    pub(crate) fn days_from_civil(y: i32, m: u8, d: u8) -> i64 {
+        // Shift the year to start in March so the leap day is the last day.
+        let y = if m <= 2 { y as i64 - 1 } else { y as i64 };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let mp = (m as i64 + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// Inverse of `days_from_civil`.
+    pub(crate) fn civil_from_days(days: i64) -> (i32, u8, u8) {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let d = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let m = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+        (y as i32, m, d)
+    }
+
+    /// Seconds since 1970-01-01T00:00:00 (negative before it).
+    pub(crate) fn to_seconds(self) -> i64 {
+        Self::days_from_civil(self.year, self.month, self.day) * SECONDS_PER_DAY
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64
+    }
+
+    /// Inverse of `to_seconds`.
+    pub(crate) fn from_seconds(secs: i64) -> Date {
+        let (year, month, day) = Self::civil_from_days(secs.div_euclid(SECONDS_PER_DAY));
+        let time = secs.rem_euclid(SECONDS_PER_DAY);
+        Date {
+            year, month, day,
+            hour: (time / 3600) as u8,
+            minute: (time % 3600 / 60) as u8,
+            second: (time % 60) as u8,
+        }
+    }
+
+    /// ISO weekday (Monday = 1 ... Sunday = 7) of a day counted from 1970-01-01,
+    /// which was a Thursday.
+    pub(crate) fn iso_weekday_from_days(days: i64) -> u8 {
+        ((days + 3).rem_euclid(7) + 1) as u8
+    }
+
+    /// Day number of the Monday starting ISO week 1 of `y`, the week that contains January 4th.
+    pub(crate) fn iso_week1_monday(y: i32) -> i64 {
+        let jan4 = Self::days_from_civil(y, 1, 4);
+        jan4 - (Self::iso_weekday_from_days(jan4) as i64 - 1)
+    }
+
+    /// Number of ISO weeks (52 or 53) in the ISO week-numbering year `y`.
+    pub(crate) fn iso_weeks_in_year(y: i32) -> u8 {
+        ((Self::iso_week1_monday(y + 1) - Self::iso_week1_monday(y)) / 7) as u8
+    }
+
+    /// Returns the ISO week-numbering year, week (1-53), and weekday (1-7) of the date.
+    /// Early January days can belong to the last week of the previous year, and
+    /// late December days to week 1 of the next.
+    pub(crate) fn iso_week_date(self) -> (i32, u8, u8) {
+        let days = Self::days_from_civil(self.year, self.month, self.day);
+        let year = if days < Self::iso_week1_monday(self.year) {
+            self.year - 1
+        } else if days >= Self::iso_week1_monday(self.year + 1) {
+            self.year + 1
+        } else {
+            self.year
+        };
+        let week = (days - Self::iso_week1_monday(year)) / 7 + 1;
+        (year, week as u8, Self::iso_weekday_from_days(days))
+    }
+
+    /// Reads the current UTC time from the system clock.
+    pub(crate) fn now_utc() -> Date {
+        let secs = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs() as i64,
+            // The clock is set before 1970
+            Err(e) => -(e.duration().as_secs_f64().ceil() as i64),
+        };
+        Self::from_seconds(secs)
+    }
+
+    /// Moves the date by `months` calendar months, clamping the day to the
+    /// length of the target month (Jan 31 + 1 month = Feb 28/29).
+    pub(crate) fn add_months(self, months: i64) -> Date {
+        let total = self.year as i64 * 12 + (self.month as i64 - 1) + months;
+        let year = total.div_euclid(12) as i32;
+        let month = (total.rem_euclid(12) + 1) as u8;
+        let day = self.day.min(Self::month_length(year, month));
+        Date { year, month, day, ..self }
+    }
+
+    /// Moves the date by `months` calendar months, resolving a day past the
+    /// end of the target month with `policy`.
+    pub(crate) fn add_months_with(self, months: i64, policy: MonthEnd) -> Result<Date, String> {
+        let clamped = self.add_months(months);
+        if clamped.day == self.day {
+            return Ok(clamped);
+        }
+        match policy {
+            MonthEnd::ClampToMonthEnd => Ok(clamped),
+            MonthEnd::Overflow => {
+                let (year, month, day) = Self::civil_from_days(
+                    Self::days_from_civil(clamped.year, clamped.month, clamped.day) + (self.day - clamped.day) as i64,
+                );
+                Ok(Date { year, month, day, ..self })
+            }
+            MonthEnd::Reject => Err(format!(
+                "Day {} does not exist in {:04}-{:02}", self.day, clamped.year, clamped.month
+            )),
+        }
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_leap_second() {
+        let leap = Date { year: 2016, month: 12, day: 31, hour: 23, minute: 59, second: 60 };
+        let next = Date { year: 2017, month: 1, day: 1, hour: 0, minute: 0, second: 0 };
+        assert_eq!(leap.resolve_leap_second(LeapSecond::Carry), Ok(next));
+        assert_eq!(leap.resolve_leap_second(LeapSecond::Clamp), Ok(Date { second: 59, ..leap }));
+        assert!(leap.resolve_leap_second(LeapSecond::Reject).is_err());
+        assert_eq!(next.resolve_leap_second(LeapSecond::Reject), Ok(next));
+    }
+
+    #[test]
+    fn test_parse_any() {
+        let date = Date { year: 1969, month: 12, day: 31, hour: 23, minute: 59, second: 58 };
+        let cases = [
+            ("1969-12-31t23:59:58.5z", DetectedFormat::Rfc3339),
+            ("1969-12-31 22:59:58-01:00", DetectedFormat::Rfc3339),
+            ("19700101T005958+0100", DetectedFormat::Iso8601Basic),
+            (" 1969-12-31 23:59:58 ", DetectedFormat::SpaceSeparated),
+            ("-2", DetectedFormat::EpochSeconds),
+            ("-0000000002000", DetectedFormat::EpochMillis),
+            ("31 Dec 69 23:59:58 GMT", DetectedFormat::Rfc2822),
+        ];
+        for (s, format) in cases {
+            assert_eq!(Date::parse_any(s), Ok((date, format)), "{}", s);
+        }
+
+        for s in ["", "-", "2023-13-01T00:00:00Z", "2023-11-23 14:30", "20231123T25", "yesterday",
+                  "99999999999999999999"] {
+            assert!(Date::parse_any(s).is_err(), "{}", s);
+        }
+        // Multibyte characters where digits are expected are errors, not panics.
+        for s in ["2023-11-23T14:3é00Z", "20231123T1é234", "2023112éT143000", "2023-11-2é 14:30:00",
+                  "é023-11-23T14:30:00Z", "Thu, 23 Nov 2023 14:30:0é +0000"] {
+            assert!(Date::parse_any(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_any_with_legacy_formats() {
+        let us = ParseOptions { slash_dates: Some(DayOrder::MonthFirst), ..Default::default() };
+        let eu = ParseOptions { slash_dates: Some(DayOrder::DayFirst), two_digit_year_pivot: Some(30), compact_dates: true };
+        let date = |s, opts| Date::parse_any_with(s, opts).map(|(d, _)| (d.year, d.month, d.day, d.hour, d.minute, d.second));
+
+        assert_eq!(date("3/4/2024", us), Ok((2024, 3, 4, 0, 0, 0)));
+        assert_eq!(date("3/4/2024", eu), Ok((2024, 4, 3, 0, 0, 0)));
+        assert_eq!(date("12/31/2024 23:59:59", eu), Ok((2024, 12, 31, 23, 59, 59)));
+        assert_eq!(date("31/12/29 8:05", eu), Ok((2029, 12, 31, 8, 5, 0)));
+        assert_eq!(date("31/12/30", eu), Ok((1930, 12, 31, 0, 0, 0)));
+        assert_eq!(date("19991231", eu), Ok((1999, 12, 31, 0, 0, 0)));
+        assert_eq!(Date::parse_any_with("19991231", us).unwrap().1, DetectedFormat::EpochSeconds);
+        assert_eq!(Date::parse_any_with("2023-11-23T14:30:00Z", eu).unwrap().1, DetectedFormat::Rfc3339);
+
+        for (s, opts) in [("31/12/29", us), ("13/13/2024", eu), ("2/30/2024", us), ("1/2/3/2024", us),
+                          ("1/2/024", eu), ("1/2/2024 25:00", eu), ("1/2/2024 noon", us), ("19991331", eu)] {
+            assert!(Date::parse_any_with(s, opts).is_err(), "{}", s);
+        }
+        assert!(Date::parse_any_with("3/4/2024", ParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_unix_range() {
+        let first = Date { year: Date::MIN_YEAR, month: 1, day: 1, hour: 0, minute: 0, second: 0 };
+        let last = Date { year: Date::MAX_YEAR, month: 12, day: 31, hour: 23, minute: 59, second: 59 };
+        for date in [first, last] {
+            assert_eq!(Date::from_unix(date.to_unix()), Ok(date));
+        }
+        for ts in [first.to_unix() - 1, last.to_unix() + 1, i64::MIN, i64::MAX] {
+            assert_eq!(Date::from_unix(ts), Err(RangeError::Timestamp(ts)));
+        }
+    }
+
+    #[test]
+    fn test_year_range_extremes() {
+        let first = Date { year: Date::MIN_YEAR, month: 1, day: 1, hour: 0, minute: 0, second: 0 };
+        let last = Date { year: Date::MAX_YEAR, month: 12, day: 31, hour: 23, minute: 59, second: 59 };
+        // The ISO weeks at both ends reach into the neighbouring year.
+        assert_eq!(first.iso_week(), (Date::MIN_YEAR, 1));
+        assert_eq!(last.iso_week(), (i32::MAX, 1));
+        assert_eq!(first.truncate_to(Unit::Week), Date { year: i32::MIN, month: 12, day: 29, ..first });
+        assert_eq!(last.round_to(Unit::Year), Date { year: i32::MAX, month: 1, day: 1, ..first });
+        assert!(Date::parse_with("%s", &i64::MAX.to_string()).is_err());
+        assert!(Date::parse_with("%s", &(last.to_unix() + 1).to_string()).is_err());
+        assert_eq!(Date::parse_with("%s", &first.to_unix().to_string()), Ok(first));
+    }
+
+    #[test]
+    fn test_civil_days_round_trip() {
+        assert_eq!(Date::days_from_civil(1970, 1, 1), 0);
+        assert_eq!(Date::days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(Date::days_from_civil(1969, 12, 31), -1);
+        let mut previous = Date::civil_from_days(-800_001);
+        for days in -800_000..800_000 {
+            let (y, m, d) = Date::civil_from_days(days);
+            assert!((1..=Date::month_length(y, m)).contains(&d));
+            assert_eq!(Date::days_from_civil(y, m, d), days);
+            // Each day follows the previous one in the calendar.
+            let expected = match previous {
+                (py, 12, 31) => (py + 1, 1, 1),
+                (py, pm, pd) if pd == Date::month_length(py, pm) => (py, pm + 1, 1),
+                (py, pm, pd) => (py, pm, pd + 1),
+            };
+            assert_eq!((y, m, d), expected);
+            previous = (y, m, d);
+        }
+        for (y, m, d) in [(i32::MIN, 1, 1), (i32::MAX, 12, 31), (-4, 2, 29), (0, 2, 29)] {
+            assert_eq!(Date::civil_from_days(Date::days_from_civil(y, m, d)), (y, m, d));
+        }
+    }
+
+    #[test]
+    fn test_duration_since_round_trips_through_add() {
+        use crate::date::iso8601::Iso8601;
+        let dates = [
+            "2020-02-29T12:00:00", "2021-01-31T00:00:00", "2021-02-28T23:59:59",
+            "2021-03-01T00:00:00", "2023-12-31T08:15:00", "2024-06-15T06:00:30",
+        ];
+        for a in dates {
+            for b in dates {
+                let (a, b) = (Iso8601::parse(a).unwrap(), Iso8601::parse(b).unwrap());
+                let (sign, dur) = b.date.signed_duration_since(&a.date);
+                assert_eq!(sign as i64, a.date.seconds_between(&b.date).signum());
+                match sign {
+                    -1 => assert_eq!(b.add(&dur), a),
+                    _ => assert_eq!(a.add(&dur), b),
+                }
+                assert_eq!(b.date.duration_since(&a.date), a.date.duration_since(&b.date));
+            }
+        }
+        let jan31 = Iso8601::parse("2021-01-31T00:00:00").unwrap().date;
+        let feb28 = Iso8601::parse("2021-02-28T00:00:00").unwrap().date;
+        assert_eq!(feb28.duration_since(&jan31), IsoDuration { months: 1, ..Default::default() });
+    }
+
+    #[test]
+    fn test_iso_week() {
+        let date = |year, month, day| Date { year, month, day, hour: 0, minute: 0, second: 0 };
+        assert_eq!(date(2023, 11, 23).iso_week_date(), (2023, 47, 4));
+        assert_eq!(date(2021, 1, 3).iso_week_date(), (2020, 53, 7));
+        assert_eq!(date(2024, 12, 30).iso_week_date(), (2025, 1, 1));
+        // January 1st in each weekday position.
+        assert_eq!(date(2018, 1, 1).iso_week(), (2018, 1)); // Monday
+        assert_eq!(date(2020, 1, 1).iso_week(), (2020, 1)); // Wednesday
+        assert_eq!(date(2015, 1, 1).iso_week(), (2015, 1)); // Thursday
+        assert_eq!(date(2016, 1, 1).iso_week(), (2015, 53)); // Friday
+        assert_eq!(date(2022, 1, 1).iso_week(), (2021, 52)); // Saturday
+        assert_eq!(date(2023, 1, 1).iso_week(), (2022, 52)); // Sunday
+        assert_eq!(date(2019, 12, 30).iso_week(), (2020, 1));
+        assert_eq!(date(2020, 12, 31).iso_week(), (2020, 53));
+        assert_eq!(Date::iso_weeks_in_year(2020), 53);
+        assert_eq!(Date::iso_weeks_in_year(2023), 52);
+    }
+
+    #[test]
+    fn test_weekday() {
+        let date = |year, month, day| Date { year, month, day, hour: 0, minute: 0, second: 0 };
+        assert_eq!(date(1970, 1, 1).weekday(), Weekday::Thursday);
+        assert_eq!(date(2000, 2, 29).weekday(), Weekday::Tuesday);
+        assert_eq!(date(1900, 3, 1).weekday(), Weekday::Thursday);
+        assert_eq!(date(2024, 6, 15).weekday().short_name(), "Sat");
+        assert!(date(2024, 6, 16).is_weekend());
+        assert!(!date(2024, 6, 17).is_weekend());
+        assert_eq!(Weekday::Sunday.number_from_monday(), 7);
+    }
+
+    #[test]
+    fn test_chronological_ordering() {
+        use std::collections::HashSet;
+        let date = |year, month, day, hour| Date { year, month, day, hour, minute: 0, second: 0 };
+        let mut dates = vec![date(2024, 1, 2, 0), date(2023, 12, 31, 23), date(2024, 1, 1, 5), date(2024, 1, 1, 4)];
+        dates.sort();
+        assert_eq!(dates, [date(2023, 12, 31, 23), date(2024, 1, 1, 4), date(2024, 1, 1, 5), date(2024, 1, 2, 0)]);
+        assert!(dates[0].is_before(&dates[1]));
+        assert!(dates[3].is_after(&dates[2]));
+        assert!(!dates[1].is_after(&dates[1]));
+        assert!(dates[1].is_between(&dates[1], &dates[1]));
+        assert!(!dates[0].is_between(&dates[1], &dates[3]));
+
+        let unique: HashSet<Date> = dates.iter().chain(dates.iter()).copied().collect();
+        assert_eq!(unique.len(), 4);
+    }
+
+    #[test]
+    fn test_calendar_helpers() {
+        let date = Date { year: 2024, month: 3, day: 2, hour: 8, minute: 0, second: 0 }; // Saturday
+        assert!(date.is_leap_year());
+        assert_eq!(date.days_in_month(), 31);
+        assert_eq!(date.day_of_year(), 62);
+        let midnight = |month, day| Date { year: 2024, month, day, hour: 0, minute: 0, second: 0 };
+        assert_eq!(date.start_of_week(Weekday::Monday), midnight(2, 26));
+        assert_eq!(date.start_of_week(Weekday::Saturday), midnight(3, 2));
+        assert_eq!(date.start_of_week(Weekday::Sunday), midnight(2, 25));
+        assert_eq!(Date { year: 2023, ..date }.days_in_month(), 31);
+        assert!(!Date { year: 1900, ..date }.is_leap_year());
+        assert!(!Date { day: 30, month: 2, ..date }.is_valid());
+        assert!(Date { second: 60, ..date }.is_valid());
+    }
+
+    #[test]
+    fn test_truncate_and_round() {
+        let date = Date { year: 2024, month: 2, day: 29, hour: 11, minute: 59, second: 30 };
+        assert_eq!(date.truncate_to(Unit::Minute), Date { second: 0, ..date });
+        assert_eq!(date.round_to(Unit::Minute), Date { hour: 12, minute: 0, second: 0, ..date });
+        assert_eq!(date.round_to(Unit::Hour), Date { hour: 12, minute: 0, second: 0, ..date });
+        assert_eq!(date.round_to(Unit::Day), Date { hour: 0, minute: 0, second: 0, ..date });
+        assert_eq!(date.round_to(Unit::Week), Date { month: 2, day: 26, hour: 0, minute: 0, second: 0, ..date });
+        assert_eq!(date.round_to(Unit::Month), Date { month: 3, day: 1, hour: 0, minute: 0, second: 0, ..date });
+        assert_eq!(date.round_to(Unit::Year), Date { month: 1, day: 1, hour: 0, minute: 0, second: 0, ..date });
+
+        // Halfway rounds up; a leap year's midpoint is July 2nd at midnight.
+        let midpoint = Date { year: 2024, month: 7, day: 2, hour: 0, minute: 0, second: 0 };
+        assert_eq!(midpoint.round_to(Unit::Year).year, 2025);
+        assert_eq!(Date { day: 1, hour: 23, minute: 59, second: 59, ..midpoint }.round_to(Unit::Year).year, 2024);
+        for unit in [Unit::Minute, Unit::Hour, Unit::Day, Unit::Week, Unit::Month, Unit::Year] {
+            let start = date.truncate_to(unit);
+            assert_eq!(start.truncate_to(unit), start);
+            assert_eq!(start.round_to(unit), start);
+        }
+    }
+
+    #[test]
+    fn test_business_days() {
+        use crate::date::calendar::Calendar;
+        let date = |month, day| Date { year: 2023, month, day, hour: 9, minute: 30, second: 0 };
+        let calendar = Calendar::new().with_holidays([date(12, 25), date(12, 26)]);
+        // Friday before Christmas: the next business day is Wednesday.
+        assert_eq!(date(12, 22).add_business_days(1, &calendar), date(12, 27));
+        assert_eq!(date(12, 27).add_business_days(-1, &calendar), date(12, 22));
+        // Starting on a holiday.
+        assert_eq!(date(12, 25).add_business_days(1, &calendar), date(12, 27));
+        assert_eq!(date(12, 25).add_business_days(0, &calendar), date(12, 25));
+        assert_eq!(date(12, 22).business_days_between(&date(12, 27), &calendar), 1);
+        assert_eq!(date(12, 23).business_days_between(&date(12, 24), &calendar), 0);
+
+        for start in 1..=30 {
+            for n in -40..=40 {
+                let end = date(11, start).add_business_days(n, &calendar);
+                assert_eq!(date(11, start).business_days_between(&end, &calendar), n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_months_clamps_day() {
+        let date = Date { year: 2024, month: 1, day: 31, hour: 8, minute: 0, second: 0 };
+        assert_eq!(date.add_months(1), Date { month: 2, day: 29, ..date });
+        assert_eq!(date.add_months(13), Date { year: 2025, month: 2, day: 28, ..date });
+        assert_eq!(date.add_months(-2), Date { year: 2023, month: 11, day: 30, ..date });
+    }
+
+    #[test]
+    fn test_add_months_with_policies() {
+        let date = Date { year: 2024, month: 1, day: 31, hour: 8, minute: 0, second: 0 };
+        assert_eq!(date.add_months_with(1, MonthEnd::ClampToMonthEnd), Ok(date.add_months(1)));
+        assert_eq!(date.add_months_with(1, MonthEnd::Overflow), Ok(Date { month: 3, day: 2, ..date }));
+        assert_eq!(date.add_months_with(13, MonthEnd::Overflow), Ok(Date { year: 2025, month: 3, day: 3, ..date }));
+        assert_eq!(date.add_months_with(-1, MonthEnd::Overflow), Ok(Date { year: 2023, month: 12, ..date }));
+        assert!(date.add_months_with(3, MonthEnd::Reject).unwrap_err().contains("2024-04"));
+        assert_eq!(date.add_months_with(2, MonthEnd::Reject), Ok(Date { month: 3, ..date }));
+    }
+}
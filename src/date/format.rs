@@ -0,0 +1,399 @@
+use std::fmt::Write;
+use crate::date::date::{Date, Weekday};
+use crate::date::rcf3339::UtcOffset;
+
+/// English month names, January first.
+pub(crate) const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Formats `date` (local time at `offset`) according to a strftime-style `pattern`.
+///
+/// # Supported Specifiers
+///
+/// * `%Y` = Year (2023), `%y` = Short Year (23), `%C` = Century (20)
+/// * `%m` = Month (01-12), `%b` = Short month name (Nov), `%B` = Month name (November)
+/// * `%d` = Day (01-31), `%e` = Space-padded day ( 1-31), `%j` = Day of the year (001-366)
+/// * `%a` = Short weekday name (Thu), `%A` = Weekday name (Thursday)
+/// * `%u` = ISO weekday (1-7, Monday = 1), `%w` = Weekday (0-6, Sunday = 0)
+/// * `%G` = ISO week-numbering year, `%V` = ISO week (01-53)
+/// * `%H` = Hour (00-23), `%I` = Hour (01-12), `%p` = AM/PM
+/// * `%M` = Minute (00-59), `%S` = Second (00-60)
+/// * `%z` = Offset (+0200), `%:z` = Offset (+02:00)
+/// * `%s` = Seconds since the epoch
+/// * `%F` = `%Y-%m-%d`, `%T` = `%H:%M:%S`
+/// * `%n` = Newline, `%t` = Tab, `%%` = A literal `%`
+///
+/// Any other specifier, and a trailing `%`, is copied to the output unchanged.
+pub(crate) fn strftime(date: &Date, offset: UtcOffset, pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len() + 16);
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let spec = match chars.next() {
+            Some(':') if chars.as_str().starts_with('z') => {
+                chars.next();
+                write_offset(&mut out, offset, true);
+                continue;
+            }
+            Some(spec) => spec,
+            None => {
+                out.push('%');
+                break;
+            }
+        };
+        // Writing into a String cannot fail.
+        let _ = match spec {
+            'Y' => write!(out, "{:04}", date.year),
+            'y' => write!(out, "{:02}", date.year.rem_euclid(100)),
+            'C' => write!(out, "{:02}", date.year.div_euclid(100)),
+            'm' => write!(out, "{:02}", date.month),
+            'b' | 'h' => write!(out, "{}", &MONTH_NAMES[date.month as usize - 1][..3]),
+            'B' => write!(out, "{}", MONTH_NAMES[date.month as usize - 1]),
+            'd' => write!(out, "{:02}", date.day),
+            'e' => write!(out, "{:2}", date.day),
+            'j' => write!(out, "{:03}", date.day_of_year()),
+            'a' => write!(out, "{}", date.weekday().short_name()),
+            'A' => write!(out, "{}", date.weekday().name()),
+            'u' => write!(out, "{}", date.weekday().number_from_monday()),
+            'w' => write!(out, "{}", date.weekday().number_from_monday() % 7),
+            'G' => write!(out, "{:04}", date.iso_week().0),
+            'V' => write!(out, "{:02}", date.iso_week().1),
+            'H' => write!(out, "{:02}", date.hour),
+            'I' => write!(out, "{:02}", (date.hour + 11) % 12 + 1),
+            'p' => write!(out, "{}", if date.hour < 12 { "AM" } else { "PM" }),
+            'M' => write!(out, "{:02}", date.minute),
+            'S' => write!(out, "{:02}", date.second),
+            'z' => {
+                write_offset(&mut out, offset, false);
+                Ok(())
+            }
+            's' => write!(out, "{}", date.to_seconds() - offset.total_seconds()),
+            'F' => write!(out, "{:04}-{:02}-{:02}", date.year, date.month, date.day),
+            'T' => write!(out, "{:02}:{:02}:{:02}", date.hour, date.minute, date.second),
+            'n' => writeln!(out),
+            't' => write!(out, "\t"),
+            '%' => write!(out, "%"),
+            other => write!(out, "%{}", other),
+        };
+    }
+    out
+}
+
+/// Tokens of the `format` methods, longest first so `dddd` wins over `dd`.
+const TOKENS: [&str; 14] = ["YYYY", "dddd", "ddd", "DDD", "yy", "mm", "dd", "ww", "HH", "hh", "MM", "SS", "tt", "TS"];
+
+/// Formats `date` by replacing the tokens of the `format` methods, scanning
+/// left to right. `TS` is only a token when a `timestamp` is given.
+///
+/// # Supported Tokens
+///
+/// * `YYYY` = Year (2023), `yy` = Short Year (23)
+/// * `mm` = Month (01-12), `dd` = Day (01-31), `DDD` = Day of the year (001-366)
+/// * `ddd` = Short weekday name (Thu), `dddd` = Weekday name (Thursday)
+/// * `ww` = ISO week (01-53)
+/// * `HH` = Hour (00-23), `hh` = Hour (01-12), `tt` = AM/PM
+/// * `MM` = Minute (00-59), `SS` = Second (00-60)
+/// * `TS` = Raw timestamp
+pub(crate) fn format_tokens(date: &Date, timestamp: Option<i64>, pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len() + 16);
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        let token = TOKENS.iter()
+            .find(|token| rest.starts_with(**token) && (**token != "TS" || timestamp.is_some()));
+        let Some(token) = token else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        let _ = match *token {
+            "YYYY" => write!(out, "{:04}", date.year),
+            "yy" => write!(out, "{:02}", date.year % 100),
+            "mm" => write!(out, "{:02}", date.month),
+            "dddd" => write!(out, "{}", date.weekday().name()),
+            "ddd" => write!(out, "{}", date.weekday().short_name()),
+            "dd" => write!(out, "{:02}", date.day),
+            "DDD" => write!(out, "{:03}", date.day_of_year()),
+            "ww" => write!(out, "{:02}", date.iso_week().1),
+            "HH" => write!(out, "{:02}", date.hour),
+            "hh" => write!(out, "{:02}", (date.hour + 11) % 12 + 1),
+            "tt" => write!(out, "{}", if date.hour < 12 { "AM" } else { "PM" }),
+            "MM" => write!(out, "{:02}", date.minute),
+            "SS" => write!(out, "{:02}", date.second),
+            _ => write!(out, "{}", timestamp.unwrap_or_default()),
+        };
+        rest = &rest[token.len()..];
+    }
+    out
+}
+
+/// Internal helper writing `offset` as `+HHMM`, or `+HH:MM` with `colon`.
+fn write_offset(out: &mut String, offset: UtcOffset, colon: bool) {
+    let sign = if offset.sign < 0 { '-' } else { '+' };
+    let sep = if colon { ":" } else { "" };
+    let _ = write!(out, "{}{:02}{}{:02}", sign, offset.hours, sep, offset.minutes);
+}
+
+/// Fields collected by `strptime` before they are assembled into a `Date`.
+#[derive(Default)]
+struct Fields {
+    year: Option<i32>,
+    month: Option<u8>,
+    day: Option<u8>,
+    ordinal: Option<u16>,
+    hour: Option<u8>,
+    hour12: Option<u8>,
+    pm: Option<bool>,
+    minute: Option<u8>,
+    second: Option<u8>,
+    weekday: Option<Weekday>,
+    offset: Option<UtcOffset>,
+    timestamp: Option<i64>,
+}
+
+/// Parses `s` according to a strftime-style `pattern` (see `strftime` for the specifiers).
+///
+/// Numeric fields accept one digit up to their full width, so `%d/%m` matches
+/// both `05/03` and `5/3`. Names match case-insensitively, in full or abbreviated.
+/// Missing fields default to 1970-01-01T00:00:00. With `%z` the result is
+/// converted to UTC; with `%s` the timestamp alone determines the result.
+pub(crate) fn strptime(pattern: &str, s: &str) -> Result<Date, String> {
+    let mut fields = Fields::default();
+    let mut input = s;
+    parse_into(pattern, &mut input, &mut fields)?;
+    if !input.is_empty() {
+        return Err(format!("Unexpected trailing input: {}", input));
+    }
+
+    if let Some(ts) = fields.timestamp {
+        return Date::from_unix(ts).map_err(|e| e.to_string());
+    }
+
+    let year = fields.year.unwrap_or(1970);
+    let (month, day) = match fields.ordinal {
+        Some(ordinal) => {
+            let days_in_year = if Date::is_leap(year) { 366 } else { 365 };
+            if !(1..=days_in_year).contains(&ordinal) {
+                return Err("Semantically invalid date".into());
+            }
+            let (_, month, day) = Date::civil_from_days(Date::days_from_civil(year, 1, 1) + ordinal as i64 - 1);
+            (month, day)
+        }
+        None => (fields.month.unwrap_or(1), fields.day.unwrap_or(1)),
+    };
+    let hour = match fields.hour12 {
+        Some(h) if !(1..=12).contains(&h) => return Err("Semantically invalid date".into()),
+        Some(h) => h % 12 + if fields.pm == Some(true) { 12 } else { 0 },
+        None => fields.hour.unwrap_or(0),
+    };
+    let minute = fields.minute.unwrap_or(0);
+    let second = fields.second.unwrap_or(0);
+    let date = Date { year, month, day, hour, minute, second };
+    if !date.is_valid() {
+        return Err("Semantically invalid date".into());
+    }
+    if fields.weekday.is_some_and(|w| w != date.weekday()) {
+        return Err("Weekday does not match date".into());
+    }
+    Ok(match fields.offset {
+        Some(offset) => Date::from_seconds(date.to_seconds() - offset.total_seconds()),
+        None => date,
+    })
+}
+
+/// Internal helper matching `pattern` against the start of `input`, advancing it.
+fn parse_into(pattern: &str, input: &mut &str, fields: &mut Fields) -> Result<(), String> {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        let literal = match c {
+            '%' => match chars.next() {
+                Some('%') | None => '%',
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some(':') if chars.as_str().starts_with('z') => {
+                    chars.next();
+                    fields.offset = Some(take_offset(input)?);
+                    continue;
+                }
+                Some(spec) => {
+                    parse_spec(spec, input, fields)?;
+                    continue;
+                }
+            },
+            c => c,
+        };
+        *input = input.strip_prefix(literal).ok_or_else(|| format!("Expected '{}' at: {}", literal, input))?;
+    }
+    Ok(())
+}
+
+/// Internal helper parsing the value of a single `%` specifier.
+fn parse_spec(spec: char, input: &mut &str, fields: &mut Fields) -> Result<(), String> {
+    match spec {
+        'Y' => {
+            let negative = input.starts_with('-');
+            if negative { *input = &input[1..]; }
+            let year = take_number(input, 4)? as i32;
+            fields.year = Some(if negative { -year } else { year });
+        }
+        // POSIX rule: 69-99 are 1969-1999, 00-68 are 2000-2068
+        'y' => fields.year = Some(match take_number(input, 2)? as i32 { y @ 69..=99 => 1900 + y, y => 2000 + y }),
+        'm' => fields.month = Some(take_number(input, 2)? as u8),
+        'b' | 'h' | 'B' => fields.month = Some(take_name(input, &MONTH_NAMES)? as u8 + 1),
+        'd' => fields.day = Some(take_number(input, 2)? as u8),
+        'e' => {
+            *input = input.strip_prefix(' ').unwrap_or(input);
+            fields.day = Some(take_number(input, 2)? as u8);
+        }
+        'j' => fields.ordinal = Some(take_number(input, 3)? as u16),
+        'a' | 'A' => {
+            let names: Vec<&str> = (1..=7).map(|n| Weekday::from_iso(n).name()).collect();
+            fields.weekday = Some(Weekday::from_iso(take_name(input, &names)? as u8 + 1));
+        }
+        'H' => fields.hour = Some(take_number(input, 2)? as u8),
+        'I' => fields.hour12 = Some(take_number(input, 2)? as u8),
+        'p' => fields.pm = Some(take_name(input, &["AM", "PM"])? == 1),
+        'M' => fields.minute = Some(take_number(input, 2)? as u8),
+        'S' => fields.second = Some(take_number(input, 2)? as u8),
+        'z' => fields.offset = Some(take_offset(input)?),
+        's' => {
+            let end = input.char_indices()
+                .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+                .map_or(input.len(), |(i, _)| i);
+            fields.timestamp = Some(input[..end].parse().map_err(|_| format!("Invalid timestamp at: {}", input))?);
+            *input = &input[end..];
+        }
+        'F' => parse_into("%Y-%m-%d", input, fields)?,
+        'T' => parse_into("%H:%M:%S", input, fields)?,
+        other => return Err(format!("Unsupported specifier: %{}", other)),
+    }
+    Ok(())
+}
+
+/// Internal helper reading 1 to `max_digits` ASCII digits.
+fn take_number(input: &mut &str, max_digits: usize) -> Result<u32, String> {
+    let len = input.bytes().take(max_digits).take_while(u8::is_ascii_digit).count();
+    if len == 0 {
+        return Err(format!("Expected a number at: {}", input));
+    }
+    let value = input[..len].parse().map_err(|_| format!("Invalid number: {}", &input[..len]))?;
+    *input = &input[len..];
+    Ok(value)
+}
+
+/// Internal helper matching one of `names` (or its 3-letter abbreviation),
+/// ignoring case. Returns its index.
+fn take_name(input: &mut &str, names: &[&str]) -> Result<usize, String> {
+    let matches = |len: usize, name: &str| {
+        input.get(..len).is_some_and(|head| head.eq_ignore_ascii_case(&name[..len]))
+    };
+    for (i, name) in names.iter().enumerate() {
+        if matches(name.len(), name) {
+            *input = &input[name.len()..];
+            return Ok(i);
+        }
+    }
+    for (i, name) in names.iter().enumerate() {
+        if name.len() > 3 && matches(3, name) {
+            *input = &input[3..];
+            return Ok(i);
+        }
+    }
+    Err(format!("Unrecognized name at: {}", input))
+}
+
+/// Internal helper reading a `Z`, `+HHMM`, or `+HH:MM` offset.
+fn take_offset(input: &mut &str) -> Result<UtcOffset, String> {
+    let len = match input.as_bytes() {
+        [b'Z' | b'z', ..] => 1,
+        [_, _, _, b':', ..] => 6,
+        _ => 5,
+    };
+    let text = input.get(..len).ok_or_else(|| format!("Invalid offset: {}", input))?;
+    let offset = UtcOffset::parse(text)?;
+    *input = &input[len..];
+    Ok(offset)
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Date {
+        Date { year: 2023, month: 11, day: 5, hour: 14, minute: 3, second: 9 }
+    }
+
+    #[test]
+    fn test_all_specifiers() {
+        let offset = UtcOffset { sign: 1, hours: 2, minutes: 0 };
+        assert_eq!(strftime(&sample(), offset, "%Y %y %C %m %b %B %h"), "2023 23 20 11 Nov November Nov");
+        assert_eq!(strftime(&sample(), offset, "%d|%e|%j|%a|%A|%u|%w|%G-W%V"), "05| 5|309|Sun|Sunday|7|0|2023-W44");
+        assert_eq!(strftime(&sample(), offset, "%H %I %p %M %S %z %:z"), "14 02 PM 03 09 +0200 +02:00");
+        assert_eq!(strftime(&sample(), offset, "%F %T %s"), "2023-11-05 14:03:09 1699185789");
+        assert_eq!(strftime(&sample(), UtcOffset::UTC, "%n%t"), "\n\t");
+    }
+
+    #[test]
+    fn test_strptime_round_trips_strftime() {
+        let offset = UtcOffset::UTC;
+        for pattern in ["%Y-%m-%d %H:%M:%S", "%d/%m/%y %I:%M:%S %p", "%a, %e %b %Y %T %z", "%A %B %d %Y %Hh%Mm%Ss",
+                        "%Y-%j %T", "%F", "%s", "100%% %FT%T%:z"] {
+            let text = strftime(&sample(), offset, pattern);
+            let expected = if pattern == "%F" { Date { hour: 0, minute: 0, second: 0, ..sample() } } else { sample() };
+            assert_eq!(strptime(pattern, &text), Ok(expected), "{} / {}", pattern, text);
+        }
+    }
+
+    #[test]
+    fn test_strptime_flexible_input() {
+        assert_eq!(strptime("%d/%m/%Y %H:%M", "5/3/2024 7:05").unwrap(),
+                   Date { year: 2024, month: 3, day: 5, hour: 7, minute: 5, second: 0 });
+        assert_eq!(strptime("%d %b %Y", "01 SEPTEMBER 2024").unwrap().month, 9);
+        assert_eq!(strptime("%d %b %Y", "01 sep 2024").unwrap().month, 9);
+        assert_eq!(strptime("%H:%M", "12:30").unwrap(), Date { year: 1970, month: 1, day: 1, hour: 12, minute: 30, second: 0 });
+        assert_eq!(strptime("%I %p", "12 am").unwrap().hour, 0);
+        assert_eq!(strptime("%F %T%z", "2024-01-01 01:00:00+0200").unwrap(),
+                   Date { year: 2023, month: 12, day: 31, hour: 23, minute: 0, second: 0 });
+        assert_eq!(strptime("%y", "69").unwrap().year, 1969);
+        assert_eq!(strptime("%y", "68").unwrap().year, 2068);
+    }
+
+    #[test]
+    fn test_strptime_errors() {
+        assert!(strptime("%d/%m/%Y", "31/02/2024").is_err());
+        assert!(strptime("%d/%m/%Y", "01-02-2024").is_err());
+        assert!(strptime("%Y", "2024x").is_err());
+        assert!(strptime("%Y-%j", "2023-366").is_err());
+        assert!(strptime("%a %F", "Mon 2023-11-23").is_err());
+        assert!(strptime("%I:%M", "13:00").is_err());
+        assert!(strptime("%Q", "x").is_err());
+        assert!(strptime("%H", "").is_err());
+    }
+
+    #[test]
+    fn test_format_tokens() {
+        let date = Date { year: 2021, month: 1, day: 3, hour: 0, minute: 5, second: 9 };
+        assert_eq!(format_tokens(&date, None, "YYYY-Www (DDD) hh:MM:SS tt"), "2021-W53 (003) 12:05:09 AM");
+        assert_eq!(format_tokens(&date, Some(7), "dddd ddd dd TS"), "Sunday Sun 03 7");
+        assert_eq!(format_tokens(&date, None, "TS yyy ·"), "TS 21y ·");
+        // Replacements are not scanned again: `tt` gives `AM`, not a minute.
+        assert_eq!(format_tokens(&date, None, "ttM"), "AMM");
+    }
+
+    #[test]
+    fn test_literal_text_is_preserved() {
+        let utc = UtcOffset::UTC;
+        assert_eq!(strftime(&sample(), utc, "MM mm YYYY dd: %d"), "MM mm YYYY dd: 05");
+        assert_eq!(strftime(&sample(), utc, "100%% at %H%"), "100% at 14%");
+        assert_eq!(strftime(&sample(), utc, "%Q %:x"), "%Q %:x");
+        assert_eq!(strftime(&sample(), utc, "%I%p"), "02PM");
+        let midnight = Date { hour: 0, ..sample() };
+        assert_eq!(strftime(&midnight, utc, "%I %p"), "12 AM");
+    }
+}
@@ -1,15 +1,23 @@
 use std::fmt;
-use crate::date::date::Date;
+use std::time::Duration;
+use crate::date::clock::{Clock, SystemClock};
+use crate::date::date::{Date, LeapSecond, MonthEnd};
+use crate::date::format;
+use crate::date::posix::Posix;
+use crate::date::rcf3339::UtcOffset;
 
 /// A wrapper structure for ISO 8601 Date and Time handling.
 /// 
 /// This struct wraps a `Date` object and provides parsing logic for both
 /// **Extended Format** (`YYYY-MM-DDTHH:MM:SS`) and **Basic Format** (`YYYYMMDDTHHMMSS`).
 /// It also validates calendar semantics.
+///
+/// `offset` is the UTC offset designator (`Z`, `+01:00`), or `None` for a
+/// local time without one.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Iso8601 {
     pub date: Date,
-    pub offset_str: Option<&'static str>, 
+    pub offset: Option<UtcOffset>,
 }
 
 /// A structure representing an ISO 8601 Duration.
@@ -17,6 +25,8 @@ pub struct Iso8601 {
 /// ISO 8601 Durations use the format `P[n]Y[n]M[n]DT[n]H[n]M[n]S`.
 /// For example: `P3Y6M4DT12H30M5S` represents a duration of 3 years,
 /// 6 months, 4 days, 12 hours, 30 minutes, and 5 seconds.
+///
+/// `nanos` holds the sub-second part of a fractional duration such as `PT0.5S`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct IsoDuration {
     pub years: u32,
@@ -25,6 +35,64 @@ pub struct IsoDuration {
     pub hours: u32,
     pub minutes: u32,
     pub seconds: u32,
+    pub nanos: u32,
+}
+
+/// A component of an `IsoDuration`, as passed to `HumanizeOptions::unit_name`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DurationUnit {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// Options for `IsoDuration::humanize_with`.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::date::iso8601::{DurationUnit, HumanizeOptions, IsoDuration};
+/// fn french(unit: DurationUnit, n: u64) -> String {
+///     let name = match unit {
+///         DurationUnit::Day => if n == 1 { "jour" } else { "jours" },
+///         DurationUnit::Hour => "h",
+///         _ => "?",
+///     };
+///     format!("{} {}", n, name)
+/// }
+/// let dur = IsoDuration::parse("P3DT4H30M").unwrap();
+/// let opts = HumanizeOptions { max_components: 2, unit_name: french };
+/// assert_eq!(dur.humanize_with(opts), "3 jours 4 h");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HumanizeOptions {
+    /// Maximum number of components shown, largest first. Defaults to 2.
+    pub max_components: usize,
+    /// Writes one component, the locale hook. Defaults to `english_unit_name`.
+    pub unit_name: fn(DurationUnit, u64) -> String,
+}
+
+impl Default for HumanizeOptions {
+    fn default() -> Self {
+        HumanizeOptions { max_components: 2, unit_name: english_unit_name }
+    }
+}
+
+/// Writes a duration component in English: calendar units in full (`3 days`,
+/// `1 year`) and clock units abbreviated (`2h`, `30m`, `5s`).
+pub fn english_unit_name(unit: DurationUnit, n: u64) -> String {
+    let plural = if n == 1 { "" } else { "s" };
+    match unit {
+        DurationUnit::Year => format!("{} year{}", n, plural),
+        DurationUnit::Month => format!("{} month{}", n, plural),
+        DurationUnit::Day => format!("{} day{}", n, plural),
+        DurationUnit::Hour => format!("{}h", n),
+        DurationUnit::Minute => format!("{}m", n),
+        DurationUnit::Second => format!("{}s", n),
+    }
 }
 
 impl Iso8601 {
@@ -33,6 +101,9 @@ impl Iso8601 {
     /// This method automatically detects and handles:
     /// * Extended format: `2023-11-23T14:30:00`
     /// * Basic format: `20231123T143000`
+    /// * Week dates in either format: `2023-W47-4T14:30:00`, `2023W474T143000`
+    /// * Ordinal dates in either format: `2023-327T14:30:00`, `2023327T143000`
+    /// * An optional offset: `Z`, `+01:00`, `+0100`, or `+01`
     ///
     /// # Arguments
     ///
@@ -52,6 +123,9 @@ impl Iso8601 {
     /// // Basic
     /// let basic = Iso8601::parse("20231123T143000").unwrap();
     /// assert_eq!(iso, basic);
+    /// // With an offset
+    /// let zoned = Iso8601::parse("2023-11-23T14:30:00+01").unwrap();
+    /// assert_eq!(zoned.offset.unwrap().to_string(), "+01:00");
     /// ```
     pub fn parse(s: &str) -> Result<Self, String> {
         if s.is_empty() { return Err("String is empty".into()); }
@@ -63,44 +137,73 @@ impl Iso8601 {
         }
 
         let date_part = parts[0];
-        let time_part = parts[1].trim_end_matches('Z'); // Strip UTC 'Z' marker if present
+        let (time_part, offset) = Self::split_offset(parts[1])?;
 
         let (year, month, day) = Self::parse_date_part(date_part)?;
         let (hour, minute, second) = Self::parse_time_part(time_part)?;
 
-        // Validate logical correctness
-        if !Self::is_valid_calendar(year, month, day, hour, minute, second) {
-            return Err("Semantically invalid date".into());
-        }
-
         let date = Date {
             year, month, day, hour, minute, second
         };
 
+        // Validate logical correctness
+        if !date.is_valid() {
+            return Err("Semantically invalid date".into());
+        }
+
         Ok(Iso8601 {
             date,
-            offset_str: None,
+            offset,
         })
     }
 
+    /// Returns the current time from the system clock, in UTC.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let now = Iso8601::now_utc();
+    /// assert_eq!(Iso8601::parse(&now.to_iso8601()).unwrap(), now);
+    /// ```
+    pub fn now_utc() -> Self {
+        Self::now_utc_with(&SystemClock)
+    }
+
+    /// Returns the current time from `clock`, in UTC. The sub-second part is dropped.
+    pub fn now_utc_with(clock: &dyn Clock) -> Self {
+        Iso8601 { date: clock.now().date, offset: Some(UtcOffset::UTC) }
+    }
+
     /// Internal helper to parse the date portion (YYYY-MM-DD or YYYYMMDD).
-    fn parse_date_part(s: &str) -> Result<(i32, u8, u8), String> {
+    pub(crate) fn parse_date_part(s: &str) -> Result<(i32, u8, u8), String> {
         let parse_num = |str_slice: &str| -> Result<u32, String> {
             str_slice.parse::<u32>().map_err(|_| format!("Invalid number: {}", str_slice))
         };
 
+        if s.contains('W') {
+            return Self::parse_week_date(s);
+        }
+
         if s.contains('-') {
             // Extended format: YYYY-MM-DD
             let parts: Vec<&str> = s.split('-').collect();
+            if parts.len() == 2 && parts[1].len() == 3 {
+                // Ordinal format: YYYY-DDD
+                return Self::ordinal_to_calendar(parse_num(parts[0])? as i32, parse_num(parts[1])?);
+            }
             if parts.len() != 3 { return Err("Invalid extended date format".into()); }
             Ok((
                 parse_num(parts[0])? as i32,
                 parse_num(parts[1])? as u8,
                 parse_num(parts[2])? as u8
             ))
+        } else if s.len() == 7 && s.is_ascii() {
+            // Basic ordinal format: YYYYDDD
+            Self::ordinal_to_calendar(parse_num(&s[0..4])? as i32, parse_num(&s[4..7])?)
         } else {
             // Basic format: YYYYMMDD (length 8)
-            if s.len() != 8 { return Err("Invalid basic date length".into()); }
+            if s.len() != 8 || !s.is_ascii() { return Err("Invalid basic date length".into()); }
             Ok((
                 parse_num(&s[0..4])? as i32,
                 parse_num(&s[4..6])? as u8,
@@ -109,8 +212,40 @@ impl Iso8601 {
         }
     }
 
+    /// Internal helper to convert a year and day of the year (1-366) into a calendar date.
+    fn ordinal_to_calendar(year: i32, ordinal: u32) -> Result<(i32, u8, u8), String> {
+        let days_in_year = if Date::is_leap(year) { 366 } else { 365 };
+        if !(1..=days_in_year).contains(&ordinal) {
+            return Err("Semantically invalid ordinal date".into());
+        }
+        Ok(Date::civil_from_days(Date::days_from_civil(year, 1, 1) + ordinal as i64 - 1))
+    }
+
+    /// Internal helper to parse a week date (YYYY-Www-D or YYYYWwwD) into a calendar date.
+    fn parse_week_date(s: &str) -> Result<(i32, u8, u8), String> {
+        let parse_num = |str_slice: &str| -> Result<u32, String> {
+            str_slice.parse::<u32>().map_err(|_| format!("Invalid number: {}", str_slice))
+        };
+
+        let (year, week, weekday) = match (s.len(), s.get(4..6)) {
+            _ if !s.is_ascii() => return Err("Invalid week date format".into()),
+            (10, Some("-W")) if &s[8..9] == "-" => (&s[0..4], &s[6..8], &s[9..10]),
+            (8, Some(w)) if w.starts_with('W') => (&s[0..4], &s[5..7], &s[7..8]),
+            _ => return Err("Invalid week date format".into()),
+        };
+        let year = parse_num(year)? as i32;
+        let week = parse_num(week)?;
+        let weekday = parse_num(weekday)?;
+        if week == 0 || week > Date::iso_weeks_in_year(year) as u32 || !(1..=7).contains(&weekday) {
+            return Err("Semantically invalid week date".into());
+        }
+
+        let days = Date::iso_week1_monday(year) + (week as i64 - 1) * 7 + (weekday as i64 - 1);
+        Ok(Date::civil_from_days(days))
+    }
+
     /// Internal helper to parse the time portion (HH:MM:SS or HHMMSS).
-    fn parse_time_part(s: &str) -> Result<(u8, u8, u8), String> {
+    pub(crate) fn parse_time_part(s: &str) -> Result<(u8, u8, u8), String> {
         let parse_num = |str_slice: &str| -> Result<u8, String> {
             str_slice.parse::<u8>().map_err(|_| format!("Invalid number: {}", str_slice))
         };
@@ -126,6 +261,7 @@ impl Iso8601 {
         } else {
             // Basic format: HHMMSS (len 6) or HHMM (len 4)
             match s.len() {
+                _ if !s.is_ascii() => Err("Invalid basic time format".into()),
                 6 => Ok((parse_num(&s[0..2])?, parse_num(&s[2..4])?, parse_num(&s[4..6])?)),
                 4 => Ok((parse_num(&s[0..2])?, parse_num(&s[2..4])?, 0)),
                 _ => Err("Invalid basic time length".into())
@@ -133,33 +269,239 @@ impl Iso8601 {
         }
     }
 
-    /// Returns the ISO 8601 Extended string representation.
+    /// Internal helper to split a trailing offset (`Z`, `±HH:MM`, `±HHMM`, `±HH`) off the time.
+    fn split_offset(s: &str) -> Result<(&str, Option<UtcOffset>), String> {
+        if let Some(time) = s.strip_suffix('Z') {
+            return Ok((time, Some(UtcOffset::UTC)));
+        }
+        match s.find(['+', '-']) {
+            Some(i) if s.len() - i == 3 => Ok((&s[..i], Some(UtcOffset::parse(&format!("{}00", &s[i..]))?))),
+            Some(i) => Ok((&s[..i], Some(UtcOffset::parse(&s[i..])?))),
+            None => Ok((s, None)),
+        }
+    }
+
+    /// Returns the ISO 8601 Extended string representation, with the offset if any.
     pub fn to_iso8601(&self) -> String {
-        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", 
-            self.date.year, self.date.month, self.date.day, self.date.hour, self.date.minute, self.date.second)
+        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+            self.date.year, self.date.month, self.date.day, self.date.hour, self.date.minute, self.date.second,
+            self.offset_suffix())
     }
 
-    /// Returns the ISO 8601 Basic string representation (compact).
+    /// Returns the ISO 8601 Basic string representation (compact), with the offset if any.
     pub fn to_iso8601_basic(&self) -> String {
-        format!("{:04}{:02}{:02}T{:02}{:02}{:02}", 
-            self.date.year, self.date.month, self.date.day, self.date.hour, self.date.minute, self.date.second)
+        format!("{:04}{:02}{:02}T{:02}{:02}{:02}{}",
+            self.date.year, self.date.month, self.date.day, self.date.hour, self.date.minute, self.date.second,
+            self.offset_suffix().replace(':', ""))
     }
 
-    // Reuse validation logic
-    fn is_valid_calendar(y: i32, m: u8, d: u8, h: u8, min: u8, s: u8) -> bool {
-        if m < 1 || m > 12 || h > 23 || min > 59 || s > 60 { return false; }
-        let days_in_month = match m {
-            4 | 6 | 9 | 11 => 30,
-            2 => if (y % 4 == 0 && y % 100 != 0) || (y % 400 == 0) { 29 } else { 28 },
-            _ => 31,
-        };
-        d >= 1 && d <= days_in_month
+    /// Returns the same instant expressed in UTC. A local time without an
+    /// offset is taken to be UTC already.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let iso = Iso8601::parse("2023-12-31T22:30:00-05:00").unwrap();
+    /// assert_eq!(iso.to_utc().to_iso8601(), "2024-01-01T03:30:00Z");
+    /// ```
+    pub fn to_utc(&self) -> Iso8601 {
+        self.with_offset(UtcOffset::UTC)
+    }
+
+    /// Returns the same instant expressed at `offset`. A local time without an
+    /// offset is taken to be UTC.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// use stdt::date::rcf3339::UtcOffset;
+    /// let iso = Iso8601::parse("2023-11-23T14:30:00Z").unwrap();
+    /// let tokyo = iso.with_offset(UtcOffset::parse("+09:00").unwrap());
+    /// assert_eq!(tokyo.to_iso8601(), "2023-11-23T23:30:00+09:00");
+    /// ```
+    pub fn with_offset(&self, offset: UtcOffset) -> Iso8601 {
+        let current = self.offset.unwrap_or(UtcOffset::UTC);
+        Iso8601 {
+            date: Date::from_seconds(self.date.to_seconds() - current.total_seconds() + offset.total_seconds()),
+            offset: Some(offset),
+        }
+    }
+
+    /// Converts to a `Posix` timestamp, resolving a leap second (`:60`) with
+    /// `policy`. A local time without an offset is taken to be UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `policy` is `LeapSecond::Reject` and the date
+    /// has a leap second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::LeapSecond;
+    /// use stdt::date::iso8601::Iso8601;
+    /// let iso = Iso8601::parse("2016-12-31T23:59:60Z").unwrap();
+    /// assert_eq!(iso.to_posix(LeapSecond::Clamp).unwrap().to_string_timestamp(), "1483228799");
+    /// assert!(iso.to_posix(LeapSecond::Reject).is_err());
+    /// ```
+    pub fn to_posix(&self, policy: LeapSecond) -> Result<Posix, String> {
+        let date = self.date.resolve_leap_second(policy)?;
+        Posix::from_date(Iso8601 { date, ..*self }.to_utc().date, LeapSecond::Reject)
+    }
+
+    /// Returns this date moved forward by `dur`.
+    ///
+    /// Years and months are applied first, clamping the day to the length of
+    /// the resulting month (so Jan 31 + `P1M` is the last day of February).
+    /// Days, hours, minutes, and seconds are then added as elapsed time,
+    /// carrying into the following days, months, and years. A leap second
+    /// carries into the next minute, as with `LeapSecond::Carry`. Since `Date`
+    /// has whole seconds, `nanos` is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::{Iso8601, IsoDuration};
+    /// let start = Iso8601::parse("2024-01-31T22:00:00").unwrap();
+    /// let expiry = start.add(&IsoDuration::parse("P1MT3H").unwrap());
+    /// assert_eq!(expiry.to_iso8601(), "2024-03-01T01:00:00");
+    /// ```
+    pub fn add(&self, dur: &IsoDuration) -> Self {
+        self.shift(self.date.add_months(dur.total_months()), dur, 1)
+    }
+
+    /// Returns this date moved forward by `dur`, resolving a day that does not
+    /// exist in the target month with `policy` instead of clamping it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `policy` is `MonthEnd::Reject` and the day
+    /// does not exist after adding the years and months of `dur`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::MonthEnd;
+    /// use stdt::date::iso8601::{Iso8601, IsoDuration};
+    /// let start = Iso8601::parse("2023-01-31T00:00:00").unwrap();
+    /// let month = IsoDuration::parse("P1M").unwrap();
+    /// assert_eq!(start.add_with(&month, MonthEnd::ClampToMonthEnd).unwrap().to_iso8601(), "2023-02-28T00:00:00");
+    /// assert_eq!(start.add_with(&month, MonthEnd::Overflow).unwrap().to_iso8601(), "2023-03-03T00:00:00");
+    /// assert!(start.add_with(&month, MonthEnd::Reject).is_err());
+    /// ```
+    pub fn add_with(&self, dur: &IsoDuration, policy: MonthEnd) -> Result<Self, String> {
+        Ok(self.shift(self.date.add_months_with(dur.total_months(), policy)?, dur, 1))
+    }
+
+    /// Returns this date moved back by `dur`, applying years and months
+    /// before the remaining fields as in [`Iso8601::add`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::{Iso8601, IsoDuration};
+    /// let end = Iso8601::parse("2024-03-31T00:30:00").unwrap();
+    /// let start = end.sub(&IsoDuration::parse("P1MT1H").unwrap());
+    /// assert_eq!(start.to_iso8601(), "2024-02-28T23:30:00");
+    /// ```
+    pub fn sub(&self, dur: &IsoDuration) -> Self {
+        self.shift(self.date.add_months(-dur.total_months()), dur, -1)
+    }
+
+    /// Returns this date moved back by `dur`, resolving a day that does not
+    /// exist in the target month with `policy` as in [`Iso8601::add_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `policy` is `MonthEnd::Reject` and the day
+    /// does not exist after subtracting the years and months of `dur`.
+    pub fn sub_with(&self, dur: &IsoDuration, policy: MonthEnd) -> Result<Self, String> {
+        Ok(self.shift(self.date.add_months_with(-dur.total_months(), policy)?, dur, -1))
+    }
+
+    /// Internal helper shared by `add` (`sign` = 1) and `sub` (`sign` = -1):
+    /// applies the elapsed-time fields of `dur` to `moved`, the date with its
+    /// years and months already applied.
+    fn shift(&self, moved: Date, dur: &IsoDuration, sign: i64) -> Self {
+        let elapsed = dur.days as i64 * 86400 + dur.hours as i64 * 3600 + dur.minutes as i64 * 60 + dur.seconds as i64;
+        Iso8601 {
+            date: Date::from_seconds(moved.to_seconds() + sign * elapsed),
+            offset: self.offset,
+        }
+    }
+
+    /// Returns the ISO 8601 week date representation (`YYYY-Www-DTHH:MM:SS`).
+    ///
+    /// The year is the ISO week-numbering year, which differs from the calendar
+    /// year for a few days around New Year.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let iso = Iso8601::parse("2023-11-23T14:30:00").unwrap();
+    /// assert_eq!(iso.to_iso_week_string(), "2023-W47-4T14:30:00");
+    /// let new_year = Iso8601::parse("2021-01-03T00:00:00").unwrap();
+    /// assert_eq!(new_year.to_iso_week_string(), "2020-W53-7T00:00:00");
+    /// ```
+    pub fn to_iso_week_string(&self) -> String {
+        let (year, week, weekday) = self.date.iso_week_date();
+        format!("{:04}-W{:02}-{}T{:02}:{:02}:{:02}{}",
+            year, week, weekday, self.date.hour, self.date.minute, self.date.second, self.offset_suffix())
+    }
+
+    /// Returns the ISO 8601 ordinal date representation (`YYYY-DDDTHH:MM:SS`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let iso = Iso8601::parse("2023-11-23T14:30:00").unwrap();
+    /// assert_eq!(iso.to_ordinal_string(), "2023-327T14:30:00");
+    /// assert_eq!(Iso8601::parse("2023-327T14:30:00").unwrap(), iso);
+    /// ```
+    pub fn to_ordinal_string(&self) -> String {
+        format!("{:04}-{:03}T{:02}:{:02}:{:02}{}",
+            self.date.year, self.date.day_of_year(), self.date.hour, self.date.minute, self.date.second,
+            self.offset_suffix())
+    }
+
+    /// Formats the date with strftime-style `%` specifiers.
+    ///
+    /// Unlike `format`, text outside specifiers is copied as-is, so words
+    /// like `MM` or `dd` are never replaced. See the supported specifiers
+    /// below; `%%` writes a literal `%`.
+    ///
+    /// # Supported Specifiers
+    ///
+    /// `%Y %y %C %m %b %B %d %e %j %a %A %u %w %G %V %H %I %p %M %S %z %:z %s %F %T %n %t %%`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// let iso = Iso8601::parse("2023-11-23T14:30:00").unwrap();
+    /// assert_eq!(iso.strftime("%G-W%V-%u, day %j"), "2023-W47-4, day 327");
+    /// ```
+    pub fn strftime(&self, pattern: &str) -> String {
+        format::strftime(&self.date, self.offset.unwrap_or(UtcOffset::UTC), pattern)
+    }
+
+    /// Internal helper writing the offset designator, or nothing for a local time.
+    fn offset_suffix(&self) -> String {
+        self.offset.map_or(String::new(), |offset| offset.to_string())
     }
 }
 
 impl IsoDuration {
     /// Parses an ISO 8601 Duration string (e.g., "P3Y6M4DT12H30M5S").
     ///
+    /// The last component may have a decimal fraction (`PT0.5S`, `PT1,5H`),
+    /// which is spread over the smaller components down to `nanos`. Fractions
+    /// of years and months are not supported, as their length varies.
+    ///
     /// # Arguments
     ///
     /// * `s` - The duration string starting with 'P'.
@@ -186,11 +528,33 @@ impl IsoDuration {
         let mut dur = IsoDuration::default();
         let mut num_buf = String::new();
         let mut is_time_part = false; // Toggles after 'T' is encountered
+        let mut fraction: Option<(String, u128)> = None; // Digits and unit length in seconds
 
         // Iterate characters skipping the first 'P'
         for c in s.chars().skip(1) {
+            if fraction.is_some() {
+                return Err("Only the smallest component may have a fraction".into());
+            }
+            // Split off a decimal fraction (`.` or `,`) before the designator
+            let unit_seconds = match (c, is_time_part) {
+                ('D', false) => 86_400,
+                ('H', true) => 3600,
+                ('M', true) => 60,
+                ('S', true) => 1,
+                _ => 0,
+            };
+            if c.is_ascii_alphabetic() && let Some((int, frac)) = num_buf.split_once(['.', ',']) {
+                if unit_seconds == 0 {
+                    return Err(format!("Fractions are not supported for '{}'", c));
+                }
+                if int.is_empty() || frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(format!("Invalid fraction: {}", num_buf));
+                }
+                fraction = Some((frac.to_string(), unit_seconds));
+                num_buf.truncate(int.len());
+            }
             match c {
-                '0'..='9' => num_buf.push(c),
+                '0'..='9' | '.' | ',' => num_buf.push(c),
                 'T' => {
                     is_time_part = true;
                     if !num_buf.is_empty() { return Err("Unexpected number before 'T'".into()); }
@@ -224,26 +588,135 @@ impl IsoDuration {
             }
         }
 
+        // Spread the fraction over the smaller components, which are all zero
+        if let Some((digits, unit_seconds)) = fraction {
+            let digits = &digits[..digits.len().min(18)];
+            let nanos = digits.parse::<u128>().unwrap_or(0) * unit_seconds * 1_000_000_000
+                / 10u128.pow(digits.len() as u32);
+            let secs = (nanos / 1_000_000_000) as u32;
+            dur.hours += secs / 3600;
+            dur.minutes += secs % 3600 / 60;
+            dur.seconds += secs % 60;
+            dur.nanos = (nanos % 1_000_000_000) as u32;
+        }
+
         Ok(dur)
     }
 
-    /// Formats the duration back to ISO 8601 string.
-    pub fn to_string(&self) -> String {
-        let mut s = String::from("P");
-        if self.years > 0 { s.push_str(&format!("{}Y", self.years)); }
-        if self.months > 0 { s.push_str(&format!("{}M", self.months)); }
-        if self.days > 0 { s.push_str(&format!("{}D", self.days)); }
+    /// Converts to a `std::time::Duration`, e.g. for timers and sleeps.
+    ///
+    /// With a `reference` date the result is exact: the time elapsed from
+    /// `reference` to `reference + self`, as computed by [`Iso8601::add`], so
+    /// `P1M` from 2024-01-31 is 29 days. Without one, years and months are
+    /// approximated by their Gregorian averages (a year is 365.2425 days and
+    /// a month is a twelfth of that); days are always 24 hours.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stdt::date::iso8601::{Iso8601, IsoDuration};
+    /// let dur = IsoDuration::parse("P1DT2H").unwrap();
+    /// assert_eq!(dur.to_std(None), Duration::from_secs(93_600));
+    ///
+    /// let month = IsoDuration::parse("P1M").unwrap();
+    /// let feb = Iso8601::parse("2023-02-01T00:00:00").unwrap().date;
+    /// assert_eq!(month.to_std(Some(&feb)), Duration::from_secs(28 * 86_400));
+    /// assert_eq!(month.to_std(None), Duration::from_secs(2_629_746));
+    /// ```
+    pub fn to_std(&self, reference: Option<&Date>) -> Duration {
+        let secs = match reference {
+            Some(date) => {
+                let start = Iso8601 { date: *date, offset: None };
+                (start.add(self).date.to_seconds() - date.to_seconds()) as u64
+            }
+            None => {
+                // 400 Gregorian years have 146097 days.
+                const SECONDS_PER_YEAR: u64 = 146_097 * 86_400 / 400;
+                self.years as u64 * SECONDS_PER_YEAR
+                    + self.months as u64 * (SECONDS_PER_YEAR / 12)
+                    + self.days as u64 * 86_400
+                    + self.hours as u64 * 3600
+                    + self.minutes as u64 * 60
+                    + self.seconds as u64
+            }
+        };
+        Duration::new(secs, self.nanos)
+    }
 
-        if self.hours > 0 || self.minutes > 0 || self.seconds > 0 {
-            s.push('T');
-            if self.hours > 0 { s.push_str(&format!("{}H", self.hours)); }
-            if self.minutes > 0 { s.push_str(&format!("{}M", self.minutes)); }
-            if self.seconds > 0 { s.push_str(&format!("{}S", self.seconds)); }
+    /// Converts a `std::time::Duration` into days, hours, minutes, and seconds.
+    ///
+    /// Years and months are never used, since their length depends on the
+    /// calendar. Fractions of a second are kept in `nanos`, and the day count
+    /// saturates at `u32::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stdt::date::iso8601::IsoDuration;
+    /// let dur = IsoDuration::from_std(Duration::from_millis(93_784_500));
+    /// assert_eq!(dur.to_string(), "P1DT2H3M4.5S");
+    /// ```
+    pub fn from_std(duration: Duration) -> Self {
+        let secs = duration.as_secs();
+        IsoDuration {
+            days: (secs / 86_400).min(u32::MAX as u64) as u32,
+            hours: (secs % 86_400 / 3600) as u32,
+            minutes: (secs % 3600 / 60) as u32,
+            seconds: (secs % 60) as u32,
+            nanos: duration.subsec_nanos(),
+            ..Default::default()
         }
-        
-        // Edge case: empty duration P0D
-        if s == "P" { return "P0D".to_string(); }
-        s
+    }
+
+    /// Formats the duration for humans with the default `HumanizeOptions`:
+    /// at most two components, in English.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::IsoDuration;
+    /// assert_eq!(IsoDuration::parse("PT2H30M15S").unwrap().humanize(), "2h 30m");
+    /// assert_eq!(IsoDuration::parse("P3D").unwrap().humanize(), "3 days");
+    /// assert_eq!(IsoDuration::parse("PT90M").unwrap().humanize(), "1h 30m");
+    /// ```
+    pub fn humanize(&self) -> String {
+        self.humanize_with(HumanizeOptions::default())
+    }
+
+    /// Formats the duration for humans: the largest non-zero components, up
+    /// to `options.max_components`, each written by `options.unit_name` and
+    /// joined with spaces. Smaller components are dropped, not rounded.
+    ///
+    /// Seconds and minutes of 60 or more are carried into minutes and hours.
+    /// Hours are not carried into days, nor days into months, as those vary in
+    /// length. An empty duration is written as zero seconds.
+    pub fn humanize_with(&self, options: HumanizeOptions) -> String {
+        let seconds = self.seconds as u64;
+        let minutes = self.minutes as u64 + seconds / 60;
+        let components = [
+            (DurationUnit::Year, self.years as u64),
+            (DurationUnit::Month, self.months as u64),
+            (DurationUnit::Day, self.days as u64),
+            (DurationUnit::Hour, self.hours as u64 + minutes / 60),
+            (DurationUnit::Minute, minutes % 60),
+            (DurationUnit::Second, seconds % 60),
+        ];
+        let parts: Vec<String> = components.iter()
+            .filter(|(_, n)| *n > 0)
+            .take(options.max_components.max(1))
+            .map(|&(unit, n)| (options.unit_name)(unit, n))
+            .collect();
+        if parts.is_empty() {
+            return (options.unit_name)(DurationUnit::Second, 0);
+        }
+        parts.join(" ")
+    }
+
+    /// Years and months combined, as applied by calendar arithmetic.
+    fn total_months(&self) -> i64 {
+        self.years as i64 * 12 + self.months as i64
     }
 }
 
@@ -254,9 +727,29 @@ impl fmt::Display for Iso8601 {
     }
 }
 
+/// Formats the duration back to an ISO 8601 string (e.g. `P1YT2H`).
 impl fmt::Display for IsoDuration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+        let mut s = String::from("P");
+        if self.years > 0 { s.push_str(&format!("{}Y", self.years)); }
+        if self.months > 0 { s.push_str(&format!("{}M", self.months)); }
+        if self.days > 0 { s.push_str(&format!("{}D", self.days)); }
+
+        if self.hours > 0 || self.minutes > 0 || self.seconds > 0 || self.nanos > 0 {
+            s.push('T');
+            if self.hours > 0 { s.push_str(&format!("{}H", self.hours)); }
+            if self.minutes > 0 { s.push_str(&format!("{}M", self.minutes)); }
+            if self.nanos > 0 {
+                let fraction = format!("{:09}", self.nanos);
+                s.push_str(&format!("{}.{}S", self.seconds, fraction.trim_end_matches('0')));
+            } else if self.seconds > 0 {
+                s.push_str(&format!("{}S", self.seconds));
+            }
+        }
+
+        // Edge case: empty duration P0D
+        if s == "P" { s.push_str("0D"); }
+        write!(f, "{}", s)
     }
 }
 
@@ -284,6 +777,40 @@ mod tests {
         assert_eq!(iso.date.minute, 30);
     }
 
+    #[test]
+    fn test_parse_week_dates() {
+        let iso = Iso8601::parse("2023-W47-4T14:30:00").unwrap();
+        assert_eq!(iso, Iso8601::parse("2023-11-23T14:30:00").unwrap());
+        assert_eq!(Iso8601::parse("2023W474T143000").unwrap(), iso);
+        assert_eq!(Iso8601::parse("2020-W53-5T00:00").unwrap().to_iso8601(), "2021-01-01T00:00:00");
+        assert_eq!(Iso8601::parse("2025-W01-1T00:00").unwrap().to_iso8601(), "2024-12-30T00:00:00");
+
+        assert!(Iso8601::parse("2023-W53-1T00:00").is_err());
+        assert!(Iso8601::parse("2023-W00-1T00:00").is_err());
+        assert!(Iso8601::parse("2023-W10-8T00:00").is_err());
+        assert!(Iso8601::parse("2023-W1-1T00:00").is_err());
+        assert!(Iso8601::parse("2023W4éT00:00").is_err());
+
+        for day in 0..800 {
+            let date = Iso8601 { date: Date::from_seconds(day * 86400 + 1_600_000_000), offset: None };
+            assert_eq!(Iso8601::parse(&date.to_iso_week_string()).unwrap(), date);
+        }
+    }
+
+    #[test]
+    fn test_parse_ordinal_dates() {
+        let iso = Iso8601::parse("2024-060T08:00:00").unwrap();
+        assert_eq!(iso.to_iso8601(), "2024-02-29T08:00:00");
+        assert_eq!(Iso8601::parse("2024060T0800").unwrap(), iso);
+        assert_eq!(Iso8601::parse("2023-365T00:00").unwrap().to_iso8601(), "2023-12-31T00:00:00");
+        assert_eq!(Iso8601::parse("2024-366T00:00").unwrap().to_ordinal_string(), "2024-366T00:00:00");
+        assert_eq!(Iso8601::parse("2023-001T00:00").unwrap().to_ordinal_string(), "2023-001T00:00:00");
+
+        assert!(Iso8601::parse("2023-366T00:00").is_err());
+        assert!(Iso8601::parse("2023-000T00:00").is_err());
+        assert!(Iso8601::parse("2023-11T00:00").is_err());
+    }
+
     #[test]
     fn test_iso_output_formats() {
         let iso = Iso8601::parse("2023-11-23T14:30:00").unwrap();
@@ -291,6 +818,53 @@ mod tests {
         assert_eq!(iso.to_iso8601_basic(), "20231123T143000");
     }
 
+    #[test]
+    fn test_parse_offsets() {
+        let utc = Iso8601::parse("2023-11-23T14:30:00Z").unwrap();
+        assert_eq!(utc.offset, Some(UtcOffset::UTC));
+        assert_eq!(utc.to_iso8601(), "2023-11-23T14:30:00Z");
+
+        let est = Iso8601::parse("20231123T093000-0500").unwrap();
+        assert_eq!(est.offset, Some(UtcOffset { sign: -1, hours: 5, minutes: 0 }));
+        assert_eq!(est.to_iso8601_basic(), "20231123T093000-0500");
+        assert_eq!(est.to_utc(), utc);
+        assert_eq!(Iso8601::parse("2023-W47-4T15:30:00+01").unwrap().to_utc(), utc);
+        assert_eq!(Iso8601::parse("2023-327T14:30:00").unwrap().to_utc(), utc);
+        assert_eq!(est.to_posix(LeapSecond::Reject).unwrap(), utc.to_posix(LeapSecond::Reject).unwrap());
+
+        assert!(Iso8601::parse("2023-11-23T14:30:00+1").is_err());
+        assert!(Iso8601::parse("2023-11-23T14:30:00+24:00").is_err());
+        assert!(Iso8601::parse("2023-11-23T14:30:00+01:00Z").is_err());
+    }
+
+    #[test]
+    fn test_add_and_sub_duration() {
+        let iso = Iso8601::parse("2023-12-31T23:59:30").unwrap();
+        let dur = IsoDuration::parse("PT45S").unwrap();
+        assert_eq!(iso.add(&dur).to_iso8601(), "2024-01-01T00:00:15");
+        assert_eq!(iso.add(&dur).sub(&dur), iso);
+
+        // Leap years: Feb 29 + 1 year clamps, + 4 years does not.
+        let leap = Iso8601::parse("2024-02-29T12:00:00").unwrap();
+        assert_eq!(leap.add(&IsoDuration { years: 1, ..Default::default() }).to_iso8601(), "2025-02-28T12:00:00");
+        assert_eq!(leap.add(&IsoDuration { years: 4, ..Default::default() }).to_iso8601(), "2028-02-29T12:00:00");
+        assert_eq!(leap.sub(&IsoDuration { days: 60, ..Default::default() }).to_iso8601(), "2023-12-31T12:00:00");
+
+        // Month-end policies.
+        let year = IsoDuration { years: 1, ..Default::default() };
+        assert_eq!(leap.add_with(&year, MonthEnd::ClampToMonthEnd).unwrap(), leap.add(&year));
+        assert_eq!(leap.add_with(&year, MonthEnd::Overflow).unwrap().to_iso8601(), "2025-03-01T12:00:00");
+        assert!(leap.sub_with(&year, MonthEnd::Reject).is_err());
+        let month_and_hour = IsoDuration::parse("P1MT1H").unwrap();
+        let end = Iso8601::parse("2024-03-31T00:30:00").unwrap();
+        assert_eq!(end.sub_with(&month_and_hour, MonthEnd::Overflow).unwrap().to_iso8601(), "2024-03-01T23:30:00");
+        assert_eq!(end.sub_with(&month_and_hour, MonthEnd::ClampToMonthEnd).unwrap(), end.sub(&month_and_hour));
+
+        // Large fields carry across months and years.
+        let big = IsoDuration { months: 14, hours: 48, minutes: 90, ..Default::default() };
+        assert_eq!(iso.add(&big).to_iso8601(), "2025-03-03T01:29:30");
+    }
+
     #[test]
     fn test_duration_parsing_full() {
         let raw = "P3Y6M4DT12H30M5S";
@@ -322,10 +896,60 @@ mod tests {
     }
 
     #[test]
+    fn test_duration_std_conversion() {
+        let dur = IsoDuration::parse("P1Y2M3DT4H5M6S").unwrap();
+        assert_eq!(dur.to_std(None).as_secs(), 31_556_952 + 2 * 2_629_746 + 3 * 86_400 + 4 * 3600 + 5 * 60 + 6);
+
+        let leap = Iso8601::parse("2024-01-31T12:00:00").unwrap().date;
+        let year = IsoDuration::parse("P1Y").unwrap();
+        assert_eq!(year.to_std(Some(&leap)).as_secs(), 366 * 86_400);
+        assert_eq!(IsoDuration::parse("P1M").unwrap().to_std(Some(&leap)).as_secs(), 29 * 86_400);
+
+        let exact = IsoDuration::parse("P3DT4H5M6S").unwrap();
+        assert_eq!(IsoDuration::from_std(exact.to_std(None)), exact);
+        assert_eq!(IsoDuration::from_std(Duration::ZERO).to_string(), "P0D");
+        assert_eq!(IsoDuration::from_std(Duration::MAX).days, u32::MAX);
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_duration_formatting() {
         let mut dur = IsoDuration::default();
         dur.years = 1;
         dur.hours = 2;
         assert_eq!(dur.to_string(), "P1YT2H");
     }
+
+    #[test]
+    fn test_duration_humanize() {
+        let dur = IsoDuration::parse("P1Y2M3DT4H5M6S").unwrap();
+        assert_eq!(dur.humanize(), "1 year 2 months");
+        let all = HumanizeOptions { max_components: 6, ..Default::default() };
+        assert_eq!(dur.humanize_with(all), "1 year 2 months 3 days 4h 5m 6s");
+        let one = HumanizeOptions { max_components: 0, ..Default::default() };
+        assert_eq!(dur.humanize_with(one), "1 year");
+
+        assert_eq!(IsoDuration::parse("P1DT5S").unwrap().humanize(), "1 day 5s");
+        assert_eq!(IsoDuration::parse("PT3725S").unwrap().humanize_with(all), "1h 2m 5s");
+        assert_eq!(IsoDuration::parse("PT48H").unwrap().humanize(), "48h");
+        assert_eq!(IsoDuration::parse("PT0.5S").unwrap().humanize(), "0s");
+        assert_eq!(IsoDuration::default().humanize(), "0s");
+    }
+
+    #[test]
+    fn test_duration_fractions() {
+        let half = IsoDuration::parse("PT0.5S").unwrap();
+        assert_eq!(half, IsoDuration { nanos: 500_000_000, ..Default::default() });
+        assert_eq!(half.to_string(), "PT0.5S");
+        assert_eq!(half.to_std(None), Duration::from_millis(500));
+
+        assert_eq!(IsoDuration::parse("P1DT1,25H").unwrap().to_string(), "P1DT1H15M");
+        assert_eq!(IsoDuration::parse("P0.5D").unwrap().to_string(), "PT12H");
+        assert_eq!(IsoDuration::parse("PT1.000000001S").unwrap().nanos, 1);
+        assert_eq!(IsoDuration::parse("PT2.5M").unwrap().to_string(), "PT2M30S");
+
+        for raw in ["P0.5Y", "P1.5M", "PT0.5H1M", "PT.5S", "PT1.S", "PT1.2.3S", "P1.5DT1H"] {
+            assert!(IsoDuration::parse(raw).is_err(), "{}", raw);
+        }
+    }
 }
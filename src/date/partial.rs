@@ -0,0 +1,191 @@
+use std::fmt;
+use crate::date::date::Date;
+use crate::date::iso8601::Iso8601;
+
+/// A calendar day without a time, such as a birthday or a due date.
+///
+/// Dates order chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateOnly {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// A time of day without a date, such as an opening hour.
+///
+/// Times order chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeOnly {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateOnly {
+    /// Parses an ISO 8601 date without a time (e.g., "2023-11-23").
+    ///
+    /// Accepts the same date forms as `Iso8601::parse`: `2023-11-23`,
+    /// `20231123`, week dates (`2023-W47-4`), and ordinal dates (`2023-327`).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the string is malformed or represents an
+    /// invalid calendar date (e.g., February 30th).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::partial::DateOnly;
+    /// let date = DateOnly::parse("2023-11-23").unwrap();
+    /// assert_eq!(date, DateOnly::parse("20231123").unwrap());
+    /// assert_eq!(date, DateOnly::parse("2023-W47-4").unwrap());
+    /// assert!(DateOnly::parse("2023-02-30").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if s.is_empty() { return Err("String is empty".into()); }
+
+        let (year, month, day) = Iso8601::parse_date_part(s)?;
+        let date = DateOnly { year, month, day };
+        if !date.and_time(TimeOnly::MIDNIGHT).is_valid() {
+            return Err("Semantically invalid date".into());
+        }
+        Ok(date)
+    }
+
+    /// Combines the day with a time of day into a full `Date`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::partial::{DateOnly, TimeOnly};
+    /// let date = DateOnly::parse("2023-11-23").unwrap();
+    /// let time = TimeOnly::parse("14:30").unwrap();
+    /// assert_eq!(date.and_time(time).hour, 14);
+    /// ```
+    pub fn and_time(self, time: TimeOnly) -> Date {
+        Date {
+            year: self.year,
+            month: self.month,
+            day: self.day,
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+        }
+    }
+
+    /// Returns the ISO 8601 extended representation (`2023-11-23`).
+    pub fn to_iso8601(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl TimeOnly {
+    /// The start of the day, `00:00:00`.
+    pub const MIDNIGHT: TimeOnly = TimeOnly { hour: 0, minute: 0, second: 0 };
+
+    /// Parses an ISO 8601 time without a date (e.g., "14:30:00").
+    ///
+    /// Accepts `14:30:00`, `14:30`, `143000`, and `1430`, optionally preceded
+    /// by the `T` designator (`T1430`). A leap second (`:60`) is allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the string is malformed or a field is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::partial::TimeOnly;
+    /// let time = TimeOnly::parse("T1430").unwrap();
+    /// assert_eq!(time, TimeOnly::parse("14:30:00").unwrap());
+    /// assert_eq!(time.to_string(), "14:30:00");
+    /// assert!(TimeOnly::parse("24:00").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.strip_prefix('T').unwrap_or(s);
+        if s.is_empty() { return Err("String is empty".into()); }
+
+        let (hour, minute, second) = Iso8601::parse_time_part(s)?;
+        if hour > 23 || minute > 59 || second > 60 {
+            return Err("Semantically invalid time".into());
+        }
+        Ok(TimeOnly { hour, minute, second })
+    }
+
+    /// Returns the ISO 8601 extended representation (`14:30:00`).
+    pub fn to_iso8601(&self) -> String {
+        format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+/// Takes the calendar day of a `Date`.
+impl From<Date> for DateOnly {
+    fn from(date: Date) -> Self {
+        DateOnly { year: date.year, month: date.month, day: date.day }
+    }
+}
+
+/// Takes the time of day of a `Date`.
+impl From<Date> for TimeOnly {
+    fn from(date: Date) -> Self {
+        TimeOnly { hour: date.hour, minute: date.minute, second: date.second }
+    }
+}
+
+/// Converts to a `Date` at midnight.
+impl From<DateOnly> for Date {
+    fn from(date: DateOnly) -> Self {
+        date.and_time(TimeOnly::MIDNIGHT)
+    }
+}
+
+impl fmt::Display for DateOnly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_iso8601())
+    }
+}
+
+impl fmt::Display for TimeOnly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_iso8601())
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date_only() {
+        let date = DateOnly { year: 2024, month: 2, day: 29 };
+        for raw in ["2024-02-29", "20240229", "2024-060", "2024-W09-4"] {
+            assert_eq!(DateOnly::parse(raw), Ok(date), "{}", raw);
+        }
+        for raw in ["", "2023-02-29", "2024-13-01", "2024-02", "2024-02-29T00:00"] {
+            assert!(DateOnly::parse(raw).is_err(), "{}", raw);
+        }
+        assert_eq!(date.to_string(), "2024-02-29");
+    }
+
+    #[test]
+    fn test_parse_time_only() {
+        assert_eq!(TimeOnly::parse("23:59:60"), Ok(TimeOnly { hour: 23, minute: 59, second: 60 }));
+        assert_eq!(TimeOnly::parse("T0905"), Ok(TimeOnly { hour: 9, minute: 5, second: 0 }));
+        assert_eq!(TimeOnly::parse("090507").unwrap().to_string(), "09:05:07");
+        for raw in ["", "T", "12", "12:60", "123", "12:00:61", "1430Z"] {
+            assert!(TimeOnly::parse(raw).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        let full = Iso8601::parse("2023-11-23T14:30:05").unwrap().date;
+        let (date, time) = (DateOnly::from(full), TimeOnly::from(full));
+        assert_eq!(date.and_time(time), full);
+        assert_eq!(Date::from(date), Iso8601::parse("2023-11-23T00:00:00").unwrap().date);
+        assert!(TimeOnly::MIDNIGHT < time);
+    }
+}
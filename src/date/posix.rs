@@ -1,17 +1,27 @@
 use std::fmt;
-use crate::date::date::Date;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::date::clock::Clock;
+use crate::date::date::{Date, LeapSecond, RangeError};
+use crate::date::format;
+use crate::date::rcf3339::UtcOffset;
 
 /// A wrapper structure for POSIX (Unix Timestamp) handling.
 /// 
-/// This struct wraps a `Date` object (business logic).
+/// This struct wraps a `Date` object (business logic). `nanos` keeps the
+/// sub-second part of millisecond and microsecond timestamps.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Posix {
     pub date: Date,
+    pub nanos: u32,
 }
 
 impl Posix {
     /// Manual parser for POSIX timestamp strings (e.g., "1700749800").
     ///
+    /// Negative timestamps count back from the epoch, so `"-86400"` is
+    /// 1969-12-31 00:00:00. Timestamps of 13 digits are read as milliseconds
+    /// and of 16 digits as microseconds, as exchanged by JavaScript and Java.
+    ///
     /// # Arguments
     ///
     /// * `s` - A string slice that holds the numeric timestamp.
@@ -19,8 +29,7 @@ impl Posix {
     /// # Errors
     ///
     /// Returns a `Result::Err` if the string contains non-numeric characters
-    /// or represents a timestamp that cannot be represented (e.g., negative 
-    /// timestamps are not supported by this implementation for simplicity).
+    /// or represents a timestamp that cannot be represented.
     ///
     /// # Examples
     ///
@@ -29,81 +38,126 @@ impl Posix {
     /// // 1700749800 is approx Nov 23 2023
     /// let posix = Posix::parse("1700749800").unwrap();
     /// assert_eq!(posix.date.year, 2023);
+    ///
+    /// let posix = Posix::parse("-86400").unwrap();
+    /// assert_eq!(posix.to_human_string(), "1969-12-31 00:00:00 UTC");
+    ///
+    /// let posix = Posix::parse("1700749800123").unwrap();
+    /// assert_eq!(posix.to_millis(), 1700749800123);
     /// ```
     pub fn parse(s: &str) -> Result<Self, String> {
         let timestamp = s.parse::<i64>()
             .map_err(|_| format!("Invalid timestamp format: {}", s))?;
 
-        if timestamp < 0 {
-            return Err("Negative timestamps (pre-1970) are not supported".into());
+        match s.trim_start_matches(['+', '-']).len() {
+            13 => Self::from_millis(timestamp),
+            16 => Self::from_micros(timestamp),
+            _ => Self::from_timestamp(timestamp),
         }
-
-        Self::from_timestamp(timestamp)
     }
 
     /// Constructs a Posix object from a raw integer.
     ///
     /// # Arguments
     ///
-    /// * `ts` - Seconds since Jan 1 1970.
+    /// * `ts` - Seconds since Jan 1 1970 (negative before it).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the year falls outside the range of `i32`.
     pub fn from_timestamp(ts: i64) -> Result<Self, String> {
-        // Constants
-        const SECONDS_PER_MINUTE: i64 = 60;
-        const SECONDS_PER_HOUR: i64 = 3600;
-        const SECONDS_PER_DAY: i64 = 86400;
-
-        // Calculate time of day
-        let mut remaining = ts;
-        let days_since_epoch = remaining / SECONDS_PER_DAY;
-        remaining %= SECONDS_PER_DAY;
-
-        let hour = (remaining / SECONDS_PER_HOUR) as u8;
-        remaining %= SECONDS_PER_HOUR;
-        let minute = (remaining / SECONDS_PER_MINUTE) as u8;
-        let second = (remaining % SECONDS_PER_MINUTE) as u8;
-
-        // Calculate Year and Day of Year
-        let mut year = 1970;
-        let mut days = days_since_epoch;
-
-        loop {
-            let days_in_year = if Self::is_leap_year(year) { 366 } else { 365 };
-            if days < days_in_year {
-                break;
-            }
-            days -= days_in_year;
-            year += 1;
-        }
-
-        // Calculate Month and Day of Month
-        // `days` is now the 0-indexed day of the current year
-        let mut month = 1;
-        let days_in_months = if Self::is_leap_year(year) {
-            [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-        } else {
-            [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-        };
-
-        for &dim in days_in_months.iter() {
-            if days < dim {
-                break;
-            }
-            days -= dim;
-            month += 1;
-        }
+        Ok(Posix {
+            date: Date::from_unix(ts).map_err(|e| e.to_string())?,
+            nanos: 0,
+        })
+    }
 
-        // `days` is 0-indexed, so add 1 for the calendar day
-        let day = (days + 1) as u8;
+    /// Constructs a Posix object from milliseconds since Jan 1 1970.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the year falls outside the range of `i32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::posix::Posix;
+    /// let posix = Posix::from_millis(-1).unwrap();
+    /// assert_eq!(posix.to_human_string(), "1969-12-31 23:59:59 UTC");
+    /// assert_eq!(posix.nanos, 999_000_000);
+    /// ```
+    pub fn from_millis(ms: i64) -> Result<Self, String> {
+        Self::from_subsec(ms, 1_000)
+    }
 
-        let date = Date {
-            year, month, day, hour, minute, second
-        };
+    /// Constructs a Posix object from microseconds since Jan 1 1970.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the year falls outside the range of `i32`.
+    pub fn from_micros(us: i64) -> Result<Self, String> {
+        Self::from_subsec(us, 1_000_000)
+    }
 
+    /// Constructs a Posix object from a UTC `Date`, resolving a leap second
+    /// (`:60`) with `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `policy` is `LeapSecond::Reject` and the date
+    /// has a leap second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::LeapSecond;
+    /// use stdt::date::posix::Posix;
+    /// use stdt::date::rcf3339::Rfc3339;
+    /// let leap = Rfc3339::parse("2016-12-31T23:59:60Z").unwrap().date;
+    /// assert_eq!(Posix::from_date(leap, LeapSecond::Clamp).unwrap().to_string_timestamp(), "1483228799");
+    /// assert_eq!(Posix::from_date(leap, LeapSecond::Carry).unwrap().to_string_timestamp(), "1483228800");
+    /// ```
+    pub fn from_date(date: Date, policy: LeapSecond) -> Result<Self, String> {
         Ok(Posix {
-            date,
+            date: date.resolve_leap_second(policy)?,
+            nanos: 0,
         })
     }
 
+    /// Returns the current time from the system clock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::posix::Posix;
+    /// let now = Posix::now();
+    /// assert!(now.date.year >= 2024);
+    /// ```
+    pub fn now() -> Self {
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => Posix {
+                date: Date::from_seconds(elapsed.as_secs() as i64),
+                nanos: elapsed.subsec_nanos(),
+            },
+            // The clock is set before 1970
+            Err(_) => Posix { date: Date::now_utc(), nanos: 0 },
+        }
+    }
+
+    /// Returns the current time from `clock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::clock::MockClock;
+    /// use stdt::date::posix::Posix;
+    /// let clock = MockClock::new(Posix::parse("1700749800").unwrap());
+    /// assert_eq!(Posix::now_with(&clock).timestamp(), 1_700_749_800);
+    /// ```
+    pub fn now_with(clock: &dyn Clock) -> Self {
+        clock.now()
+    }
+
     /// Returns a custom "Human Readable" string representation.
     ///
     /// Format: `YYYY-MM-DD HH:MM:SS UTC`
@@ -121,6 +175,19 @@ impl Posix {
             self.date.hour, self.date.minute, self.date.second)
     }
 
+    /// Returns the timestamp in seconds since Jan 1 1970 (negative before it).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::posix::Posix;
+    /// assert_eq!(Posix::parse("1700749800").unwrap().timestamp(), 1_700_749_800);
+    /// assert_eq!(Posix::from_millis(-1).unwrap().timestamp(), -1);
+    /// ```
+    pub fn timestamp(&self) -> i64 {
+        self.date.to_unix()
+    }
+
     /// Returns the raw timestamp as a string.
     ///
     /// # Examples
@@ -131,34 +198,78 @@ impl Posix {
     /// assert_eq!(posix.to_string_timestamp(), "1700749800");
     /// ```
     pub fn to_string_timestamp(&self) -> String {
-        let mut total_days: i64 = 0;
-        
-        // Add days for past years
-        for y in 1970..self.date.year {
-             total_days += if Self::is_leap_year(y) { 366 } else { 365 };
-        }
+        self.timestamp().to_string()
+    }
 
-        // Add days for past months in current year
-        let days_in_months: [i64; 12] = if Self::is_leap_year(self.date.year) {
-            [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-        } else {
-            [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-        };
+    /// Returns the timestamp in milliseconds since Jan 1 1970.
+    ///
+    /// The result overflows for years beyond the range of `checked_to_millis`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::posix::Posix;
+    /// assert_eq!(Posix::from_micros(1_700_749_800_123_456).unwrap().to_millis(), 1_700_749_800_123);
+    /// assert_eq!(Posix::parse("1700749800").unwrap().to_millis(), 1_700_749_800_000);
+    /// ```
+    pub fn to_millis(&self) -> i64 {
+        self.timestamp() * 1_000 + (self.nanos / 1_000_000) as i64
+    }
 
-        for i in 0..(self.date.month - 1) as usize {
-            total_days += days_in_months[i];
-        }
+    /// Returns the timestamp in microseconds since Jan 1 1970.
+    ///
+    /// The result overflows for years beyond the range of `checked_to_micros`.
+    pub fn to_micros(&self) -> i64 {
+        self.timestamp() * 1_000_000 + (self.nanos / 1_000) as i64
+    }
 
-        // Add days in current month (1-indexed -> 0-indexed)
-        total_days += (self.date.day - 1) as i64;
+    /// Same as `to_millis`, for dates of any year. Milliseconds fit in an
+    /// `i64` for about 292 million years either side of 1970.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RangeError::Overflow` if the timestamp does not fit in an `i64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::posix::Posix;
+    /// assert_eq!(Posix::from_millis(-1).unwrap().checked_to_millis(), Ok(-1));
+    /// assert!(Posix::from_timestamp(i64::MAX / 1_000 + 1).unwrap().checked_to_millis().is_err());
+    /// ```
+    pub fn checked_to_millis(&self) -> Result<i64, RangeError> {
+        self.checked_to_subsec(1_000, "milliseconds")
+    }
 
-        // Convert to seconds
-        let timestamp = total_days * 86400 
-            + (self.date.hour as i64) * 3600 
-            + (self.date.minute as i64) * 60 
-            + (self.date.second as i64);
+    /// Same as `to_micros`, for dates of any year. Microseconds fit in an
+    /// `i64` for about 292,000 years either side of 1970.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RangeError::Overflow` if the timestamp does not fit in an `i64`.
+    pub fn checked_to_micros(&self) -> Result<i64, RangeError> {
+        self.checked_to_subsec(1_000_000, "microseconds")
+    }
 
-        timestamp.to_string()
+    /// Formats the date with strftime-style `%` specifiers.
+    ///
+    /// Unlike `format`, text outside specifiers is copied as-is, so words
+    /// like `MM` or `dd` are never replaced. See the supported specifiers
+    /// below; `%%` writes a literal `%`.
+    ///
+    /// # Supported Specifiers
+    ///
+    /// `%Y %y %C %m %b %B %d %e %j %a %A %u %w %G %V %H %I %p %M %S %z %:z %s %F %T %n %t %%`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::posix::Posix;
+    /// let posix = Posix::parse("1700000000").unwrap();
+    /// assert_eq!(posix.strftime("%a %d %b %Y, %H:%M %z (MM/dd)"), "Tue 14 Nov 2023, 22:13 +0000 (MM/dd)");
+    /// ```
+    pub fn strftime(&self, pattern: &str) -> String {
+        format::strftime(&self.date, UtcOffset::UTC, pattern)
     }
 
     /// Manual formatting via pattern replacement.
@@ -169,7 +280,13 @@ impl Posix {
     /// * `yy`   = Short Year (23)
     /// * `mm`   = Month (01-12)
     /// * `dd`   = Day (01-31)
+    /// * `DDD`  = Day of the year (001-366)
+    /// * `ddd`  = Short weekday name (Thu)
+    /// * `dddd` = Weekday name (Thursday)
+    /// * `ww`   = ISO week (01-53)
     /// * `HH`   = Hour (00-23)
+    /// * `hh`   = Hour (01-12)
+    /// * `tt`   = AM/PM
     /// * `MM`   = Minute (00-59)
     /// * `SS`   = Second (00-59)
     /// * `TS`   = Raw Timestamp
@@ -182,28 +299,33 @@ impl Posix {
     /// let formatted = posix.format("At TS, date was dd/mm/yy");
     /// assert_eq!(formatted, "At 1700000000, date was 14/11/23");
     /// ```
+    ///
+    /// Tokens are replaced wherever they appear, including inside words;
+    /// prefer [`Posix::strftime`] for patterns with literal text.
     pub fn format(&self, pattern: &str) -> String {
-        pattern
-            .replace("YYYY", &format!("{:04}", self.date.year))
-            .replace("yy",   &format!("{:02}", self.date.year % 100))
-            .replace("mm",   &format!("{:02}", self.date.month))
-            .replace("dd",   &format!("{:02}", self.date.day))
-            .replace("HH",   &format!("{:02}", self.date.hour))
-            .replace("MM",   &format!("{:02}", self.date.minute))
-            .replace("SS",   &format!("{:02}", self.date.second))
-            .replace("TS",   &self.to_string_timestamp())
+        format::format_tokens(&self.date, Some(self.timestamp()), pattern)
     }
 
     // --- Internal Helpers ---
 
-    fn is_leap_year(y: i32) -> bool {
-        (y % 4 == 0 && y % 100 != 0) || (y % 400 == 0)
+    /// Combines seconds and nanoseconds into a timestamp with `per_second` units per second.
+    fn checked_to_subsec(&self, per_second: i64, unit: &'static str) -> Result<i64, RangeError> {
+        // Widen first: near `i64::MIN` the whole seconds overflow before the fraction is added.
+        let units = self.timestamp() as i128 * per_second as i128 + (self.nanos as i64 / (1_000_000_000 / per_second)) as i128;
+        i64::try_from(units).map_err(|_| RangeError::Overflow { year: self.date.year, unit })
+    }
+
+    /// Splits a timestamp with `per_second` units per second into seconds and nanoseconds.
+    fn from_subsec(value: i64, per_second: i64) -> Result<Self, String> {
+        let mut posix = Self::from_timestamp(value.div_euclid(per_second))?;
+        posix.nanos = (value.rem_euclid(per_second) * (1_000_000_000 / per_second)) as u32;
+        Ok(posix)
     }
 }
 
 impl fmt::Display for Posix {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string_timestamp())
+        write!(f, "{}", self.timestamp())
     }
 }
 
@@ -262,10 +384,45 @@ mod tests {
 
     #[test]
     fn test_parse_negative_timestamp() {
-        let raw = "-100";
-        let res = Posix::parse(raw);
-        assert!(res.is_err());
-        assert!(res.unwrap_err().contains("Negative timestamps"));
+        let posix = Posix::parse("-1").expect("Should parse pre-epoch second");
+        assert_eq!(posix.to_human_string(), "1969-12-31 23:59:59 UTC");
+
+        // 0001-01-01 00:00:00 UTC
+        let posix = Posix::parse("-62135596800").expect("Should parse year 1");
+        assert_eq!(posix.date, Date { year: 1, month: 1, day: 1, hour: 0, minute: 0, second: 0 });
+
+        for ts in [-1, -86399, -86400, -86401, -2208988800, -62135596800, -62135596801, 951782400] {
+            assert_eq!(Posix::from_timestamp(ts).unwrap().timestamp(), ts);
+        }
+        assert!(Posix::from_timestamp(i64::MIN).unwrap_err().contains("out of range"));
+    }
+
+    #[test]
+    fn test_millis_and_micros() {
+        let posix = Posix::parse("1700749800123").expect("Should detect milliseconds");
+        assert_eq!(posix.date, Posix::parse("1700749800").unwrap().date);
+        assert_eq!(posix.nanos, 123_000_000);
+        assert_eq!(posix.to_string_timestamp(), "1700749800");
+
+        let posix = Posix::parse("1700749800123456").expect("Should detect microseconds");
+        assert_eq!(posix.to_micros(), 1700749800123456);
+        assert_eq!(posix.to_millis(), 1700749800123);
+
+        let posix = Posix::parse("-0000000001500").unwrap();
+        assert_eq!(posix.to_human_string(), "1969-12-31 23:59:58 UTC");
+        for ms in [-1, 0, 999, -86_400_001, -62135596800000] {
+            assert_eq!(Posix::from_millis(ms).unwrap().to_millis(), ms);
+        }
+        assert!(Posix::from_micros(i64::MIN).is_ok());
+
+        for us in [i64::MIN, i64::MAX] {
+            let posix = Posix::from_micros(us).unwrap();
+            assert_eq!(posix.checked_to_micros(), Ok(us));
+            assert_eq!(posix.checked_to_millis(), Ok(us.div_euclid(1_000)));
+        }
+        let far = Posix::from_timestamp(i64::MAX / 1_000 + 1).unwrap();
+        assert!(far.checked_to_micros().is_err());
+        assert_eq!(far.checked_to_millis(), Err(RangeError::Overflow { year: far.date.year, unit: "milliseconds" }));
     }
 
     #[test]
@@ -275,6 +432,15 @@ mod tests {
 
         let pattern = "TS -> YYYY/mm/dd";
         assert_eq!(posix.format(pattern), "1234567890 -> 2009/02/13");
+        assert_eq!(posix.format("dddd (ddd) dd"), "Friday (Fri) 13");
+    }
+
+    #[test]
+    fn test_now_matches_system_clock() {
+        let before = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        let now = Posix::now().timestamp() as u64;
+        let after = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        assert!((before..=after).contains(&now));
     }
 
     #[test]
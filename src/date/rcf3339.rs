@@ -1,15 +1,96 @@
 use std::fmt;
-use crate::date::date::Date;
+use crate::date::clock::{Clock, SystemClock};
+use crate::date::date::{Date, LeapSecond};
+use crate::date::format;
+use crate::date::posix::Posix;
 
 
 /// A wrapper structure for RFC3339 handling.
 /// 
 /// This struct wraps a `Date` object (business logic) and adds RFC3339 specific
-/// context like the offset string.
+/// context like the UTC offset. `date` holds the local time at that offset.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rfc3339 {
     pub date: Date,
-    pub offset_str: Option<&'static str>, 
+    pub offset: UtcOffset,
+}
+
+/// A numeric offset from UTC, such as the `+02:00` in `2023-11-23T14:30:00+02:00`.
+///
+/// `sign` is `1` for offsets east of UTC (and for UTC itself) and `-1` for
+/// offsets west of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtcOffset {
+    pub sign: i8,
+    pub hours: u8,
+    pub minutes: u8,
+}
+
+impl UtcOffset {
+    /// The zero offset, written `Z`.
+    pub const UTC: UtcOffset = UtcOffset { sign: 1, hours: 0, minutes: 0 };
+
+    /// Parses an offset suffix: `Z`, `+HH:MM`, `-HH:MM`, `+HHMM`, or `-HHMM`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if the suffix has another shape or its hours or
+    /// minutes are out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rcf3339::UtcOffset;
+    /// let offset = UtcOffset::parse("-0500").unwrap();
+    /// assert_eq!(offset, UtcOffset { sign: -1, hours: 5, minutes: 0 });
+    /// assert_eq!(offset.to_string(), "-05:00");
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if s == "Z" || s == "z" { return Ok(UtcOffset::UTC); }
+
+        let sign = match s.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return Err(format!("Invalid offset: {}", s)),
+        };
+        let digits = match (s.len(), s.as_bytes().get(3)) {
+            _ if !s.is_ascii() => return Err(format!("Invalid offset: {}", s)),
+            (6, Some(b':')) => [&s[1..3], &s[4..6]],
+            (5, _) => [&s[1..3], &s[3..5]],
+            _ => return Err(format!("Invalid offset: {}", s)),
+        };
+        let parse_num = |str_slice: &str| str_slice.parse::<u8>()
+            .ok()
+            .filter(|_| str_slice.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(|| format!("Invalid offset: {}", s));
+
+        let hours = parse_num(digits[0])?;
+        let minutes = parse_num(digits[1])?;
+        if hours > 23 || minutes > 59 {
+            return Err(format!("Offset out of range: {}", s));
+        }
+        Ok(UtcOffset { sign, hours, minutes })
+    }
+
+    /// Returns the offset in seconds, negative west of UTC.
+    pub fn total_seconds(&self) -> i64 {
+        self.sign as i64 * (self.hours as i64 * 3600 + self.minutes as i64 * 60)
+    }
+
+    /// Builds an offset from whole minutes of `secs`, negative west of UTC.
+    pub(crate) fn from_seconds(secs: i64) -> Self {
+        let minutes = secs.abs() / 60;
+        UtcOffset {
+            sign: if secs < 0 { -1 } else { 1 },
+            hours: (minutes / 60) as u8,
+            minutes: (minutes % 60) as u8,
+        }
+    }
+
+    /// Returns `true` for the zero offset.
+    pub fn is_utc(&self) -> bool {
+        self.hours == 0 && self.minutes == 0
+    }
 }
 
 impl Rfc3339 {
@@ -22,7 +103,10 @@ impl Rfc3339 {
     /// # Errors
     ///
     /// Returns a `Result::Err` if the string is too short, contains non-numeric characters
-    /// in date fields, or represents an invalid calendar date (e.g., February 30th).
+    /// in date fields, has a malformed offset, or represents an invalid calendar date
+    /// (e.g., February 30th).
+    ///
+    /// A missing offset is read as UTC. Fractional seconds are accepted and dropped.
     ///
     /// # Examples
     ///
@@ -31,13 +115,18 @@ impl Rfc3339 {
     /// let rfc = Rfc3339::parse("2023-11-23T14:30:00Z").unwrap();
     /// assert_eq!(rfc.date.year, 2023);
     /// assert_eq!(rfc.date.month, 11);
+    ///
+    /// let local = Rfc3339::parse("2023-11-23T14:30:00.250+02:00").unwrap();
+    /// assert_eq!(local.offset.hours, 2);
+    /// assert_eq!(local.to_utc().to_rfc3339(), "2023-11-23T12:30:00Z");
     /// ```
     pub fn parse(s: &str) -> Result<Self, String> {
         if s.len() < 19 { return Err("String too short".into()); }
 
         // Helper for parsing numeric slices
-        let parse_part = |start, end| s[start..end].parse::<u32>()
-            .map_err(|_| format!("Error parsing number between indices {} and {}", start, end));
+        let parse_part = |start, end| s.get(start..end)
+            .and_then(|part| part.parse::<u32>().ok())
+            .ok_or_else(|| format!("Error parsing number between indices {} and {}", start, end));
 
         let year = parse_part(0, 4)? as i32;
         let month = parse_part(5, 7)? as u8;
@@ -46,21 +135,114 @@ impl Rfc3339 {
         let minute = parse_part(14, 16)? as u8;
         let second = parse_part(17, 19)? as u8;
 
+        let date = Date {
+            year, month, day, hour, minute, second
+        };
+
         // Logical validation (Months, days, leap years)
-        if !Self::is_valid_calendar(year, month, day, hour, minute, second) {
+        if !date.is_valid() {
             return Err("Semantically invalid date".into());
         }
 
-        let date = Date {
-            year, month, day, hour, minute, second
+        // Skip fractional seconds, then read the offset suffix
+        let rest = &s[19..];
+        let rest = match rest.strip_prefix('.') {
+            Some(frac) => frac.trim_start_matches(|c: char| c.is_ascii_digit()),
+            None => rest,
         };
+        let offset = if rest.is_empty() { UtcOffset::UTC } else { UtcOffset::parse(rest)? };
 
         Ok(Rfc3339 {
             date,
-            offset_str: None, // Offset handling omitted for simplicity
+            offset,
         })
     }
 
+    /// Returns the current time from the system clock, in UTC.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rcf3339::Rfc3339;
+    /// let now = Rfc3339::now_utc();
+    /// assert!(now.offset.is_utc());
+    /// assert!(now.to_rfc3339().ends_with('Z'));
+    /// ```
+    pub fn now_utc() -> Self {
+        Self::now_utc_with(&SystemClock)
+    }
+
+    /// Returns the current time from `clock`, in UTC. The sub-second part is dropped.
+    pub fn now_utc_with(clock: &dyn Clock) -> Self {
+        Rfc3339 { date: clock.now().date, offset: UtcOffset::UTC }
+    }
+
+    /// Returns the same instant expressed in UTC.
+    ///
+    /// A leap second carries into the next minute; use [`Rfc3339::to_utc_with`]
+    /// to choose another `LeapSecond` policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rcf3339::Rfc3339;
+    /// let rfc = Rfc3339::parse("2023-12-31T22:30:00-05:00").unwrap();
+    /// assert_eq!(rfc.to_utc().to_rfc3339(), "2024-01-01T03:30:00Z");
+    /// ```
+    pub fn to_utc(&self) -> Rfc3339 {
+        self.with_offset(UtcOffset::UTC)
+    }
+
+    /// Returns the same instant expressed at `offset`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rcf3339::{Rfc3339, UtcOffset};
+    /// let rfc = Rfc3339::parse("2023-11-23T14:30:00+01:00").unwrap();
+    /// let ist = rfc.with_offset(UtcOffset::parse("+05:30").unwrap());
+    /// assert_eq!(ist.to_rfc3339(), "2023-11-23T19:00:00+05:30");
+    /// ```
+    pub fn with_offset(&self, offset: UtcOffset) -> Rfc3339 {
+        Rfc3339 {
+            date: Date::from_seconds(self.date.to_seconds() - self.offset.total_seconds() + offset.total_seconds()),
+            offset,
+        }
+    }
+
+    /// Returns the same instant expressed in UTC, resolving a leap second
+    /// (`:60`) with `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `policy` is `LeapSecond::Reject` and the date
+    /// has a leap second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::date::LeapSecond;
+    /// use stdt::date::rcf3339::Rfc3339;
+    /// let rfc = Rfc3339::parse("2016-12-31T18:59:60-05:00").unwrap();
+    /// assert_eq!(rfc.to_utc_with(LeapSecond::Clamp).unwrap().to_rfc3339(), "2016-12-31T23:59:59Z");
+    /// assert_eq!(rfc.to_utc_with(LeapSecond::Carry).unwrap().to_rfc3339(), "2017-01-01T00:00:00Z");
+    /// assert!(rfc.to_utc_with(LeapSecond::Reject).is_err());
+    /// ```
+    pub fn to_utc_with(&self, policy: LeapSecond) -> Result<Rfc3339, String> {
+        let date = self.date.resolve_leap_second(policy)?;
+        Ok(Rfc3339 { date, offset: self.offset }.to_utc())
+    }
+
+    /// Converts to a `Posix` timestamp, resolving a leap second with `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `policy` is `LeapSecond::Reject` and the date
+    /// has a leap second.
+    pub fn to_posix(&self, policy: LeapSecond) -> Result<Posix, String> {
+        Posix::from_date(self.to_utc_with(policy)?.date, LeapSecond::Reject)
+    }
+
     /// Returns a custom "Human Readable" string representation.
     ///
     /// Format: `DD/MM/YYYY - HH:MM`
@@ -85,10 +267,33 @@ impl Rfc3339 {
     /// use stdt::date::rcf3339::Rfc3339;
     /// let rfc = Rfc3339::parse("2023-11-23T14:30:00Z").unwrap();
     /// assert_eq!(rfc.to_rfc3339(), "2023-11-23T14:30:00Z");
+    /// let rfc = Rfc3339::parse("2023-11-23T14:30:00-0530").unwrap();
+    /// assert_eq!(rfc.to_rfc3339(), "2023-11-23T14:30:00-05:30");
     /// ```
     pub fn to_rfc3339(&self) -> String {
-        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", 
-            self.date.year, self.date.month, self.date.day, self.date.hour, self.date.minute, self.date.second)
+        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}", 
+            self.date.year, self.date.month, self.date.day, self.date.hour, self.date.minute, self.date.second, self.offset)
+    }
+
+    /// Formats the date with strftime-style `%` specifiers.
+    ///
+    /// Unlike `format`, text outside specifiers is copied as-is, so words
+    /// like `MM` or `dd` are never replaced. See the supported specifiers
+    /// below; `%%` writes a literal `%`.
+    ///
+    /// # Supported Specifiers
+    ///
+    /// `%Y %y %C %m %b %B %d %e %j %a %A %u %w %G %V %H %I %p %M %S %z %:z %s %F %T %n %t %%`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rcf3339::Rfc3339;
+    /// let rfc = Rfc3339::parse("2023-11-23T14:30:05+01:00").unwrap();
+    /// assert_eq!(rfc.strftime("%A %e %B %Y %T%:z"), "Thursday 23 November 2023 14:30:05+01:00");
+    /// ```
+    pub fn strftime(&self, pattern: &str) -> String {
+        format::strftime(&self.date, self.offset, pattern)
     }
 
     /// Manual formatting via pattern replacement.
@@ -99,7 +304,13 @@ impl Rfc3339 {
     /// * `yy`   = Short Year (23)
     /// * `mm`   = Month (01-12)
     /// * `dd`   = Day (01-31)
+    /// * `DDD`  = Day of the year (001-366)
+    /// * `ddd`  = Short weekday name (Thu)
+    /// * `dddd` = Weekday name (Thursday)
+    /// * `ww`   = ISO week (01-53)
     /// * `HH`   = Hour (00-23)
+    /// * `hh`   = Hour (01-12)
+    /// * `tt`   = AM/PM
     /// * `MM`   = Minute (00-59)
     /// * `SS`   = Second (00-59)
     ///
@@ -111,30 +322,11 @@ impl Rfc3339 {
     /// let formatted = rfc.format("Today is dd/mm/yy at HH:MM");
     /// assert_eq!(formatted, "Today is 23/11/23 at 14:30");
     /// ```
+    ///
+    /// Tokens are replaced wherever they appear, including inside words;
+    /// prefer [`Rfc3339::strftime`] for patterns with literal text.
     pub fn format(&self, pattern: &str) -> String {
-        // Order is important: parse longer tokens first (YYYY before yy)
-        pattern
-            .replace("YYYY", &format!("{:04}", self.date.year))
-            .replace("yy",   &format!("{:02}", self.date.year % 100))
-            .replace("mm",   &format!("{:02}", self.date.month))
-            .replace("dd",   &format!("{:02}", self.date.day))
-            .replace("HH",   &format!("{:02}", self.date.hour))
-            .replace("MM",   &format!("{:02}", self.date.minute))
-            .replace("SS",   &format!("{:02}", self.date.second))
-    }
-
-    // --- Internal Validation Logic ---
-
-    fn is_valid_calendar(y: i32, m: u8, d: u8, h: u8, min: u8, s: u8) -> bool {
-        if m < 1 || m > 12 || h > 23 || min > 59 || s > 60 { return false; }
-        
-        let days_in_month = match m {
-            4 | 6 | 9 | 11 => 30,
-            2 => if (y % 4 == 0 && y % 100 != 0) || (y % 400 == 0) { 29 } else { 28 },
-            _ => 31,
-        };
-        
-        d >= 1 && d <= days_in_month
+        format::format_tokens(&self.date, None, pattern)
     }
 }
 
@@ -145,6 +337,15 @@ impl fmt::Display for Rfc3339 {
     }
 }
 
+/// Formats the offset as `Z` for UTC, otherwise as `+HH:MM` or `-HH:MM`.
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_utc() { return write!(f, "Z"); }
+        let sign = if self.sign < 0 { '-' } else { '+' };
+        write!(f, "{}{:02}:{:02}", sign, self.hours, self.minutes)
+    }
+}
+
 // --- Tests ---
 
 #[cfg(test)]
@@ -217,7 +418,7 @@ mod tests {
         };
         let rfc = Rfc3339 {
             date: date_struct,
-            offset_str: None
+            offset: UtcOffset::UTC
         };
         // Expecting padding: 07/05/2023 - 09:05
         assert_eq!(rfc.to_human_string(), "07/05/2023 - 09:05");
@@ -231,7 +432,7 @@ mod tests {
         };
         let rfc = Rfc3339 {
             date: date_struct,
-            offset_str: None
+            offset: UtcOffset::UTC
         };
 
         let pattern = "Date: YYYY/mm/dd Time: HH:MM:SS";
@@ -239,6 +440,44 @@ mod tests {
 
         let pattern_short = "yy-mm-dd";
         assert_eq!(rfc.format(pattern_short), "23-12-25");
+
+        assert_eq!(rfc.format("dddd, dd/mm (ddd)"), "Monday, 25/12 (Mon)");
+        assert_eq!(rfc.format("YYYY-Www, day DDD, hh:MM tt"), "2023-W52, day 359, 06:30 PM");
+    }
+
+    #[test]
+    fn test_parse_numeric_offsets() {
+        let rfc = Rfc3339::parse("2023-11-23T14:30:00+02:00").unwrap();
+        assert_eq!(rfc.offset, UtcOffset { sign: 1, hours: 2, minutes: 0 });
+        assert_eq!(rfc.date.hour, 14);
+        assert_eq!(rfc.to_rfc3339(), "2023-11-23T14:30:00+02:00");
+        assert_eq!(rfc.to_utc().date.hour, 12);
+
+        let rfc = Rfc3339::parse("2023-01-01T01:00:00.5-0530").unwrap();
+        assert_eq!(rfc.offset.total_seconds(), -(5 * 3600 + 30 * 60));
+        assert_eq!(rfc.to_utc().to_rfc3339(), "2023-01-01T06:30:00Z");
+        assert_eq!(Rfc3339::parse("2023-01-01T01:00:00+00:00").unwrap().to_rfc3339(), "2023-01-01T01:00:00Z");
+        assert!(Rfc3339::parse("2023-01-01T01:00:00").unwrap().offset.is_utc());
+    }
+
+    #[test]
+    fn test_parse_invalid_offsets() {
+        for raw in ["2023-01-01T01:00:00+2", "2023-01-01T01:00:00+24:00", "2023-01-01T01:00:00+02:60",
+                    "2023-01-01T01:00:00Zjunk", "2023-01-01T01:00:00+02-00", "2023-01-01T01:00:00+-200", "2023-01-01T01:00:00+€0"] {
+            assert!(Rfc3339::parse(raw).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_leap_second_policies() {
+        let rfc = Rfc3339::parse("2016-12-31T23:59:60Z").unwrap();
+        assert_eq!(rfc.to_utc().to_rfc3339(), "2017-01-01T00:00:00Z");
+        assert_eq!(rfc.to_posix(LeapSecond::Clamp).unwrap().to_string_timestamp(), "1483228799");
+        assert_eq!(rfc.to_posix(LeapSecond::Carry).unwrap().to_string_timestamp(), "1483228800");
+        assert!(rfc.to_posix(LeapSecond::Reject).is_err());
+
+        let plain = Rfc3339::parse("2016-12-31T23:59:59+01:00").unwrap();
+        assert_eq!(plain.to_utc_with(LeapSecond::Reject).unwrap(), plain.to_utc());
     }
 
     #[test]
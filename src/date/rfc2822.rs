@@ -0,0 +1,186 @@
+use std::fmt;
+use crate::date::date::Date;
+use crate::date::format::{self, MONTH_NAMES};
+use crate::date::rcf3339::UtcOffset;
+
+/// A wrapper structure for RFC 2822 handling.
+///
+/// RFC 2822 dates (`Thu, 23 Nov 2023 14:30:00 +0000`) are used in email headers
+/// and many HTTP APIs. This struct wraps a `Date` object holding the local time
+/// at `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rfc2822 {
+    pub date: Date,
+    pub offset: UtcOffset,
+}
+
+impl Rfc2822 {
+    /// Parses an RFC 2822 date string (e.g., "Thu, 23 Nov 2023 14:30:00 +0000").
+    ///
+    /// The weekday and the seconds are optional. Besides numeric offsets, the
+    /// obsolete zone names `UT`, `GMT`, `Z`, `EST`, `EDT`, `CST`, `CDT`, `MST`,
+    /// `MDT`, `PST`, and `PDT` are accepted, as are two-digit years (`00`-`49`
+    /// meaning 2000-2049, `50`-`99` meaning 1950-1999).
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - A string slice that holds the date to parse.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if a field is missing or malformed, the weekday
+    /// does not match the date, or the date is semantically invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rfc2822::Rfc2822;
+    /// let rfc = Rfc2822::parse("Thu, 23 Nov 2023 14:30:00 +0100").unwrap();
+    /// assert_eq!(rfc.date.day, 23);
+    /// assert_eq!(rfc.offset.hours, 1);
+    /// assert_eq!(rfc.to_utc().to_rfc2822(), "Thu, 23 Nov 2023 13:30:00 +0000");
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (weekday, rest) = match s.split_once(',') {
+            Some((name, rest)) => (Some(name.trim()), rest),
+            None => (None, s),
+        };
+
+        let parts: Vec<&str> = rest.split_whitespace().collect();
+        if parts.len() != 5 {
+            return Err("Expected day, month, year, time, and zone".into());
+        }
+
+        let parse_num = |str_slice: &str| -> Result<u32, String> {
+            if str_slice.is_empty() || !str_slice.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(format!("Invalid number: {}", str_slice));
+            }
+            str_slice.parse::<u32>().map_err(|_| format!("Invalid number: {}", str_slice))
+        };
+
+        if parts[0].len() > 2 {
+            return Err(format!("Invalid day: {}", parts[0]));
+        }
+        let day = parse_num(parts[0])? as u8;
+        let month = MONTH_NAMES.iter()
+            .position(|name| name[..3].eq_ignore_ascii_case(parts[1]))
+            .ok_or_else(|| format!("Invalid month: {}", parts[1]))? as u8 + 1;
+        let year = match parts[2].len() {
+            2 => match parse_num(parts[2])? as i32 { y @ 0..=49 => 2000 + y, y => 1900 + y },
+            _ => i32::try_from(parse_num(parts[2])?).map_err(|_| format!("Invalid year: {}", parts[2]))?,
+        };
+
+        let time: Vec<&str> = parts[3].split(':').collect();
+        if !(2..=3).contains(&time.len()) || time.iter().any(|t| t.len() != 2) {
+            return Err(format!("Invalid time: {}", parts[3]));
+        }
+        let hour = parse_num(time[0])? as u8;
+        let minute = parse_num(time[1])? as u8;
+        let second = if time.len() == 3 { parse_num(time[2])? as u8 } else { 0 };
+
+        let offset = Self::parse_zone(parts[4])?;
+
+        let date = Date { year, month, day, hour, minute, second };
+        if !date.is_valid() {
+            return Err("Semantically invalid date".into());
+        }
+
+        if let Some(name) = weekday.filter(|name| !date.weekday().short_name().eq_ignore_ascii_case(name)) {
+            return Err(format!("Weekday {} does not match date", name));
+        }
+
+        Ok(Rfc2822 { date, offset })
+    }
+
+    /// Wraps a `Date` holding a UTC time.
+    pub fn from_date(date: Date) -> Self {
+        Rfc2822 { date, offset: UtcOffset::UTC }
+    }
+
+    /// Returns the same instant expressed in UTC.
+    pub fn to_utc(&self) -> Rfc2822 {
+        Rfc2822 {
+            date: Date::from_seconds(self.date.to_seconds() - self.offset.total_seconds()),
+            offset: UtcOffset::UTC,
+        }
+    }
+
+    /// Reconstructs the RFC 2822 string representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// use stdt::date::rfc2822::Rfc2822;
+    /// let date = Iso8601::parse("2023-11-05T09:03:00").unwrap().date;
+    /// assert_eq!(Rfc2822::from_date(date).to_rfc2822(), "Sun, 05 Nov 2023 09:03:00 +0000");
+    /// ```
+    pub fn to_rfc2822(&self) -> String {
+        self.strftime("%a, %d %b %Y %T %z")
+    }
+
+    /// Formats the date with strftime-style `%` specifiers.
+    ///
+    /// The specifiers are the same as for `Rfc3339::strftime`; `%z` writes `offset`.
+    pub fn strftime(&self, pattern: &str) -> String {
+        format::strftime(&self.date, self.offset, pattern)
+    }
+
+    // --- Internal Helpers ---
+
+    fn parse_zone(zone: &str) -> Result<UtcOffset, String> {
+        let west = |hours| UtcOffset { sign: -1, hours, minutes: 0 };
+        match zone.to_ascii_uppercase().as_str() {
+            "UT" | "GMT" | "Z" => Ok(UtcOffset::UTC),
+            "EDT" => Ok(west(4)),
+            "EST" | "CDT" => Ok(west(5)),
+            "CST" | "MDT" => Ok(west(6)),
+            "MST" | "PDT" => Ok(west(7)),
+            "PST" => Ok(west(8)),
+            _ if zone.len() == 5 => UtcOffset::parse(zone),
+            _ => Err(format!("Invalid zone: {}", zone)),
+        }
+    }
+}
+
+impl fmt::Display for Rfc2822 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_rfc2822())
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full() {
+        let rfc = Rfc2822::parse("Thu, 23 Nov 2023 14:30:05 +0000").unwrap();
+        assert_eq!(rfc.date, Date { year: 2023, month: 11, day: 23, hour: 14, minute: 30, second: 5 });
+        assert!(rfc.offset.is_utc());
+        assert_eq!(rfc.to_string(), "Thu, 23 Nov 2023 14:30:05 +0000");
+    }
+
+    #[test]
+    fn test_parse_optional_and_obsolete_forms() {
+        let rfc = Rfc2822::parse("1 Feb 24 08:00 EST").unwrap();
+        assert_eq!(rfc.date, Date { year: 2024, month: 2, day: 1, hour: 8, minute: 0, second: 0 });
+        assert_eq!(rfc.to_rfc2822(), "Thu, 01 Feb 2024 08:00:00 -0500");
+        assert_eq!(rfc.to_utc().date.hour, 13);
+        assert_eq!(Rfc2822::parse("Fri, 31 Dec 99 23:59:59 GMT").unwrap().date.year, 1999);
+        assert_eq!(Rfc2822::parse("  sun,  05 nov 2023 09:03:00 -0130 ").unwrap().offset.minutes, 30);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for raw in ["", "Thu, 23 Nov 2023 14:30:00", "Fri, 23 Nov 2023 14:30:00 +0000", "Thu, 23 Nox 2023 14:30:00 +0000",
+                    "Thu, 31 Nov 2023 14:30:00 +0000", "Thu, 23 Nov 2023 14:3:00 +0000", "Thu, 23 Nov 2023 14:30:00 +00",
+                    "Thu, 23 Nov 2023 14:30:00 XYZ", "Thu, +3 Nov 2023 14:30:00 +0000", "257 Nov 2023 14:30:00 +0000",
+                    "001 Nov 2023 14:30:00 +0000", "1 Nov 4294967295 14:30:00 +0000"] {
+            assert!(Rfc2822::parse(raw).is_err(), "{}", raw);
+        }
+    }
+}
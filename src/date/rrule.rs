@@ -0,0 +1,432 @@
+use std::fmt;
+use crate::date::date::{Date, Weekday};
+use crate::date::iso8601::Iso8601;
+
+/// How often an `RRule` repeats (`FREQ`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A `BYDAY` entry: a weekday, optionally limited to the nth one (counting
+/// from the end when negative) of the month (`FREQ=MONTHLY`) or year (`FREQ=YEARLY`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByDay {
+    pub nth: Option<i8>,
+    pub weekday: Weekday,
+}
+
+/// A recurrence rule, the subset of iCalendar `RRULE` (RFC 5545) needed for
+/// reminders and recurring jobs.
+///
+/// Supported parts are `FREQ` (`DAILY`, `WEEKLY`, `MONTHLY`, `YEARLY`),
+/// `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY`, and `BYMONTHDAY`. Weeks start on Monday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<Date>,
+    pub by_day: Vec<ByDay>,
+    pub by_month_day: Vec<i8>,
+}
+
+/// Iterator over the occurrences of an `RRule`, created by [`RRule::occurrences`].
+#[derive(Debug, Clone)]
+pub struct Occurrences {
+    rule: RRule,
+    start: Date,
+    period: i64,
+    pending: std::vec::IntoIter<Date>,
+    emitted: u32,
+    last_year: i32,
+    done: bool,
+}
+
+impl RRule {
+    /// Creates a rule repeating every period of `freq`, with no limits.
+    pub fn new(freq: Frequency) -> Self {
+        RRule { freq, interval: 1, count: None, until: None, by_day: Vec::new(), by_month_day: Vec::new() }
+    }
+
+    /// Parses an `RRULE` value (e.g., "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=10").
+    ///
+    /// An `RRULE:` prefix is accepted. `UNTIL` is either a basic ISO 8601 date
+    /// time (`20231231T235959Z`) or a date (`20231231`), which includes the
+    /// whole day.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if `FREQ` is missing, a part is unsupported,
+    /// repeated, or malformed, both `COUNT` and `UNTIL` are given, or a part
+    /// does not apply to the frequency (`BYMONTHDAY` with `WEEKLY`, ordinal
+    /// `BYDAY` with `DAILY` or `WEEKLY`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rrule::{Frequency, RRule};
+    /// let rule = RRule::parse("RRULE:FREQ=MONTHLY;BYDAY=-1FR;COUNT=3").unwrap();
+    /// assert_eq!(rule.freq, Frequency::Monthly);
+    /// assert_eq!(rule.by_day[0].nth, Some(-1));
+    /// assert_eq!(rule.to_string(), "FREQ=MONTHLY;COUNT=3;BYDAY=-1FR");
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+        let mut freq = None;
+        let mut rule = RRule::new(Frequency::Daily);
+        let mut seen = Vec::new();
+
+        for part in s.split(';') {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("Invalid rule part: {}", part))?;
+            if seen.contains(&key) {
+                return Err(format!("Repeated rule part: {}", key));
+            }
+            seen.push(key);
+
+            let parse_num = |str_slice: &str| str_slice.parse::<u32>()
+                .ok()
+                .filter(|n| *n > 0 && str_slice.bytes().all(|b| b.is_ascii_digit()))
+                .ok_or_else(|| format!("Invalid {}: {}", key, str_slice));
+
+            match key {
+                "FREQ" => freq = Some(match value {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return Err(format!("Unsupported FREQ: {}", value)),
+                }),
+                "INTERVAL" => rule.interval = parse_num(value)?,
+                "COUNT" => rule.count = Some(parse_num(value)?),
+                "UNTIL" => rule.until = Some(Self::parse_until(value)?),
+                "BYDAY" => {
+                    rule.by_day = value.split(',').map(Self::parse_by_day).collect::<Result<_, _>>()?;
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = value.split(',')
+                        .map(|day| day.parse::<i8>().ok()
+                            .filter(|d| *d != 0 && (-31..=31).contains(d))
+                            .ok_or_else(|| format!("Invalid BYMONTHDAY: {}", day)))
+                        .collect::<Result<_, _>>()?;
+                }
+                _ => return Err(format!("Unsupported rule part: {}", key)),
+            }
+        }
+
+        rule.freq = freq.ok_or("Missing FREQ")?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("COUNT and UNTIL cannot both be set".into());
+        }
+        if rule.freq == Frequency::Weekly && !rule.by_month_day.is_empty() {
+            return Err("BYMONTHDAY is not allowed with FREQ=WEEKLY".into());
+        }
+        if matches!(rule.freq, Frequency::Daily | Frequency::Weekly) && rule.by_day.iter().any(|d| d.nth.is_some()) {
+            return Err("Ordinal BYDAY requires FREQ=MONTHLY or FREQ=YEARLY".into());
+        }
+        Ok(rule)
+    }
+
+    /// Returns the occurrences of the rule from `start`, in chronological order.
+    ///
+    /// Every occurrence has the time of day of `start`, and `start` itself is
+    /// only included if it matches the rule. Without `COUNT` or `UNTIL` the
+    /// iterator is unbounded, except that it ends once `400 * INTERVAL` years
+    /// pass without an occurrence (e.g. `BYMONTHDAY=30` every February), or
+    /// once the next period lies past year `i32::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// use stdt::date::rrule::RRule;
+    /// let rule = RRule::parse("FREQ=WEEKLY;BYDAY=TU,TH;COUNT=3").unwrap();
+    /// let start = Iso8601::parse("2023-11-23T09:00:00").unwrap().date; // Thursday
+    /// let dates: Vec<String> = rule.occurrences(start)
+    ///     .map(|date| Iso8601 { date, offset: None }.to_iso8601())
+    ///     .collect();
+    /// assert_eq!(dates, ["2023-11-23T09:00:00", "2023-11-28T09:00:00", "2023-11-30T09:00:00"]);
+    /// ```
+    pub fn occurrences(&self, start: Date) -> Occurrences {
+        Occurrences {
+            rule: self.clone(),
+            start,
+            period: 0,
+            pending: Vec::new().into_iter(),
+            emitted: 0,
+            last_year: start.year,
+            done: false,
+        }
+    }
+
+    // --- Internal Helpers ---
+
+    fn parse_until(value: &str) -> Result<Date, String> {
+        let iso = match value.len() {
+            8 => Iso8601::parse(&format!("{}T235959", value)),
+            _ => Iso8601::parse(value),
+        };
+        iso.map(|iso| iso.date).map_err(|_| format!("Invalid UNTIL: {}", value))
+    }
+
+    fn parse_by_day(value: &str) -> Result<ByDay, String> {
+        let invalid = || format!("Invalid BYDAY: {}", value);
+        let split = value.len().checked_sub(2).filter(|i| value.is_char_boundary(*i)).ok_or_else(invalid)?;
+        let (nth, code) = value.split_at(split);
+        let weekday = match code {
+            "MO" => Weekday::Monday,
+            "TU" => Weekday::Tuesday,
+            "WE" => Weekday::Wednesday,
+            "TH" => Weekday::Thursday,
+            "FR" => Weekday::Friday,
+            "SA" => Weekday::Saturday,
+            "SU" => Weekday::Sunday,
+            _ => return Err(invalid()),
+        };
+        let nth = match nth {
+            "" => None,
+            _ => Some(nth.parse::<i8>().ok().filter(|n| *n != 0 && (-53..=53).contains(n)).ok_or_else(invalid)?),
+        };
+        Ok(ByDay { nth, weekday })
+    }
+
+    /// Returns `true` if `days` (counted from 1970-01-01) is an occurrence day
+    /// within a period spanning `first..=last`.
+    fn matches(&self, start: &Date, days: i64, first: i64, last: i64) -> bool {
+        let (year, month, day) = Date::civil_from_days(days);
+        let weekday = Weekday::from_iso(Date::iso_weekday_from_days(days));
+
+        if !self.by_month_day.is_empty() {
+            // Negative days count back from the end of the month (-1 is the last day).
+            let length = Date::month_length(year, month) as i8;
+            let hit = self.by_month_day.iter()
+                .any(|&d| if d > 0 { d == day as i8 } else { length + d + 1 == day as i8 });
+            if !hit {
+                return false;
+            }
+        }
+        if !self.by_day.is_empty() {
+            return self.by_day.iter().any(|by| by.weekday == weekday && match by.nth {
+                None => true,
+                Some(n) if n > 0 => (days - first) / 7 + 1 == n as i64,
+                Some(n) => (last - days) / 7 + 1 == -n as i64,
+            });
+        }
+        match self.freq {
+            Frequency::Daily => true,
+            Frequency::Weekly => weekday == start.weekday(),
+            Frequency::Monthly => !self.by_month_day.is_empty() || day == start.day,
+            Frequency::Yearly => !self.by_month_day.is_empty() || (month, day) == (start.month, start.day),
+        }
+    }
+}
+
+impl Occurrences {
+    /// Returns the candidate days of period `n` as a range of day numbers,
+    /// or `None` once the period lies past the years a `Date` can hold.
+    /// For monthly and yearly rules, `BYDAY` ordinals count within it.
+    fn period_days(&self, n: i64) -> Option<(i64, i64)> {
+        let step = n.checked_mul(self.rule.interval as i64)?;
+        let start = &self.start;
+        let (first, last) = match self.rule.freq {
+            Frequency::Daily => {
+                let day = Date::days_from_civil(start.year, start.month, start.day).checked_add(step)?;
+                (day, day)
+            }
+            Frequency::Weekly => {
+                let monday = start.start_of_week(Weekday::Monday);
+                let first = Date::days_from_civil(monday.year, monday.month, monday.day)
+                    .checked_add(step.checked_mul(7)?)?;
+                (first, first + 6)
+            }
+            Frequency::Monthly => {
+                let total = (start.year as i64 * 12 + (start.month as i64 - 1)).checked_add(step)?;
+                let year = i32::try_from(total.div_euclid(12)).ok()?;
+                let month = (total.rem_euclid(12) + 1) as u8;
+                let first = Date::days_from_civil(year, month, 1);
+                (first, first + Date::month_length(year, month) as i64 - 1)
+            }
+            Frequency::Yearly => {
+                let year = i32::try_from((start.year as i64).checked_add(step)?).ok()?;
+                (Date::days_from_civil(year, 1, 1), Date::days_from_civil(year, 12, 31))
+            }
+        };
+        let representable = Date::days_from_civil(i32::MIN, 1, 1)..=Date::days_from_civil(i32::MAX, 12, 31);
+        (representable.contains(&first) && representable.contains(&last)).then_some((first, last))
+    }
+}
+
+impl Iterator for Occurrences {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Date> {
+        loop {
+            if self.done || self.rule.count.is_some_and(|count| self.emitted >= count) {
+                return None;
+            }
+            if let Some(date) = self.pending.next() {
+                if self.rule.until.is_some_and(|until| date > until) {
+                    self.done = true;
+                    return None;
+                }
+                self.emitted += 1;
+                self.last_year = date.year;
+                return Some(date);
+            }
+
+            let Some((first, last)) = self.period_days(self.period) else {
+                self.done = true;
+                return None;
+            };
+            self.period += 1;
+            let (year, _, _) = Date::civil_from_days(first);
+            if year as i64 - self.last_year as i64 > 400 * self.rule.interval as i64 {
+                self.done = true;
+                return None;
+            }
+
+            let start = self.start;
+            self.pending = (first..=last)
+                .filter(|&days| self.rule.matches(&start, days, first, last))
+                .map(|days| {
+                    let (year, month, day) = Date::civil_from_days(days);
+                    Date { year, month, day, ..start }
+                })
+                .filter(|date| *date >= start)
+                .collect::<Vec<_>>()
+                .into_iter();
+        }
+    }
+}
+
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let freq = match self.freq {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", freq)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}Z", Iso8601 { date: until, offset: None }.to_iso8601_basic())?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self.by_day.iter().map(|by| {
+                let code = &by.weekday.short_name()[..2].to_ascii_uppercase();
+                match by.nth {
+                    Some(n) => format!("{}{}", n, code),
+                    None => code.to_string(),
+                }
+            }).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|d| d.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        Iso8601::parse(s).unwrap().date
+    }
+
+    fn take(rule: &str, start: &str, n: usize) -> Vec<String> {
+        RRule::parse(rule).unwrap()
+            .occurrences(date(start))
+            .take(n)
+            .map(|date| Iso8601 { date, offset: None }.to_iso8601())
+            .collect()
+    }
+
+    #[test]
+    fn test_daily_and_weekly() {
+        assert_eq!(take("FREQ=DAILY;INTERVAL=10;COUNT=3", "2023-12-25T08:00:00", 10),
+                   ["2023-12-25T08:00:00", "2024-01-04T08:00:00", "2024-01-14T08:00:00"]);
+        // Weekdays only.
+        assert_eq!(take("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR", "2023-11-24T09:00:00", 2),
+                   ["2023-11-24T09:00:00", "2023-11-27T09:00:00"]);
+        // Every other week, starting from a day that does not match.
+        assert_eq!(take("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR", "2023-11-22T10:00:00", 4),
+                   ["2023-11-24T10:00:00", "2023-12-04T10:00:00", "2023-12-08T10:00:00", "2023-12-18T10:00:00"]);
+        assert_eq!(take("FREQ=WEEKLY;UNTIL=20231207", "2023-11-23T10:00:00", 10),
+                   ["2023-11-23T10:00:00", "2023-11-30T10:00:00", "2023-12-07T10:00:00"]);
+    }
+
+    #[test]
+    fn test_monthly() {
+        // Months without a 31st are skipped.
+        assert_eq!(take("FREQ=MONTHLY", "2024-01-31T12:00:00", 3),
+                   ["2024-01-31T12:00:00", "2024-03-31T12:00:00", "2024-05-31T12:00:00"]);
+        assert_eq!(take("FREQ=MONTHLY;BYMONTHDAY=1,-1", "2024-02-10T00:00:00", 3),
+                   ["2024-02-29T00:00:00", "2024-03-01T00:00:00", "2024-03-31T00:00:00"]);
+        assert_eq!(take("FREQ=MONTHLY;BYDAY=-1FR", "2023-11-01T17:00:00", 2),
+                   ["2023-11-24T17:00:00", "2023-12-29T17:00:00"]);
+        assert_eq!(take("FREQ=MONTHLY;INTERVAL=3;BYDAY=2TU", "2024-01-01T00:00:00", 2),
+                   ["2024-01-09T00:00:00", "2024-04-09T00:00:00"]);
+        // Friday the 13th.
+        assert_eq!(take("FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13", "2023-01-01T00:00:00", 2),
+                   ["2023-01-13T00:00:00", "2023-10-13T00:00:00"]);
+    }
+
+    #[test]
+    fn test_yearly() {
+        assert_eq!(take("FREQ=YEARLY;COUNT=2", "2024-02-29T00:00:00", 5),
+                   ["2024-02-29T00:00:00", "2028-02-29T00:00:00"]);
+        assert_eq!(take("FREQ=YEARLY;BYDAY=1MO", "2024-06-01T00:00:00", 2),
+                   ["2025-01-06T00:00:00", "2026-01-05T00:00:00"]);
+        // A rule that can never match ends instead of looping forever.
+        assert_eq!(take("FREQ=MONTHLY;INTERVAL=12;BYMONTHDAY=30", "2024-02-01T00:00:00", 1).len(), 0);
+    }
+
+    #[test]
+    fn test_huge_intervals() {
+        let max = "4294967295";
+        assert_eq!(take(&format!("FREQ=YEARLY;INTERVAL={}", max), "2023-01-15T00:00:00", 5),
+                   ["2023-01-15T00:00:00"]);
+        let monthly = RRule::parse(&format!("FREQ=MONTHLY;INTERVAL={}", max)).unwrap();
+        let years: Vec<i32> = monthly.occurrences(date("2023-01-15T00:00:00")).map(|d| d.year).collect();
+        assert_eq!(years[..2], [2023, 357915964]);
+        for freq in ["DAILY", "WEEKLY"] {
+            let rule = RRule::parse(&format!("FREQ={};INTERVAL={}", freq, max)).unwrap();
+            let dates: Vec<Date> = rule.occurrences(date("2023-01-15T00:00:00")).collect();
+            assert!(dates.windows(2).all(|w| w[0] < w[1]), "{}", freq);
+        }
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for raw in ["FREQ=DAILY", "FREQ=WEEKLY;INTERVAL=2;COUNT=5;BYDAY=MO,WE",
+                    "FREQ=MONTHLY;UNTIL=20241231T235959Z;BYDAY=1SU,-1SA;BYMONTHDAY=-1"] {
+            assert_eq!(RRule::parse(raw).unwrap().to_string(), raw);
+        }
+        assert_eq!(RRule::parse("FREQ=YEARLY;UNTIL=20241231").unwrap().until, Some(date("2024-12-31T23:59:59")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for raw in ["", "INTERVAL=2", "FREQ=HOURLY", "FREQ=DAILY;INTERVAL=0", "FREQ=DAILY;COUNT=2;UNTIL=20240101",
+                    "FREQ=DAILY;BYDAY=XX", "FREQ=WEEKLY;BYDAY=1MO", "FREQ=WEEKLY;BYMONTHDAY=1",
+                    "FREQ=MONTHLY;BYMONTHDAY=32", "FREQ=MONTHLY;BYMONTHDAY=0", "FREQ=DAILY;FREQ=DAILY",
+                    "FREQ=DAILY;WKST=SU", "FREQ=DAILY;BYDAY=é"] {
+            assert!(RRule::parse(raw).is_err(), "{}", raw);
+        }
+    }
+}
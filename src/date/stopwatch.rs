@@ -0,0 +1,179 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A stopwatch for timing code, built on the monotonic `Instant` clock.
+///
+/// The stopwatch can be stopped and started again; the time while stopped is
+/// not counted. `Display` prints the elapsed time with `format_elapsed`.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::date::stopwatch::Stopwatch;
+/// let mut sw = Stopwatch::start_new();
+/// let _sum: u64 = (0..1000).sum();
+/// let first = sw.lap();
+/// let total = sw.stop();
+/// assert!(first <= total);
+/// assert_eq!(sw.laps(), &[first]);
+/// println!("took {}", sw);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Stopwatch {
+    started: Option<Instant>,
+    accumulated: Duration,
+    last_lap: Duration,
+    laps: Vec<Duration>,
+}
+
+impl Stopwatch {
+    /// Creates a stopped stopwatch at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a stopwatch and starts it.
+    pub fn start_new() -> Self {
+        let mut sw = Self::new();
+        sw.start();
+        sw
+    }
+
+    /// Starts the stopwatch, or resumes it after `stop`. Does nothing if it is running.
+    pub fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// Stops the stopwatch and returns the total elapsed time.
+    pub fn stop(&mut self) -> Duration {
+        if let Some(started) = self.started.take() {
+            self.accumulated += started.elapsed();
+        }
+        self.accumulated
+    }
+
+    /// Stops the stopwatch and clears the elapsed time and laps.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns `true` if the stopwatch is running.
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Returns the total elapsed time, including the current run.
+    pub fn elapsed(&self) -> Duration {
+        self.accumulated + self.started.map_or(Duration::ZERO, |started| started.elapsed())
+    }
+
+    /// Records a lap and returns its time: the elapsed time since the previous
+    /// lap, or since the start for the first one.
+    pub fn lap(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        let lap = elapsed - self.last_lap;
+        self.last_lap = elapsed;
+        self.laps.push(lap);
+        lap
+    }
+
+    /// Returns the recorded lap times, in order.
+    pub fn laps(&self) -> &[Duration] {
+        &self.laps
+    }
+}
+
+impl fmt::Display for Stopwatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_elapsed(self.elapsed()))
+    }
+}
+
+/// Formats a duration for humans, with the unit scaled to its size.
+///
+/// Durations below a second use `ns`, `µs`, or `ms` with up to three decimals.
+/// Longer ones show seconds with milliseconds, prefixed by minutes and hours
+/// when needed.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stdt::date::stopwatch::format_elapsed;
+/// assert_eq!(format_elapsed(Duration::from_nanos(950)), "950ns");
+/// assert_eq!(format_elapsed(Duration::from_micros(1_500)), "1.5ms");
+/// assert_eq!(format_elapsed(Duration::from_millis(2_345)), "2.345s");
+/// assert_eq!(format_elapsed(Duration::from_millis(3_723_004)), "1h 02m 03.004s");
+/// ```
+pub fn format_elapsed(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    let scaled = |unit: u128, suffix: &str| {
+        let value = format!("{}.{:03}", nanos / unit, nanos % unit * 1000 / unit);
+        format!("{}{}", value.trim_end_matches('0').trim_end_matches('.'), suffix)
+    };
+    match nanos {
+        0..=999 => format!("{}ns", nanos),
+        1_000..=999_999 => scaled(1_000, "µs"),
+        1_000_000..=999_999_999 => scaled(1_000_000, "ms"),
+        _ => {
+            let secs = d.as_secs();
+            let seconds = format!("{:02}.{:03}s", secs % 60, d.subsec_millis());
+            match (secs / 3600, secs % 3600 / 60) {
+                (0, 0) => scaled(1_000_000_000, "s"),
+                (0, m) => format!("{}m {}", m, seconds),
+                (h, m) => format!("{}h {:02}m {}", h, m, seconds),
+            }
+        }
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_and_resume() {
+        let mut sw = Stopwatch::new();
+        assert!(!sw.is_running());
+        assert_eq!(sw.elapsed(), Duration::ZERO);
+
+        sw.start();
+        std::thread::sleep(Duration::from_millis(5));
+        let first = sw.stop();
+        assert!(first >= Duration::from_millis(5));
+        assert_eq!(sw.elapsed(), first);
+
+        sw.start();
+        assert!(sw.is_running());
+        assert!(sw.elapsed() >= first);
+        sw.reset();
+        assert!(!sw.is_running());
+        assert_eq!(sw.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_laps_add_up() {
+        let mut sw = Stopwatch::start_new();
+        for _ in 0..3 {
+            std::thread::sleep(Duration::from_millis(1));
+            sw.lap();
+        }
+        let total = sw.stop();
+        assert_eq!(sw.laps().len(), 3);
+        assert!(sw.laps().iter().sum::<Duration>() <= total);
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::ZERO), "0ns");
+        assert_eq!(format_elapsed(Duration::from_nanos(1_234)), "1.234µs");
+        assert_eq!(format_elapsed(Duration::from_micros(12_000)), "12ms");
+        assert_eq!(format_elapsed(Duration::from_millis(59_999)), "59.999s");
+        assert_eq!(format_elapsed(Duration::from_secs(60)), "1m 00.000s");
+        assert_eq!(format_elapsed(Duration::from_secs(90_000)), "25h 00m 00.000s");
+    }
+}
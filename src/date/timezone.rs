@@ -0,0 +1,413 @@
+use crate::date::date::{Date, SECONDS_PER_DAY};
+use crate::date::rcf3339::UtcOffset;
+
+/// A time zone: a fixed UTC offset, optionally with a daylight saving time rule.
+///
+/// Rules follow the POSIX `TZ` format (e.g. `CET-1CEST,M3.5.0,M10.5.0/3`), which
+/// covers most zones for current dates without a time zone database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZone {
+    name: String,
+    offset: UtcOffset,
+    dst: Option<Dst>,
+}
+
+/// The daylight saving part of a POSIX `TZ` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Dst {
+    name: String,
+    offset: UtcOffset,
+    start: Transition,
+    end: Transition,
+}
+
+/// A yearly transition: a day rule and a local time in seconds (may be
+/// negative or exceed 24 hours).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Transition {
+    day: DayRule,
+    time: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayRule {
+    /// `Jn`: day 1-365, February 29th is never counted.
+    Julian(u16),
+    /// `n`: day 0-365, counting February 29th in leap years.
+    Ordinal(u16),
+    /// `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (5 = last) of month `m`.
+    MonthWeekDay(u8, u8, u8),
+}
+
+impl TimeZone {
+    /// Returns the UTC time zone.
+    pub fn utc() -> Self {
+        Self::fixed(UtcOffset::UTC)
+    }
+
+    /// Returns a time zone that is always at `offset`, named after it (e.g. `+01:00`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::rcf3339::UtcOffset;
+    /// use stdt::date::timezone::TimeZone;
+    /// let tz = TimeZone::fixed(UtcOffset::parse("+05:30").unwrap());
+    /// assert_eq!(tz.name(), "+05:30");
+    /// ```
+    pub fn fixed(offset: UtcOffset) -> Self {
+        TimeZone { name: offset.to_string(), offset, dst: None }
+    }
+
+    /// Parses a POSIX `TZ` string (e.g., "CET-1CEST,M3.5.0,M10.5.0/3").
+    ///
+    /// The format is `std offset [dst [offset] [,start[/time],end[/time]]]`:
+    ///
+    /// * Names are three or more letters, or any text in angle brackets (`<+03>`).
+    /// * Offsets are `[+-]hh[:mm]` **west** of UTC, so `CET-1` is one hour ahead
+    ///   of UTC. The DST offset defaults to one hour ahead of standard time.
+    /// * Transition days are `Mm.w.d` (weekday `d`, 0 = Sunday, of week `w`,
+    ///   5 = last, of month `m`), `Jn` (1-365, skipping February 29th), or `n`
+    ///   (0-365). Times default to `02:00`. Without transition rules, the US
+    ///   rules `M3.2.0,M11.1.0` apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Result::Err` if a name, offset, or rule is malformed or out
+    /// of range, or if an offset has a seconds part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::timezone::TimeZone;
+    /// let tz = TimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    /// assert_eq!(tz.name(), "CET");
+    /// assert!(tz.has_dst());
+    /// assert!(!TimeZone::parse("<+03>-3").unwrap().has_dst());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut rest = s;
+        let name = take_name(&mut rest)?;
+        let offset = take_offset(&mut rest)?;
+        if rest.is_empty() {
+            return Ok(TimeZone { name, offset, dst: None });
+        }
+
+        let dst_name = take_name(&mut rest)?;
+        let dst_offset = if rest.is_empty() || rest.starts_with(',') {
+            UtcOffset::from_seconds(offset.total_seconds() + 3600)
+        } else {
+            take_offset(&mut rest)?
+        };
+        let (start, end) = if rest.is_empty() {
+            let us = |month, week| Transition { day: DayRule::MonthWeekDay(month, week, 0), time: 7200 };
+            (us(3, 2), us(11, 1))
+        } else {
+            rest = rest.strip_prefix(',').ok_or_else(|| format!("Expected ',' before rules: {}", s))?;
+            let start = take_transition(&mut rest)?;
+            rest = rest.strip_prefix(',').ok_or_else(|| format!("Missing end rule: {}", s))?;
+            (start, take_transition(&mut rest)?)
+        };
+        if !rest.is_empty() {
+            return Err(format!("Trailing characters in time zone: {}", rest));
+        }
+
+        Ok(TimeZone {
+            name,
+            offset,
+            dst: Some(Dst { name: dst_name, offset: dst_offset, start, end }),
+        })
+    }
+
+    /// Returns the name of standard time (e.g. `CET`).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the zone observes daylight saving time.
+    pub fn has_dst(&self) -> bool {
+        self.dst.is_some()
+    }
+
+    /// Returns `true` if daylight saving time is in effect at the UTC time `utc`.
+    pub fn is_dst(&self, utc: &Date) -> bool {
+        self.dst_at(utc.to_seconds()).is_some()
+    }
+
+    /// Returns the offset in effect at the UTC time `utc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::date::iso8601::Iso8601;
+    /// use stdt::date::timezone::TimeZone;
+    /// let tz = TimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+    /// let winter = Iso8601::parse("2024-01-15T12:00:00").unwrap().date;
+    /// let summer = Iso8601::parse("2024-07-15T12:00:00").unwrap().date;
+    /// assert_eq!(tz.offset_at(&winter).to_string(), "+01:00");
+    /// assert_eq!(tz.offset_at(&summer).to_string(), "+02:00");
+    /// ```
+    pub fn offset_at(&self, utc: &Date) -> UtcOffset {
+        self.offset_at_seconds(utc.to_seconds())
+    }
+
+    /// Returns the abbreviation in effect at the UTC time `utc` (e.g. `CEST`).
+    pub fn abbreviation_at(&self, utc: &Date) -> &str {
+        match self.dst_at(utc.to_seconds()) {
+            Some(dst) => &dst.name,
+            None => &self.name,
+        }
+    }
+
+    // --- Internal Helpers ---
+
+    pub(crate) fn offset_at_seconds(&self, utc: i64) -> UtcOffset {
+        self.dst_at(utc).map_or(self.offset, |dst| dst.offset)
+    }
+
+    /// Returns the DST rule if it is in effect at `utc` (seconds since the epoch).
+    fn dst_at(&self, utc: i64) -> Option<&Dst> {
+        let dst = self.dst.as_ref()?;
+        let year = Date::from_seconds(utc + self.offset.total_seconds()).year;
+        // The start time is given in standard time, the end time in DST.
+        let start = dst.start.seconds_in(year) - self.offset.total_seconds();
+        let end = dst.end.seconds_in(year) - dst.offset.total_seconds();
+        let in_dst = if start < end {
+            (start..end).contains(&utc)
+        } else {
+            // Southern hemisphere: DST spans the new year.
+            !(end..start).contains(&utc)
+        };
+        in_dst.then_some(dst)
+    }
+
+    /// Returns the UTC seconds for the local time `local`.
+    ///
+    /// A local time repeated when clocks go back resolves to the earlier
+    /// instant. A local time skipped when clocks go forward is read with the
+    /// offset in effect before the change, so it lands after the gap.
+    pub(crate) fn utc_seconds(&self, local: i64) -> i64 {
+        let candidates = match &self.dst {
+            Some(dst) => vec![dst.offset, self.offset],
+            None => vec![self.offset],
+        };
+        candidates.iter()
+            .map(|offset| local - offset.total_seconds())
+            .find(|&utc| self.offset_at_seconds(utc).total_seconds() == local - utc)
+            .unwrap_or(local - self.offset.total_seconds())
+    }
+}
+
+impl Transition {
+    /// Seconds since the epoch (as a local time) of this transition in `year`.
+    fn seconds_in(self, year: i32) -> i64 {
+        let jan1 = Date::days_from_civil(year, 1, 1);
+        let day = match self.day {
+            DayRule::Julian(n) => {
+                let leap_shift = (Date::is_leap(year) && n >= 60) as i64;
+                jan1 + n as i64 - 1 + leap_shift
+            }
+            DayRule::Ordinal(n) => jan1 + n as i64,
+            DayRule::MonthWeekDay(month, week, weekday) => {
+                let first = Date::days_from_civil(year, month, 1);
+                let first_weekday = Date::iso_weekday_from_days(first) % 7;
+                let mut day = first + (weekday as i64 - first_weekday as i64).rem_euclid(7) + 7 * (week as i64 - 1);
+                while day >= first + Date::month_length(year, month) as i64 {
+                    day -= 7;
+                }
+                day
+            }
+        };
+        day * SECONDS_PER_DAY + self.time
+    }
+}
+
+// --- Parsing Helpers ---
+
+fn take_name(s: &mut &str) -> Result<String, String> {
+    if let Some(quoted) = s.strip_prefix('<') {
+        let end = quoted.find('>').ok_or_else(|| format!("Unclosed time zone name: {}", s))?;
+        let name = &quoted[..end];
+        *s = &quoted[end + 1..];
+        if name.is_empty() {
+            return Err("Empty time zone name".into());
+        }
+        return Ok(name.to_string());
+    }
+    let end = s.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(s.len());
+    if end < 3 {
+        return Err(format!("Invalid time zone name: {}", s));
+    }
+    let name = s[..end].to_string();
+    *s = &s[end..];
+    Ok(name)
+}
+
+/// Reads `[+-]h[h[h]][:mm[:ss]]` as seconds, with hours up to `max_hours`.
+fn take_time(s: &mut &str, max_hours: i64) -> Result<i64, String> {
+    let invalid = || format!("Invalid time: {}", s);
+    let sign = if s.starts_with('-') { -1 } else { 1 };
+    let digits = s.trim_start_matches(['+', '-']);
+    if digits.len() + 1 < s.len() {
+        return Err(invalid());
+    }
+    let end = digits.find(|c: char| !c.is_ascii_digit() && c != ':').unwrap_or(digits.len());
+    let fields: Vec<&str> = digits[..end].split(':').collect();
+
+    let mut total = 0;
+    for (i, field) in fields.iter().enumerate() {
+        let (limit, widths) = match i {
+            0 => (max_hours, 1..=3),
+            1 | 2 => (59, 2..=2),
+            _ => return Err(invalid()),
+        };
+        let value = field.parse::<i64>().ok()
+            .filter(|v| widths.contains(&field.len()) && *v <= limit)
+            .ok_or_else(invalid)?;
+        total += value * [3600, 60, 1][i];
+    }
+    *s = &digits[end..];
+    Ok(sign * total)
+}
+
+fn take_offset(s: &mut &str) -> Result<UtcOffset, String> {
+    let original = *s;
+    let seconds = take_time(s, 24)?;
+    if seconds % 60 != 0 {
+        return Err(format!("Offsets with seconds are not supported: {}", original));
+    }
+    // POSIX offsets count hours west of UTC.
+    Ok(UtcOffset::from_seconds(-seconds))
+}
+
+fn take_transition(s: &mut &str) -> Result<Transition, String> {
+    let original = *s;
+    let end = s.find([',', '/']).unwrap_or(s.len());
+    let (rule, rest) = s.split_at(end);
+    *s = rest;
+
+    let invalid = || format!("Invalid transition rule: {}", original);
+    let parse_num = |str_slice: &str, range: std::ops::RangeInclusive<u16>| str_slice.parse::<u16>()
+        .ok()
+        .filter(|n| str_slice.bytes().all(|b| b.is_ascii_digit()) && range.contains(n))
+        .ok_or_else(invalid);
+
+    let day = if let Some(n) = rule.strip_prefix('J') {
+        DayRule::Julian(parse_num(n, 1..=365)?)
+    } else if let Some(mwd) = rule.strip_prefix('M') {
+        let parts: Vec<&str> = mwd.split('.').collect();
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        DayRule::MonthWeekDay(
+            parse_num(parts[0], 1..=12)? as u8,
+            parse_num(parts[1], 1..=5)? as u8,
+            parse_num(parts[2], 0..=6)? as u8,
+        )
+    } else {
+        DayRule::Ordinal(parse_num(rule, 0..=365)?)
+    };
+
+    let time = match s.strip_prefix('/') {
+        Some(rest) => {
+            *s = rest;
+            take_time(s, 167)?
+        }
+        None => 7200,
+    };
+    Ok(Transition { day, time })
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::iso8601::Iso8601;
+
+    fn date(s: &str) -> Date {
+        Iso8601::parse(s).unwrap().date
+    }
+
+    #[test]
+    fn test_parse_fixed_zones() {
+        let tz = TimeZone::parse("EST5").unwrap();
+        assert_eq!(tz.name(), "EST");
+        assert_eq!(tz.offset_at(&date("2024-07-01T00:00:00")), UtcOffset { sign: -1, hours: 5, minutes: 0 });
+
+        let tz = TimeZone::parse("<+0530>-5:30").unwrap();
+        assert_eq!(tz.name(), "+0530");
+        assert_eq!(tz.offset_at(&date("2024-01-01T00:00:00")).to_string(), "+05:30");
+        assert_eq!(TimeZone::parse("UTC0").unwrap().offset_at(&date("2024-01-01T00:00:00")), UtcOffset::UTC);
+        assert_eq!(TimeZone::utc(), TimeZone::fixed(UtcOffset::UTC));
+    }
+
+    #[test]
+    fn test_cet_transitions() {
+        // 2024: CEST from Mar 31 01:00 UTC to Oct 27 01:00 UTC.
+        let tz = TimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert!(!tz.is_dst(&date("2024-03-31T00:59:59")));
+        assert!(tz.is_dst(&date("2024-03-31T01:00:00")));
+        assert!(tz.is_dst(&date("2024-10-27T00:59:59")));
+        assert!(!tz.is_dst(&date("2024-10-27T01:00:00")));
+        assert_eq!(tz.abbreviation_at(&date("2024-08-01T00:00:00")), "CEST");
+        assert_eq!(tz.abbreviation_at(&date("2024-12-01T00:00:00")), "CET");
+
+        assert_eq!(date("2024-03-31T00:59:59").to_zone(&tz), date("2024-03-31T01:59:59"));
+        assert_eq!(date("2024-03-31T01:00:00").to_zone(&tz), date("2024-03-31T03:00:00"));
+    }
+
+    #[test]
+    fn test_southern_hemisphere() {
+        // Sydney: AEDT from the first Sunday of October to the first Sunday of April.
+        let tz = TimeZone::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert!(tz.is_dst(&date("2024-01-15T00:00:00")));
+        assert!(!tz.is_dst(&date("2024-07-15T00:00:00")));
+        // 2024-04-07 03:00 AEDT = 2024-04-06 16:00 UTC
+        assert!(tz.is_dst(&date("2024-04-06T15:59:59")));
+        assert!(!tz.is_dst(&date("2024-04-06T16:00:00")));
+        // 2024-10-06 02:00 AEST = 2024-10-05 16:00 UTC
+        assert!(!tz.is_dst(&date("2024-10-05T15:59:59")));
+        assert!(tz.is_dst(&date("2024-10-05T16:00:00")));
+    }
+
+    #[test]
+    fn test_day_rules() {
+        let jan1 = Date::days_from_civil(2024, 1, 1) * SECONDS_PER_DAY;
+        let at = |day| Transition { day, time: 0 };
+        assert_eq!(at(DayRule::Julian(60)).seconds_in(2024), jan1 + 60 * SECONDS_PER_DAY); // Mar 1
+        assert_eq!(at(DayRule::Ordinal(59)).seconds_in(2024), jan1 + 59 * SECONDS_PER_DAY); // Feb 29
+        let last_sunday_of_feb = at(DayRule::MonthWeekDay(2, 5, 0)).seconds_in(2024);
+        assert_eq!(Date::from_seconds(last_sunday_of_feb), date("2024-02-25T00:00:00"));
+
+        // Default rules and DST offset.
+        let tz = TimeZone::parse("EST5EDT").unwrap();
+        assert_eq!(tz.offset_at(&date("2024-03-10T07:00:00")).to_string(), "-04:00");
+        assert_eq!(tz.offset_at(&date("2024-11-03T06:00:00")).to_string(), "-05:00");
+    }
+
+    #[test]
+    fn test_from_zone_gaps_and_overlaps() {
+        let tz = TimeZone::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        // Skipped hour: 02:30 is read as CET and lands at 03:30 CEST.
+        let utc = Date::from_zone(&date("2024-03-31T02:30:00"), &tz);
+        assert_eq!(utc, date("2024-03-31T01:30:00"));
+        assert_eq!(utc.to_zone(&tz), date("2024-03-31T03:30:00"));
+        // Repeated hour: 02:30 resolves to the earlier (CEST) instant.
+        assert_eq!(Date::from_zone(&date("2024-10-27T02:30:00"), &tz), date("2024-10-27T00:30:00"));
+
+        for s in ["2024-01-01T00:00:00", "2024-03-31T01:59:59", "2024-06-30T23:59:59", "2024-10-27T03:00:00"] {
+            assert_eq!(Date::from_zone(&date(s), &tz).to_zone(&tz), date(s));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for raw in ["", "AB5", "EST", "EST25", "EST5:30:15", "<EST5", "CET-1CEST,M3.5.0",
+                    "CET-1CEST,M13.5.0,M10.5.0", "CET-1CEST,M3.5.7,M10.5.0", "CET-1CEST,J0,J365",
+                    "CET-1CEST,M3.5.0,M10.5.0/3x", "EST5x", "CET-1CEST-2é",
+                    "CET-1CEST-2XM3.5.0,M10.5.0/3"] {
+            assert!(TimeZone::parse(raw).is_err(), "{}", raw);
+        }
+    }
+}
@@ -1,9 +1,42 @@
 mod value;
+mod value_ref;
+mod map;
+mod pointer;
+mod index;
+mod path;
+mod convert;
+mod compare;
+mod traits;
+mod merge;
+mod diff;
+mod document;
+mod lines;
 mod parser;
 mod serializer;
+mod stats;
+mod walk;
+
+pub mod patch;
+pub mod query;
+pub mod builder;
+pub mod schema;
+pub mod csv;
+pub mod stream;
 
 pub use value::Value;
-pub use parser::{from_str, ParseError};
+pub use value_ref::{from_str_ref, from_str_ref_with, ValueRef};
+pub use map::{Entry, Map, OccupiedEntry, VacantEntry};
+pub use path::PathError;
+pub use convert::ConversionError;
+pub use traits::{field, FromJson, ToJson};
+pub use merge::{ArrayMerge, MergeStrategy};
+pub use diff::diff;
+pub use document::{Document, Node};
+pub use stats::Stats;
+pub use walk::{Bfs, Dfs};
+pub use lines::{lines, Lines, ParseErrorWithLine};
+pub use parser::{from_slice, from_slice_with, from_str, from_str_with, LoneSurrogate, ParseError, ParserOptions};
+pub use serializer::{to_string_with, to_writer, to_writer_pretty, to_writer_with, SerializerOptions};
 
 /// A macro to create a `json::Value` with a JSON-like syntax.
 ///
@@ -29,8 +62,9 @@ pub use parser::{from_str, ParseError};
 /// ```
 ///
 /// ### Objects
-/// Create an object from key-value pairs. Keys must be string literals or expressions
-/// that evaluate to a `String` or `&str`.
+/// Create an object from key-value pairs. Keys are string literals or any
+/// expression that evaluates to a `String` or `&str`; a bare identifier names
+/// a variable, and longer expressions can be wrapped in parentheses.
 ///
 /// ```
 /// # use stdt::json;
@@ -44,27 +78,189 @@ pub use parser::{from_str, ParseError};
 ///     ]
 /// });
 /// ```
+///
+/// ### Expressions
+/// Values may be arbitrary Rust expressions, and arrays and objects may end
+/// with a trailing comma.
+///
+/// ```
+/// # use stdt::json;
+/// let field = "retries";
+/// let base = 2;
+/// let obj = json!({
+///     field: base * 3,
+///     (format!("{}_max", field)): base + 8,
+///     "sizes": [base, base * 2,],
+/// });
+/// assert_eq!(obj.to_string(), r#"{"retries":6,"retries_max":10,"sizes":[2,4]}"#);
+/// ```
 #[macro_export]
 macro_rules! json {
-    // Null literal
+    ($($json:tt)+) => {
+        $crate::json_internal!($($json)+)
+    };
+}
+
+// Implementation of `json!`. Arrays and objects are munched one token tree
+// at a time so that elements and keys can be arbitrary expressions.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_internal {
+    //////////////////////////////////////////////////////////////////////////
+    // Array elements: `(@array [$($elems,)*] $($rest)*)` accumulates the
+    // elements parsed so far and produces a `vec![...]`.
+    //////////////////////////////////////////////////////////////////////////
+
+    // Done, with or without a trailing comma.
+    (@array [$($elems:expr,)*]) => {
+        vec![$($elems,)*]
+    };
+    (@array [$($elems:expr),*]) => {
+        vec![$($elems),*]
+    };
+
+    // Next element is `null`, `true`, `false`, an array, or an object.
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(true)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(false)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!({$($map)*})] $($rest)*)
+    };
+
+    // Next element is an expression followed by a comma, or the last element.
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($last)])
+    };
+
+    // Comma after the most recent element.
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // Anything else is a syntax error.
+    (@array [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::json_unexpected!($unexpected)
+    };
+
+    //////////////////////////////////////////////////////////////////////////
+    // Object members: `(@object $map ($($key)*) ($($rest)*) ($($rest)*))`
+    // inserts each member into `$map`. The key is accumulated token by token
+    // until the `:`; the last argument is a copy of the remaining input used
+    // only for error spans.
+    //////////////////////////////////////////////////////////////////////////
+
+    // Done.
+    (@object $object:ident () () ()) => {};
+
+    // Insert the current member, followed by a comma or at the end.
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(String::from($($key)+), $value);
+        $crate::json_internal!(@object $object () ($($rest)*) ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::json_unexpected!($unexpected);
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(String::from($($key)+), $value);
+    };
+
+    // Next value is `null`, `true`, `false`, an array, or an object.
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(true)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(false)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!({$($map)*})) $($rest)*);
+    };
+
+    // Next value is an expression followed by a comma, or the last value.
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)));
+    };
+
+    // Missing value for the last member, or missing colon.
+    (@object $object:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::json_internal!();
+    };
+    (@object $object:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::json_internal!();
+    };
+
+    // A colon or comma where a key was expected.
+    (@object $object:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($colon);
+    };
+    (@object $object:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($comma);
+    };
+
+    // A parenthesized key is a single expression.
+    (@object $object:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($key) (: $($rest)*) (: $($rest)*));
+    };
+
+    // Otherwise, munch one more token into the key.
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    //////////////////////////////////////////////////////////////////////////
+    // Entry points.
+    //////////////////////////////////////////////////////////////////////////
+
     (null) => {
         $crate::json::Value::Null
     };
 
-    // Array literal
-    ([ $( $element:tt ),* ]) => {
-        $crate::json::Value::Array(vec![ $( $crate::json!($element) ),* ])
+    (true) => {
+        $crate::json::Value::Bool(true)
     };
 
-    // Object literal
-    ({ $( $key:literal : $value:tt ),* }) => {
-        {
-            let mut map = std::collections::HashMap::new();
-            $(
-                map.insert(String::from($key), $crate::json!($value));
-            )*
-            $crate::json::Value::Object(map)
-        }
+    (false) => {
+        $crate::json::Value::Bool(false)
+    };
+
+    ([]) => {
+        $crate::json::Value::Array(vec![])
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::json::Value::Array($crate::json_internal!(@array [] $($tt)+))
+    };
+
+    ({}) => {
+        $crate::json::Value::Object($crate::json::Map::new())
+    };
+
+    ({ $($tt:tt)+ }) => {
+        $crate::json::Value::Object({
+            let mut object = $crate::json::Map::new();
+            $crate::json_internal!(@object object () ($($tt)+) ($($tt)+));
+            object
+        })
     };
 
     // Any other expression is converted into a Value.
@@ -72,3 +268,36 @@ macro_rules! json {
         $crate::json::Value::from($other)
     };
 }
+
+// Reports an unexpected token by failing to match it.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_unexpected {
+    () => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Value;
+
+    #[test]
+    fn macro_builds_literals_and_nesting() {
+        let v = json!({"a": [1, "two", null, true, {"b": false}], "c": {}, "d": []});
+        assert_eq!(v.to_string(), r#"{"a":[1,"two",null,true,{"b":false}],"c":{},"d":[]}"#);
+        assert_eq!(json!(null), Value::Null);
+        assert_eq!(json!(-1.5), Value::Number(-1.5));
+    }
+
+    #[test]
+    fn macro_accepts_expressions_and_trailing_commas() {
+        let name = String::from("n");
+        let items = [1, 2];
+        let v = json!({
+            name: items.len() + 1,
+            (format!("{}2", "k")): -items[0],
+            "list": [items[1] * 10, "x".repeat(2),],
+            "nested": {"t": 1 < 2,},
+        });
+        assert_eq!(v.to_string(), r#"{"n":3,"k2":-1,"list":[20,"xx"],"nested":{"t":true}}"#);
+    }
+}
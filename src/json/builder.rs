@@ -0,0 +1,158 @@
+//! Fluent builders for assembling `json::Value` documents in code.
+//!
+//! The `json!` macro covers documents whose shape is known up front. The
+//! builders here cover the rest: keys computed at runtime, fields added in a
+//! loop, or members included only under some condition.
+//!
+//! Builders convert into `Value`, so a nested builder can be passed straight
+//! to `field` or `item` without calling `build` on it first.
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json::builder;
+//! let mut tags = builder::array();
+//! for tag in ["a", "b"] {
+//!     tags = tags.item(tag);
+//! }
+//! let doc = builder::object()
+//!     .field("id", 7)
+//!     .field("tags", tags)
+//!     .field("meta", builder::object().field("draft", false))
+//!     .build();
+//! assert_eq!(doc.to_string(), r#"{"id":7,"tags":["a","b"],"meta":{"draft":false}}"#);
+//! ```
+
+use super::map::Map;
+use super::value::Value;
+use std::sync::Arc;
+
+/// Starts building a JSON object.
+pub fn object() -> ObjectBuilder {
+    ObjectBuilder::default()
+}
+
+/// Starts building a JSON array.
+pub fn array() -> ArrayBuilder {
+    ArrayBuilder::default()
+}
+
+/// Builds a `Value::Object` one field at a time.
+#[derive(Debug, Clone, Default)]
+pub struct ObjectBuilder {
+    map: Map,
+}
+
+impl ObjectBuilder {
+    /// Sets `key` to `value`. A repeated key keeps its original position and
+    /// takes the new value.
+    pub fn field<K: Into<Arc<str>>, V: Into<Value>>(mut self, key: K, value: V) -> Self {
+        self.map.insert(key, value.into());
+        self
+    }
+
+    /// Sets `key` to `value` only if `value` is `Some`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::builder;
+    /// let nickname: Option<&str> = None;
+    /// let doc = builder::object().field("name", "Ada").field_opt("nickname", nickname).build();
+    /// assert_eq!(doc.to_string(), r#"{"name":"Ada"}"#);
+    /// ```
+    pub fn field_opt<K: Into<Arc<str>>, V: Into<Value>>(self, key: K, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.field(key, value),
+            None => self,
+        }
+    }
+
+    /// Sets every key-value pair produced by `fields`.
+    pub fn fields<I, K, V>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Arc<str>>,
+        V: Into<Value>,
+    {
+        for (key, value) in fields {
+            self.map.insert(key, value.into());
+        }
+        self
+    }
+
+    /// Finishes the object.
+    pub fn build(self) -> Value {
+        Value::Object(self.map)
+    }
+}
+
+impl From<ObjectBuilder> for Value {
+    fn from(builder: ObjectBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builds a `Value::Array` one element at a time.
+#[derive(Debug, Clone, Default)]
+pub struct ArrayBuilder {
+    items: Vec<Value>,
+}
+
+impl ArrayBuilder {
+    /// Appends `value`.
+    pub fn item<V: Into<Value>>(mut self, value: V) -> Self {
+        self.items.push(value.into());
+        self
+    }
+
+    /// Appends every value produced by `items`.
+    pub fn items<I, V>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.items.extend(items.into_iter().map(Into::into));
+        self
+    }
+
+    /// Finishes the array.
+    pub fn build(self) -> Value {
+        Value::Array(self.items)
+    }
+}
+
+impl From<ArrayBuilder> for Value {
+    fn from(builder: ArrayBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_nested_documents_with_dynamic_keys() {
+        let mut obj = object();
+        for i in 0..3 {
+            obj = obj.field(format!("k{}", i), i);
+        }
+        let doc = obj.field("list", array().items(vec![1, 2]).item(array())).build();
+        assert_eq!(doc.to_string(), r#"{"k0":0,"k1":1,"k2":2,"list":[1,2,[]]}"#);
+    }
+
+    #[test]
+    fn repeated_field_overwrites_in_place() {
+        let doc = object()
+            .field("a", 1)
+            .field("b", 2)
+            .field("a", 3)
+            .fields(vec![("c", true)])
+            .field_opt("d", Some("x"))
+            .field_opt::<_, i32>("e", None)
+            .build();
+        assert_eq!(doc.to_string(), r#"{"a":3,"b":2,"c":true,"d":"x"}"#);
+        assert_eq!(object().build().to_string(), "{}");
+    }
+}
@@ -0,0 +1,92 @@
+//! Looser equality checks for comparing JSON fixtures in tests.
+//!
+//! `Value`'s `PartialEq` is exact: numbers must be bit-for-bit equal and
+//! array elements must appear in the same order. `approx_eq` tolerates float
+//! rounding, and `eq_ignoring_array_order` treats arrays as multisets.
+
+use super::value::Value;
+
+impl Value {
+    /// Compares two trees like `==`, except that numbers are equal when they
+    /// differ by at most `epsilon`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let computed = json!({"total": 0.1 + 0.2, "items": [1.0000001]});
+    /// assert_ne!(computed, json!({"total": 0.3, "items": [1]}));
+    /// assert!(computed.approx_eq(&json!({"total": 0.3, "items": [1]}), 1e-6));
+    /// ```
+    pub fn approx_eq(&self, other: &Value, epsilon: f64) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b || (a - b).abs() <= epsilon,
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.approx_eq(y, epsilon))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| v.approx_eq(w, epsilon)))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Compares two trees like `==`, except that arrays at every level are
+    /// equal when they hold the same elements in any order (respecting how
+    /// many times each occurs).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let a = json!({"tags": ["x", "y", "x"], "rows": [{"id": 2}, {"id": 1}]});
+    /// let b = json!({"tags": ["x", "x", "y"], "rows": [{"id": 1}, {"id": 2}]});
+    /// assert!(a.eq_ignoring_array_order(&b));
+    /// assert!(!a.eq_ignoring_array_order(&json!({"tags": ["x", "y", "y"], "rows": []})));
+    /// ```
+    pub fn eq_ignoring_array_order(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Array(a), Value::Array(b)) => {
+                if a.len() != b.len() {
+                    return false;
+                }
+                // Match each element of `a` with a distinct unused element of `b`.
+                let mut used = vec![false; b.len()];
+                a.iter().all(|x| {
+                    let found = b
+                        .iter()
+                        .enumerate()
+                        .position(|(i, y)| !used[i] && x.eq_ignoring_array_order(y));
+                    found.map(|i| used[i] = true).is_some()
+                })
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| v.eq_ignoring_array_order(w)))
+            }
+            _ => self == other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::from_str;
+
+    #[test]
+    fn approx_eq_tolerates_rounding_only_in_numbers() {
+        let a = from_str(r#"{"x":[1.0,2.5],"y":"s"}"#).unwrap();
+        assert!(a.approx_eq(&from_str(r#"{"y":"s","x":[1.0005,2.4996]}"#).unwrap(), 1e-3));
+        assert!(!a.approx_eq(&from_str(r#"{"y":"s","x":[1.01,2.5]}"#).unwrap(), 1e-3));
+        assert!(!a.approx_eq(&from_str(r#"{"y":"s","x":[2.5,1.0]}"#).unwrap(), 1e-3));
+        assert!(!a.approx_eq(&from_str(r#"{"y":"s","x":[1.0,2.5],"z":null}"#).unwrap(), 1e-3));
+        assert!(!from_str("1").unwrap().approx_eq(&from_str("\"1\"").unwrap(), 1.0));
+    }
+
+    #[test]
+    fn array_order_ignored_at_every_level() {
+        let a = from_str(r#"[[1,2],[3],{"k":[true,null]}]"#).unwrap();
+        assert!(a.eq_ignoring_array_order(&from_str(r#"[{"k":[null,true]},[3],[2,1]]"#).unwrap()));
+        assert!(!a.eq_ignoring_array_order(&from_str(r#"[[1,2],[3],{"k":[true]}]"#).unwrap()));
+        assert!(!from_str("[1,1,2]").unwrap().eq_ignoring_array_order(&from_str("[1,2,2]").unwrap()));
+    }
+}
@@ -0,0 +1,406 @@
+//! Implements `TryFrom<Value>` and `TryFrom<&Value>` for common Rust types.
+//!
+//! Conversions are strict: a string is never parsed as a number, and a number
+//! converts to an integer type only if it is integral and fits. Errors inside
+//! arrays and objects report where the offending value was found, using the
+//! same `a.b[0]` path syntax as `Value::set_path`.
+//!
+//! `Value::as_vec_f64`, `as_vec_str`, `to_vec`, and `to_map` wrap the
+//! container conversions for one-call extraction of homogeneous data.
+
+use super::parser::ParseError;
+use super::path::escape_key;
+use super::value::Value;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/// An error that can occur while converting a `Value` into a Rust type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    /// The value has a different JSON type than the target requires.
+    TypeMismatch {
+        path: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The number is not integral or does not fit in the target type.
+    OutOfRange {
+        path: String,
+        value: f64,
+        target: &'static str,
+    },
+    /// The input text was not valid JSON (see `FromJson::from_json_str`).
+    Parse(ParseError),
+}
+
+impl ConversionError {
+    pub(crate) fn mismatch(expected: &'static str, found: &Value) -> Self {
+        ConversionError::TypeMismatch { path: String::new(), expected, found: found.type_name() }
+    }
+
+    /// Returns the path to the value that failed to convert, empty for the
+    /// root or for parse errors.
+    pub fn path(&self) -> &str {
+        match self {
+            ConversionError::TypeMismatch { path, .. } | ConversionError::OutOfRange { path, .. } => path,
+            ConversionError::Parse(_) => "",
+        }
+    }
+
+    /// Prefixes the error location with an array index.
+    pub(crate) fn at_index(mut self, index: usize) -> Self {
+        if let Some(path) = self.path_mut() {
+            *path = if path.is_empty() || path.starts_with('[') {
+                format!("[{}]{}", index, path)
+            } else {
+                format!("[{}].{}", index, path)
+            };
+        }
+        self
+    }
+
+    /// Prefixes the error location with an object key.
+    pub(crate) fn at_key(mut self, key: &str) -> Self {
+        if let Some(path) = self.path_mut() {
+            // The empty key has no dotted form.
+            let key = if key.is_empty() { "[\"\"]".to_string() } else { escape_key(key) };
+            *path = if path.is_empty() || path.starts_with('[') {
+                format!("{}{}", key, path)
+            } else {
+                format!("{}.{}", key, path)
+            };
+        }
+        self
+    }
+
+    fn path_mut(&mut self) -> Option<&mut String> {
+        match self {
+            ConversionError::TypeMismatch { path, .. } | ConversionError::OutOfRange { path, .. } => Some(path),
+            ConversionError::Parse(_) => None,
+        }
+    }
+}
+
+impl Error for ConversionError {}
+
+impl From<ParseError> for ConversionError {
+    fn from(e: ParseError) -> Self {
+        ConversionError::Parse(e)
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::TypeMismatch { expected, found, .. } => {
+                write!(f, "Expected {}, found {}", expected, found)?
+            }
+            ConversionError::OutOfRange { value, target, .. } => {
+                write!(f, "Number {} does not fit in {}", value, target)?
+            }
+            ConversionError::Parse(e) => return write!(f, "{}", e),
+        }
+        if !self.path().is_empty() {
+            write!(f, " at '{}'", self.path())?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = ConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.as_bool().ok_or_else(|| ConversionError::mismatch("boolean", value))
+    }
+}
+
+impl TryFrom<&Value> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.as_f64().ok_or_else(|| ConversionError::mismatch("number", value))
+    }
+}
+
+impl TryFrom<&Value> for f32 {
+    type Error = ConversionError;
+
+    /// Converts with `as`, so large magnitudes become infinite and precision may be lost.
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        f64::try_from(value).map(|n| n as f32)
+    }
+}
+
+impl TryFrom<&Value> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.as_str().map(str::to_string).ok_or_else(|| ConversionError::mismatch("string", value))
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for &'a str {
+    type Error = ConversionError;
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        value.as_str().ok_or_else(|| ConversionError::mismatch("string", value))
+    }
+}
+
+// Macro to implement integer conversions through `as_i64`/`as_u64`.
+macro_rules! impl_try_from_value_for_int {
+    ( $via:ident => $( $t:ty ),* ) => {
+        $(
+            impl TryFrom<&Value> for $t {
+                type Error = ConversionError;
+
+                fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                    let n = value.as_f64().ok_or_else(|| ConversionError::mismatch("number", value))?;
+                    value
+                        .$via()
+                        .and_then(|i| <$t>::try_from(i).ok())
+                        .ok_or_else(|| ConversionError::OutOfRange {
+                            path: String::new(),
+                            value: n,
+                            target: stringify!($t),
+                        })
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_value_for_int!(as_i64 => i8, i16, i32, i64, isize);
+impl_try_from_value_for_int!(as_u64 => u8, u16, u32, u64, usize);
+
+impl<'a, T> TryFrom<&'a Value> for Vec<T>
+where
+    T: TryFrom<&'a Value, Error = ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        let arr = value.as_array().ok_or_else(|| ConversionError::mismatch("array", value))?;
+        arr.iter()
+            .enumerate()
+            .map(|(i, v)| T::try_from(v).map_err(|e| e.at_index(i)))
+            .collect()
+    }
+}
+
+impl<'a, T> TryFrom<&'a Value> for HashMap<String, T>
+where
+    T: TryFrom<&'a Value, Error = ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        let map = value.as_object().ok_or_else(|| ConversionError::mismatch("object", value))?;
+        map.iter()
+            .map(|(k, v)| T::try_from(v).map(|t| (k.to_string(), t)).map_err(|e| e.at_key(k)))
+            .collect()
+    }
+}
+
+impl Value {
+    /// Converts an array of numbers into a `Vec<f64>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConversionError` if `self` is not an array, naming the
+    /// index of the first element that is not a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// assert_eq!(json!([1, 2.5]).as_vec_f64(), Ok(vec![1.0, 2.5]));
+    /// let err = json!([1, "2"]).as_vec_f64().unwrap_err();
+    /// assert_eq!(err.to_string(), "Expected number, found string at '[1]'");
+    /// ```
+    pub fn as_vec_f64(&self) -> Result<Vec<f64>, ConversionError> {
+        Vec::try_from(self)
+    }
+
+    /// Borrows an array of strings as a `Vec<&str>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConversionError` if `self` is not an array, naming the
+    /// index of the first element that is not a string.
+    pub fn as_vec_str(&self) -> Result<Vec<&str>, ConversionError> {
+        Vec::try_from(self)
+    }
+
+    /// Converts every element of an array into `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConversionError` if `self` is not an array, naming the
+    /// location of the first value that does not convert.
+    pub fn to_vec<'a, T>(&'a self) -> Result<Vec<T>, ConversionError>
+    where
+        T: TryFrom<&'a Value, Error = ConversionError>,
+    {
+        Vec::try_from(self)
+    }
+
+    /// Converts every member value of an object into `T`, keyed by member name.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConversionError` if `self` is not an object, naming the
+    /// location of the first value that does not convert.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let limits = json!({"cpu": 2, "memory": 512}).to_map::<u32>().unwrap();
+    /// assert_eq!(limits["memory"], 512);
+    /// let err = json!({"cpu": 2, "disk": -1}).to_map::<u32>().unwrap_err();
+    /// assert_eq!(err.path(), "disk");
+    /// ```
+    pub fn to_map<'a, T>(&'a self) -> Result<HashMap<String, T>, ConversionError>
+    where
+        T: TryFrom<&'a Value, Error = ConversionError>,
+    {
+        HashMap::try_from(self)
+    }
+}
+
+// Macro to implement owned conversions for types that are cheap to copy out.
+macro_rules! impl_try_from_owned_value {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl TryFrom<Value> for $t {
+                type Error = ConversionError;
+
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    <$t>::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_owned_value!(bool, f64, f32, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl TryFrom<Value> for String {
+    type Error = ConversionError;
+
+    /// Moves the string out without copying it.
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s),
+            other => Err(ConversionError::mismatch("string", &other)),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value, Error = ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(arr) => arr
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| T::try_from(v).map_err(|e| e.at_index(i)))
+                .collect(),
+            other => Err(ConversionError::mismatch("array", &other)),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for HashMap<String, T>
+where
+    T: TryFrom<Value, Error = ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Object(map) => map
+                .into_iter()
+                .map(|(k, v)| match T::try_from(v) {
+                    Ok(t) => Ok((k, t)),
+                    Err(e) => Err(e.at_key(&k)),
+                })
+                .collect(),
+            other => Err(ConversionError::mismatch("object", &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    #[test]
+    fn converts_scalars() {
+        assert_eq!(String::try_from(Value::from("hi")), Ok("hi".to_string()));
+        assert_eq!(bool::try_from(&Value::Bool(true)), Ok(true));
+        assert_eq!(f64::try_from(Value::Number(2.5)), Ok(2.5));
+        assert_eq!(i32::try_from(&Value::Number(-7.0)), Ok(-7));
+        assert_eq!(u8::try_from(Value::Number(255.0)), Ok(255));
+        assert_eq!(
+            u8::try_from(Value::Number(256.0)),
+            Err(ConversionError::OutOfRange { path: String::new(), value: 256.0, target: "u8" })
+        );
+        assert!(u32::try_from(Value::Number(-1.0)).is_err());
+        assert!(i64::try_from(Value::Number(1.5)).is_err());
+        assert_eq!(
+            bool::try_from(Value::from("true")),
+            Err(ConversionError::TypeMismatch { path: String::new(), expected: "boolean", found: "string" })
+        );
+    }
+
+    #[test]
+    fn converts_containers() {
+        let v = from_str(r#"{"a":[1,2],"b":[]}"#).unwrap();
+        let map: HashMap<String, Vec<u16>> = HashMap::try_from(&v).unwrap();
+        assert_eq!(map["a"], vec![1, 2]);
+        assert!(map["b"].is_empty());
+
+        let owned: HashMap<String, Vec<u16>> = HashMap::try_from(v).unwrap();
+        assert_eq!(owned.len(), 2);
+    }
+
+    #[test]
+    fn container_errors_report_their_location() {
+        let v = from_str(r#"{"servers":[{"port":80},{"port":"x"}]}"#).unwrap();
+        let err = HashMap::<String, Vec<HashMap<String, u16>>>::try_from(&v).unwrap_err();
+        assert_eq!(err.path(), "servers[1].port");
+        assert_eq!(err.to_string(), "Expected number, found string at 'servers[1].port'");
+
+        let err = Vec::<i32>::try_from(from_str("[1,[2]]").unwrap()).unwrap_err();
+        assert_eq!(err.path(), "[1]");
+
+        let v = from_str(r#"{"a":{"":[{"":{"b":"x"}}]}}"#).unwrap();
+        let err = HashMap::<String, HashMap<String, Vec<HashMap<String, HashMap<String, u8>>>>>::try_from(&v).unwrap_err();
+        assert_eq!(err.path(), r#"a[""][0][""].b"#);
+        assert_eq!(v.at(err.path()), Some(&Value::from("x")));
+    }
+
+    #[test]
+    fn bulk_helpers() {
+        let v = from_str(r#"{"names":["a","b"],"scores":[1.5,2],"nested":{"x":[1],"y":[2,true]}}"#).unwrap();
+        assert_eq!(v["names"].as_vec_str(), Ok(vec!["a", "b"]));
+        assert_eq!(v["scores"].as_vec_f64(), Ok(vec![1.5, 2.0]));
+        assert_eq!(v["scores"].to_vec::<u8>().unwrap_err().path(), "[0]");
+        assert_eq!(v["names"].as_vec_f64().unwrap_err().path(), "[0]");
+        assert_eq!(
+            v.as_vec_str(),
+            Err(ConversionError::TypeMismatch { path: String::new(), expected: "array", found: "object" })
+        );
+        assert_eq!(v["nested"].to_map::<Vec<f64>>().unwrap_err().path(), "y[1]");
+    }
+}
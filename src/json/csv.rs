@@ -0,0 +1,299 @@
+//! Converts between JSON arrays of flat objects and CSV text (RFC 4180).
+//!
+//! `to_csv` writes one row per object, with the union of all keys as the
+//! header in order of first appearance. `from_csv` reads the header and turns
+//! each row into an object.
+//!
+//! Cell types survive a round trip: unquoted cells holding `null`, `true`,
+//! `false`, or a JSON number are read as those values, an empty unquoted
+//! cell is `null`, and everything else is a string. `to_csv` quotes any
+//! string that would otherwise be read back as another type.
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json;
+//! use stdt::json::csv;
+//!
+//! let rows = json!([
+//!     {"id": 1, "name": "Ada", "admin": true},
+//!     {"id": 2, "name": "Lovelace, A.", "note": "42"}
+//! ]);
+//! let text = csv::to_csv(&rows).unwrap();
+//! assert_eq!(text, "id,name,admin,note\r\n1,Ada,true,\r\n2,\"Lovelace, A.\",,\"42\"\r\n");
+//!
+//! let back = csv::from_csv(&text).unwrap();
+//! assert_eq!(back[1]["note"], json!("42"));
+//! assert_eq!(back[1]["admin"], json!(null));
+//! ```
+
+use super::map::Map;
+use super::parser::from_str;
+use super::value::Value;
+use std::error::Error;
+use std::fmt;
+
+/// An error that can occur while converting to or from CSV.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvError {
+    /// `to_csv` was given something other than an array.
+    NotAnArray,
+    /// The element at `index` is not an object.
+    NotAnObject { index: usize },
+    /// The member `key` of the element at `index` is an array or object.
+    NestedValue { index: usize, key: String },
+    /// A quoted field starting on `line` is never closed.
+    UnterminatedQuote { line: usize },
+    /// A quote appears in the middle of an unquoted field, or text follows a
+    /// closing quote, on `line`.
+    UnexpectedQuote { line: usize },
+    /// The record starting on `line` has a different number of fields than
+    /// the header.
+    FieldCount { line: usize, expected: usize, found: usize },
+}
+
+impl Error for CsvError {}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::NotAnArray => write!(f, "Expected an array of objects"),
+            CsvError::NotAnObject { index } => write!(f, "Element {} is not an object", index),
+            CsvError::NestedValue { index, key } => {
+                write!(f, "Member '{}' of element {} is not a scalar", key, index)
+            }
+            CsvError::UnterminatedQuote { line } => write!(f, "Unterminated quoted field on line {}", line),
+            CsvError::UnexpectedQuote { line } => write!(f, "Unexpected quote on line {}", line),
+            CsvError::FieldCount { line, expected, found } => write!(
+                f,
+                "Record on line {} has {} fields, expected {}",
+                line, found, expected
+            ),
+        }
+    }
+}
+
+/// Converts an array of flat objects into CSV text with CRLF line endings.
+///
+/// Missing members and `null`s become empty cells.
+///
+/// # Errors
+///
+/// Returns a `CsvError` if `value` is not an array of objects whose members
+/// are all scalars.
+pub fn to_csv(value: &Value) -> Result<String, CsvError> {
+    let rows = value.as_array().ok_or(CsvError::NotAnArray)?;
+    let mut header = Map::new();
+    for (index, row) in rows.iter().enumerate() {
+        let obj = row.as_object().ok_or(CsvError::NotAnObject { index })?;
+        for (key, cell) in obj {
+            if cell.is_array() || cell.is_object() {
+                return Err(CsvError::NestedValue { index, key: key.to_string() });
+            }
+            if !header.contains_key(key) {
+                header.insert(key, Value::Null);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    write_record(&mut out, header.keys().map(Cell::Text));
+    for row in rows {
+        let obj = row.as_object().unwrap();
+        write_record(&mut out, header.keys().map(|k| Cell::from(obj.get(k))));
+    }
+    Ok(out)
+}
+
+/// Parses CSV text into an array of objects keyed by the header row.
+///
+/// LF, CRLF, and bare CR line endings are accepted, and quoted fields may span
+/// lines. A trailing newline at the end of the input is optional.
+///
+/// # Errors
+///
+/// Returns a `CsvError` for malformed quoting or a record whose field count
+/// differs from the header's.
+pub fn from_csv(text: &str) -> Result<Value, CsvError> {
+    let mut records = parse_records(text)?.into_iter();
+    let header = match records.next() {
+        Some((_, fields)) => fields,
+        None => return Ok(Value::Array(Vec::new())),
+    };
+    let mut rows = Vec::new();
+    for (line, fields) in records {
+        if fields.len() != header.len() {
+            return Err(CsvError::FieldCount { line, expected: header.len(), found: fields.len() });
+        }
+        let mut obj = Map::with_capacity(header.len());
+        for ((key, _), field) in header.iter().zip(fields) {
+            obj.insert(key.as_str(), field_to_value(field));
+        }
+        rows.push(Value::Object(obj));
+    }
+    Ok(Value::Array(rows))
+}
+
+enum Cell<'a> {
+    Empty,
+    Text(&'a str),
+    /// A string that must be quoted to stay a string when read back.
+    Quoted(&'a str),
+    Scalar(String),
+}
+
+impl<'a> From<Option<&'a Value>> for Cell<'a> {
+    fn from(value: Option<&'a Value>) -> Self {
+        match value {
+            None | Some(Value::Null) => Cell::Empty,
+            Some(Value::String(s)) if s.is_empty() || infer(s).is_some() => Cell::Quoted(s),
+            Some(Value::String(s)) => Cell::Text(s),
+            Some(other) => Cell::Scalar(other.to_string()),
+        }
+    }
+}
+
+fn write_record<'a>(out: &mut String, cells: impl Iterator<Item = Cell<'a>>) {
+    for (i, cell) in cells.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        match cell {
+            Cell::Empty => {}
+            Cell::Scalar(s) => out.push_str(&s),
+            Cell::Text(s) if !s.contains([',', '"', '\r', '\n']) => out.push_str(s),
+            Cell::Text(s) | Cell::Quoted(s) => {
+                out.push('"');
+                out.push_str(&s.replace('"', "\"\""));
+                out.push('"');
+            }
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// A parsed field and whether it was quoted.
+type Field = (String, bool);
+
+/// Splits `text` into records, each tagged with the line it starts on.
+fn parse_records(text: &str) -> Result<Vec<(usize, Vec<Field>)>, CsvError> {
+    let mut records = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        loop {
+            let mut field = String::new();
+            let quoted = chars.next_if_eq(&'"').is_some();
+            if quoted {
+                loop {
+                    match chars.next() {
+                        None => return Err(CsvError::UnterminatedQuote { line: start }),
+                        Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' || (c == '\r' && chars.peek() != Some(&'\n')) {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                    }
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !matches!(c, ',' | '\r' | '\n')) {
+                    if c == '"' {
+                        return Err(CsvError::UnexpectedQuote { line });
+                    }
+                    field.push(c);
+                }
+            }
+            fields.push((field, quoted));
+            match chars.next() {
+                Some(',') => continue,
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                    break;
+                }
+                Some('\n') | None => break,
+                Some(_) => return Err(CsvError::UnexpectedQuote { line }),
+            }
+        }
+        line += 1;
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+fn field_to_value((text, quoted): Field) -> Value {
+    if quoted {
+        return Value::String(text);
+    }
+    if text.is_empty() {
+        return Value::Null;
+    }
+    infer(&text).unwrap_or(Value::String(text))
+}
+
+/// Reads an unquoted cell as `null`, a boolean, or a number, if it is one.
+fn infer(text: &str) -> Option<Value> {
+    let first = text.chars().next()?;
+    if !(first == '-' || first.is_ascii_alphanumeric()) || text.trim() != text {
+        return None;
+    }
+    from_str(text).ok().filter(|v| !(v.is_array() || v.is_object() || v.is_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_types_and_quoting() {
+        let src = from_str(
+            r#"[{"s":"plain","n":-1.5,"b":false,"z":null,"e":"","q":"say \"hi\", ok","m":"two\nlines","t":"true"},
+                {"s":"7","extra":1e3}]"#,
+        )
+        .unwrap();
+        let text = to_csv(&src).unwrap();
+        assert!(text.starts_with("s,n,b,z,e,q,m,t,extra\r\n"));
+        assert!(text.contains(r#""say ""hi"", ok""#));
+        let back = from_csv(&text).unwrap();
+        // Rows read back from CSV carry every header column.
+        let mut first = src[0].clone();
+        first["extra"] = Value::Null;
+        assert_eq!(back[0], first);
+        assert_eq!(back[1]["s"], Value::from("7"));
+        assert_eq!(back[1]["extra"], Value::from(1000));
+        assert_eq!(back[1]["n"], Value::Null);
+    }
+
+    #[test]
+    fn parses_lf_and_crlf_and_quoted_newlines() {
+        let v = from_csv("a,b\n1,\"x\r\ny\"\r\n,abc").unwrap();
+        assert_eq!(v.to_string(), r#"[{"a":1,"b":"x\r\ny"},{"a":null,"b":"abc"}]"#);
+        assert_eq!(from_csv("").unwrap(), Value::Array(vec![]));
+        assert_eq!(from_csv("h\n").unwrap(), Value::Array(vec![]));
+        // A bare CR ends a line, as in old Mac files.
+        assert_eq!(from_csv("a,b\r1,x\r\"2\",y\r").unwrap().to_string(), r#"[{"a":1,"b":"x"},{"a":"2","b":"y"}]"#);
+        assert_eq!(from_csv("a\r\"x\ry\",1").unwrap_err(), CsvError::FieldCount { line: 2, expected: 1, found: 2 });
+        assert_eq!(from_csv("a\r\"x\ry\"\r1,2").unwrap_err(), CsvError::FieldCount { line: 4, expected: 1, found: 2 });
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(to_csv(&Value::from(1)), Err(CsvError::NotAnArray));
+        assert_eq!(to_csv(&from_str("[{},1]").unwrap()), Err(CsvError::NotAnObject { index: 1 }));
+        assert_eq!(
+            to_csv(&from_str(r#"[{"a":[1]}]"#).unwrap()),
+            Err(CsvError::NestedValue { index: 0, key: "a".into() })
+        );
+        assert_eq!(from_csv("a\n\"x"), Err(CsvError::UnterminatedQuote { line: 2 }));
+        assert_eq!(from_csv("a\nx\"y"), Err(CsvError::UnexpectedQuote { line: 2 }));
+        assert_eq!(from_csv("a\n\"x\"y"), Err(CsvError::UnexpectedQuote { line: 2 }));
+        assert_eq!(
+            from_csv("a,b\n\"1\n2\",3\n4"),
+            Err(CsvError::FieldCount { line: 4, expected: 2, found: 1 })
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Computes a JSON Patch (RFC 6902) that turns one `json::Value` into another.
+//!
+//! The diff walks both documents together. Objects are compared key by key;
+//! arrays are compared element by element after trimming a common prefix and
+//! suffix, so a single insertion or deletion yields a single operation. Any
+//! other difference becomes a `replace` of the whole value.
+
+use super::patch::PatchOp;
+use super::pointer::escape_token;
+use super::value::Value;
+
+/// Returns the operations that transform `from` into `to`.
+///
+/// Applying the result with `json::patch::apply` to `from` yields a document
+/// equal to `to`. Only `add`, `remove`, and `replace` operations are emitted.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json::{self, patch};
+/// let old = json::from_str(r#"{"name":"app","tags":["a","c"]}"#).unwrap();
+/// let new = json::from_str(r#"{"name":"api","tags":["a","b","c"]}"#).unwrap();
+///
+/// let ops = json::diff(&old, &new);
+/// let expected = r#"[{"op":"replace","path":"/name","value":"api"},{"op":"add","path":"/tags/1","value":"b"}]"#;
+/// assert_eq!(patch::to_value(&ops), json::from_str(expected).unwrap());
+///
+/// let mut doc = old.clone();
+/// patch::apply(&mut doc, &ops).unwrap();
+/// assert_eq!(doc, new);
+/// ```
+pub fn diff(from: &Value, to: &Value) -> Vec<PatchOp> {
+    let mut ops = Vec::new();
+    diff_at(from, to, &mut String::new(), &mut ops);
+    ops
+}
+
+fn diff_at(from: &Value, to: &Value, path: &mut String, ops: &mut Vec<PatchOp>) {
+    if from == to {
+        return;
+    }
+    match (from, to) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, _) in a.iter().filter(|(key, _)| !b.contains_key(key)) {
+                ops.push(PatchOp::Remove { path: child(path, &escape_token(key)) });
+            }
+            for (key, new) in b.iter() {
+                match a.get(key) {
+                    Some(old) => with_child(path, &escape_token(key), |path| diff_at(old, new, path, ops)),
+                    None => ops.push(PatchOp::Add { path: child(path, &escape_token(key)), value: new.clone() }),
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+            let max_suffix = a.len().min(b.len()) - prefix;
+            let suffix = a.iter().rev().zip(b.iter().rev()).take(max_suffix).take_while(|(x, y)| x == y).count();
+            let old = &a[prefix..a.len() - suffix];
+            let new = &b[prefix..b.len() - suffix];
+            let common = old.len().min(new.len());
+
+            for i in 0..common {
+                let index = (prefix + i).to_string();
+                with_child(path, &index, |path| diff_at(&old[i], &new[i], path, ops));
+            }
+            // Remove surplus elements back to front so earlier indices stay valid.
+            for i in (common..old.len()).rev() {
+                ops.push(PatchOp::Remove { path: child(path, &(prefix + i).to_string()) });
+            }
+            for (i, value) in new.iter().enumerate().skip(common) {
+                ops.push(PatchOp::Add { path: child(path, &(prefix + i).to_string()), value: value.clone() });
+            }
+        }
+        _ => ops.push(PatchOp::Replace { path: path.clone(), value: to.clone() }),
+    }
+}
+
+/// Returns the pointer of `token` under `path`.
+fn child(path: &str, token: &str) -> String {
+    format!("{}/{}", path, token)
+}
+
+/// Runs `f` with `token` temporarily appended to `path`.
+fn with_child(path: &mut String, token: &str, f: impl FnOnce(&mut String)) {
+    let len = path.len();
+    path.push('/');
+    path.push_str(token);
+    f(path);
+    path.truncate(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{from_str, patch};
+
+    fn round_trip(a: &str, b: &str) -> Vec<PatchOp> {
+        let (a, b) = (from_str(a).unwrap(), from_str(b).unwrap());
+        let ops = diff(&a, &b);
+        let mut doc = a.clone();
+        patch::apply(&mut doc, &ops).unwrap();
+        assert_eq!(doc, b, "patch {:?} did not transform {} into {}", ops, a, b);
+        ops
+    }
+
+    #[test]
+    fn identical_documents_produce_no_ops() {
+        assert!(round_trip(r#"{"a":[1,{"b":null}]}"#, r#"{"a":[1,{"b":null}]}"#).is_empty());
+        // Key order does not matter for object equality.
+        assert!(round_trip(r#"{"a":1,"b":2}"#, r#"{"b":2,"a":1}"#).is_empty());
+    }
+
+    #[test]
+    fn objects_and_scalars() {
+        let ops = round_trip(r#"{"a":1,"b":{"c":2},"x/y":0}"#, r#"{"b":{"c":3},"d":[],"x/y":1}"#);
+        assert_eq!(
+            ops,
+            [
+                PatchOp::Remove { path: "/a".into() },
+                PatchOp::Replace { path: "/b/c".into(), value: Value::from(3) },
+                PatchOp::Add { path: "/d".into(), value: Value::Array(vec![]) },
+                PatchOp::Replace { path: "/x~1y".into(), value: Value::from(1) },
+            ]
+        );
+        assert_eq!(round_trip("1", r#""one""#), [PatchOp::Replace { path: "".into(), value: Value::from("one") }]);
+    }
+
+    #[test]
+    fn arrays_use_prefix_and_suffix() {
+        assert_eq!(round_trip("[1,2,3]", "[0,1,2,3]"), [PatchOp::Add { path: "/0".into(), value: Value::from(0) }]);
+        assert_eq!(round_trip("[1,2,3,4]", "[1,4]"), [
+            PatchOp::Remove { path: "/2".into() },
+            PatchOp::Remove { path: "/1".into() },
+        ]);
+        round_trip("[1,1,1]", "[1,1]");
+        round_trip("[1,2,3]", "[4,5,6,7,8]");
+        round_trip("[[1,2],{\"a\":1}]", "[[1,3],{\"a\":2},5]");
+        round_trip("[]", "[1]");
+    }
+}
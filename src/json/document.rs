@@ -0,0 +1,362 @@
+//! An arena-backed parse mode for hot paths that parse many small documents.
+//!
+//! Parsing into a `Value` allocates separately for every string, array, and
+//! object. A `Document` instead stores a whole parsed tree in a handful of
+//! flat buffers that it owns: one for all string bytes, one for the nodes,
+//! and one each for array elements and object members. Parsing the next
+//! message into the same `Document` clears and reuses those buffers, so once
+//! they have grown to fit the typical message, parsing allocates nothing.
+//!
+//! Nodes are read through lightweight `Node` handles borrowed from the
+//! document; `Node::to_value` copies a subtree out when an owned `Value` is
+//! needed.
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json::Document;
+//!
+//! let mut doc = Document::new();
+//! for message in [r#"{"id": 1, "tags": ["a"]}"#, r#"{"id": 2, "tags": []}"#] {
+//!     let root = doc.parse(message).unwrap();
+//!     let id = root.get("id").and_then(|n| n.as_f64()).unwrap();
+//!     let tags = root.get("tags").unwrap();
+//!     println!("message {} has {} tags", id, tags.len());
+//! }
+//! ```
+
+use super::map::Map;
+use super::parser::{parse_document, Builder, ParseError, ParserOptions};
+use super::value::Value;
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+
+/// A node stored in the arena. Containers and strings refer to ranges of the
+/// document's shared buffers.
+#[derive(Debug, Clone)]
+enum Slot {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(Range<usize>),
+    Array(Range<usize>),
+    Object(Range<usize>),
+}
+
+/// A reusable arena holding one parsed JSON document.
+#[derive(Default)]
+pub struct Document {
+    /// Bytes of every string and object key, back to back.
+    text: String,
+    nodes: Vec<Slot>,
+    /// Node ids of array elements; each array owns a contiguous range.
+    elements: Vec<usize>,
+    /// Key ranges into `text` and node ids of object members; each object
+    /// owns a contiguous range.
+    members: Vec<(Range<usize>, usize)>,
+    /// Elements and members of containers that are still being parsed.
+    pending_elements: Vec<usize>,
+    pending_members: Vec<(Range<usize>, usize)>,
+    root: Option<usize>,
+}
+
+impl Document {
+    /// Creates an empty document. Its buffers grow on first use.
+    pub fn new() -> Self {
+        Document::default()
+    }
+
+    /// Parses `s` into this document, replacing its previous contents, and
+    /// returns the root node. Uses `ParserOptions::default()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ParseError` if the input string is not valid JSON. The
+    /// document is left empty.
+    pub fn parse(&mut self, s: &str) -> Result<Node<'_>, ParseError> {
+        self.parse_with(s, ParserOptions::default())
+    }
+
+    /// Parses `s` into this document with the given options, replacing its
+    /// previous contents, and returns the root node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ParseError` if the input string is not valid JSON or
+    /// violates one of the limits set in `options`. The document is left
+    /// empty.
+    pub fn parse_with(&mut self, s: &str, options: ParserOptions) -> Result<Node<'_>, ParseError> {
+        self.clear();
+        match parse_document(s, options, &mut *self) {
+            Ok(root) => {
+                self.root = Some(root);
+                Ok(Node { doc: self, id: root })
+            }
+            Err(e) => {
+                self.clear();
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the root node, or `None` if nothing has been parsed.
+    pub fn root(&self) -> Option<Node<'_>> {
+        self.root.map(|id| Node { doc: self, id })
+    }
+
+    /// Removes the parsed document, keeping the allocated buffers.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.nodes.clear();
+        self.elements.clear();
+        self.members.clear();
+        self.pending_elements.clear();
+        self.pending_members.clear();
+        self.root = None;
+    }
+
+    fn push(&mut self, slot: Slot) -> usize {
+        self.nodes.push(slot);
+        self.nodes.len() - 1
+    }
+
+    fn push_text(&mut self, s: &str) -> Range<usize> {
+        let start = self.text.len();
+        self.text.push_str(s);
+        start..self.text.len()
+    }
+}
+
+impl fmt::Debug for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Document").field("root", &self.root()).finish()
+    }
+}
+
+impl<'a> Builder<'a> for Document {
+    type Value = usize;
+    /// The start of the array's elements in `pending_elements`.
+    type Array = usize;
+    /// The start of the object's members in `pending_members`.
+    type Object = usize;
+
+    fn null(&mut self) -> usize {
+        self.push(Slot::Null)
+    }
+
+    fn bool(&mut self, b: bool) -> usize {
+        self.push(Slot::Bool(b))
+    }
+
+    fn number(&mut self, n: f64) -> usize {
+        self.push(Slot::Number(n))
+    }
+
+    fn string(&mut self, s: Cow<'a, str>) -> usize {
+        let range = self.push_text(&s);
+        self.push(Slot::String(range))
+    }
+
+    fn array_start(&mut self) -> usize {
+        self.pending_elements.len()
+    }
+
+    fn array_push(&mut self, _: &mut usize, value: usize) {
+        self.pending_elements.push(value);
+    }
+
+    // Nested containers always end before their parent resumes, so each
+    // container's pending items sit at the top of the pending stack.
+    fn array_end(&mut self, start: usize) -> usize {
+        let range = self.elements.len()..self.elements.len() + self.pending_elements.len() - start;
+        self.elements.extend(self.pending_elements.drain(start..));
+        self.push(Slot::Array(range))
+    }
+
+    fn object_start(&mut self) -> usize {
+        self.pending_members.len()
+    }
+
+    fn object_insert(&mut self, _: &mut usize, key: Cow<'a, str>, value: usize) {
+        let key = self.push_text(&key);
+        self.pending_members.push((key, value));
+    }
+
+    fn object_end(&mut self, start: usize) -> usize {
+        let range = self.members.len()..self.members.len() + self.pending_members.len() - start;
+        self.members.extend(self.pending_members.drain(start..));
+        self.push(Slot::Object(range))
+    }
+}
+
+/// A read-only handle to one node of a `Document`.
+#[derive(Clone, Copy)]
+pub struct Node<'d> {
+    doc: &'d Document,
+    id: usize,
+}
+
+impl<'d> Node<'d> {
+    fn slot(&self) -> &'d Slot {
+        &self.doc.nodes[self.id]
+    }
+
+    fn node(&self, id: usize) -> Node<'d> {
+        Node { doc: self.doc, id }
+    }
+
+    /// Returns `true` if the node is `null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self.slot(), Slot::Null)
+    }
+
+    /// Returns `true` if the node is an array.
+    pub fn is_array(&self) -> bool {
+        matches!(self.slot(), Slot::Array(_))
+    }
+
+    /// Returns `true` if the node is an object.
+    pub fn is_object(&self) -> bool {
+        matches!(self.slot(), Slot::Object(_))
+    }
+
+    /// If the node is a boolean, returns it.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.slot() {
+            Slot::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// If the node is a number, returns it.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.slot() {
+            Slot::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// If the node is a string, returns it, borrowed from the document.
+    pub fn as_str(&self) -> Option<&'d str> {
+        match self.slot() {
+            Slot::String(range) => Some(&self.doc.text[range.clone()]),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of elements of an array or members of an object,
+    /// and 0 for any other node.
+    pub fn len(&self) -> usize {
+        match self.slot() {
+            Slot::Array(range) | Slot::Object(range) => range.len(),
+            _ => 0,
+        }
+    }
+
+    /// Returns `true` if the node has no elements or members.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `index` if the node is an array.
+    pub fn get_index(&self, index: usize) -> Option<Node<'d>> {
+        match self.slot() {
+            Slot::Array(range) if index < range.len() => Some(self.node(self.doc.elements[range.start + index])),
+            _ => None,
+        }
+    }
+
+    /// Returns the member value under `key` if the node is an object. With
+    /// duplicate keys, the last one wins, as in `Value`.
+    pub fn get(&self, key: &str) -> Option<Node<'d>> {
+        self.members().rev().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    /// Iterates over the elements of an array. Yields nothing for any other
+    /// kind of node.
+    pub fn elements(&self) -> impl Iterator<Item = Node<'d>> + 'd {
+        let doc = self.doc;
+        let ids = match self.slot() {
+            Slot::Array(range) => &doc.elements[range.clone()],
+            _ => &[],
+        };
+        ids.iter().map(move |&id| Node { doc, id })
+    }
+
+    /// Iterates over the members of an object in document order. Yields
+    /// nothing for any other kind of node.
+    pub fn members(&self) -> impl DoubleEndedIterator<Item = (&'d str, Node<'d>)> + 'd {
+        let doc = self.doc;
+        let members = match self.slot() {
+            Slot::Object(range) => &doc.members[range.clone()],
+            _ => &[],
+        };
+        members.iter().map(move |(key, id)| (&doc.text[key.clone()], Node { doc, id: *id }))
+    }
+
+    /// Copies the subtree rooted at this node into an owned `Value`.
+    pub fn to_value(&self) -> Value {
+        match self.slot() {
+            Slot::Null => Value::Null,
+            Slot::Bool(b) => Value::Bool(*b),
+            Slot::Number(n) => Value::Number(*n),
+            Slot::String(range) => Value::String(self.doc.text[range.clone()].to_string()),
+            Slot::Array(_) => Value::Array(self.elements().map(|n| n.to_value()).collect()),
+            Slot::Object(range) => {
+                let mut map = Map::with_capacity(range.len());
+                for (key, node) in self.members() {
+                    map.insert(key, node.to_value());
+                }
+                Value::Object(map)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_value(), f)
+    }
+}
+
+impl fmt::Display for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_value(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    #[test]
+    fn matches_value_parser() {
+        let text = r#"{"a":[1,[true,null],{"b":"x\ny"}],"c":{},"d":[],"a":"dup"}"#;
+        let mut doc = Document::new();
+        let root = doc.parse(text).unwrap();
+        assert_eq!(root.to_value(), from_str(text).unwrap());
+        assert_eq!(root.len(), 4);
+        assert_eq!(root.get("a").and_then(|n| n.as_str()), Some("dup"));
+        let first = root.members().next().unwrap().1;
+        assert_eq!(first.get_index(1).and_then(|n| n.get_index(0)).and_then(|n| n.as_bool()), Some(true));
+        assert_eq!(first.get_index(2).and_then(|n| n.get("b")).and_then(|n| n.as_str()), Some("x\ny"));
+        assert!(first.get_index(3).is_none());
+        assert!(root.get("d").unwrap().is_empty());
+        assert_eq!(root.to_string(), r#"{"a":"dup","c":{},"d":[]}"#);
+    }
+
+    #[test]
+    fn reuses_buffers_between_parses() {
+        let mut doc = Document::new();
+        doc.parse(r#"[{"k":"long enough string"},[1,2,3],{"k":2}]"#).unwrap();
+        let capacity = (doc.text.capacity(), doc.nodes.capacity(), doc.elements.capacity());
+        let root = doc.parse(r#"[{"k":"short"},[4]]"#).unwrap();
+        assert_eq!(root.get_index(1).map(|n| n.to_value()), Some(from_str("[4]").unwrap()));
+        assert_eq!((doc.text.capacity(), doc.nodes.capacity(), doc.elements.capacity()), capacity);
+
+        assert!(doc.parse("[1,").is_err());
+        assert!(doc.root().is_none());
+    }
+}
@@ -0,0 +1,134 @@
+//! Implements `Index` and `IndexMut` for `json::Value`.
+//!
+//! Reading with `value["key"]` or `value[0]` never panics: a missing key, an
+//! out-of-bounds index, or a value of the wrong kind yields `Value::Null`.
+//! Writing with `value["key"] = ...` turns a `Null` into an empty object and
+//! inserts missing keys, so nested documents can be assembled in one line.
+
+use super::map::Map;
+use super::value::Value;
+use std::ops::{Index, IndexMut};
+
+static NULL: Value = Value::Null;
+
+impl Index<&str> for Value {
+    type Output = Value;
+
+    /// Returns the member named `key`, or `Value::Null` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::{self, Value};
+    /// let v = json::from_str(r#"{"config":{"port":8080}}"#).unwrap();
+    /// assert_eq!(v["config"]["port"], Value::Number(8080.0));
+    /// assert_eq!(v["config"]["host"], Value::Null);
+    /// ```
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl Index<usize> for Value {
+    type Output = Value;
+
+    /// Returns the element at `index`, or `Value::Null` if there is none.
+    fn index(&self, index: usize) -> &Value {
+        self.get_index(index).unwrap_or(&NULL)
+    }
+}
+
+impl IndexMut<&str> for Value {
+    /// Returns the member named `key`, inserting `Value::Null` if missing.
+    /// A `Null` receiver is first replaced by an empty object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the receiver is neither an object nor `null`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::Value;
+    /// let mut v = Value::Null;
+    /// v["server"]["port"] = Value::from(80);
+    /// assert_eq!(v.to_string(), r#"{"server":{"port":80}}"#);
+    /// ```
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        if self.is_null() {
+            *self = Value::Object(Map::new());
+        }
+        match self {
+            Value::Object(map) => {
+                if !map.contains_key(key) {
+                    map.insert(key, Value::Null);
+                }
+                map.get_mut(key).unwrap()
+            }
+            other => panic!("cannot index into a non-object JSON value with key {:?}: {}", key, other),
+        }
+    }
+}
+
+impl IndexMut<usize> for Value {
+    /// Returns the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the receiver is not an array or `index` is out of bounds.
+    fn index_mut(&mut self, index: usize) -> &mut Value {
+        match self {
+            Value::Array(arr) => {
+                let len = arr.len();
+                arr.get_mut(index).unwrap_or_else(|| {
+                    panic!("index {} out of bounds for JSON array of length {}", index, len)
+                })
+            }
+            other => panic!("cannot index into a non-array JSON value with {}: {}", index, other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::{from_str, Value};
+
+    #[test]
+    fn index_reads_return_null_for_missing_paths() {
+        let v = from_str(r#"{"a":[10,{"b":true}]}"#).unwrap();
+        assert_eq!(v["a"][0], Value::Number(10.0));
+        assert_eq!(v["a"][1]["b"], Value::Bool(true));
+        assert_eq!(v["a"][5], Value::Null);
+        assert_eq!(v["a"]["not-an-object"], Value::Null);
+        assert_eq!(v["missing"]["deeper"][3], Value::Null);
+    }
+
+    #[test]
+    fn index_mut_creates_intermediate_objects() {
+        let mut v = from_str(r#"{"a":{"x":1}}"#).unwrap();
+        v["a"]["y"] = Value::from(2);
+        v["b"]["c"]["d"] = Value::from("deep");
+        assert_eq!(v.to_string(), r#"{"a":{"x":1,"y":2},"b":{"c":{"d":"deep"}}}"#);
+    }
+
+    #[test]
+    fn index_mut_on_arrays() {
+        let mut v = from_str("[1,2,3]").unwrap();
+        v[1] = Value::Null;
+        assert_eq!(v.to_string(), "[1,null,3]");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn index_mut_out_of_bounds_panics() {
+        let mut v = from_str("[]").unwrap();
+        v[0] = Value::Null;
+    }
+
+    #[test]
+    #[should_panic(expected = "non-object")]
+    fn index_mut_key_on_scalar_panics() {
+        let mut v = Value::from(1);
+        v["a"] = Value::Null;
+    }
+}
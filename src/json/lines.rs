@@ -0,0 +1,231 @@
+//! Reads JSON Lines (one JSON value per line) from any `io::BufRead`.
+//!
+//! `lines` returns an iterator that parses one record at a time, so memory use
+//! depends on the longest line rather than the size of the file. A malformed
+//! record does not end the stream: it is reported with its line number and
+//! reading resumes on the next line. With `skip_malformed`, bad records are not
+//! yielded at all but counted, and the first few errors kept for inspection
+//! afterwards. Blank lines are ignored.
+
+use super::parser::{from_str_with, ParseError, ParserOptions};
+use super::value::Value;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+
+/// How many errors `Lines::skipped` keeps; later ones are only counted.
+const MAX_SKIPPED: usize = 100;
+
+/// An error for one record of a JSON Lines stream, tagged with its 1-based
+/// line number.
+#[derive(Debug)]
+pub enum ParseErrorWithLine {
+    /// The line is not valid JSON.
+    Parse { line: usize, error: ParseError },
+    /// Reading the line failed. Invalid UTF-8 on a line is reported as an
+    /// `io::ErrorKind::InvalidData` error, counts as a malformed record, and
+    /// reading continues; any other I/O error ends the iteration.
+    Io { line: usize, error: io::Error },
+}
+
+impl ParseErrorWithLine {
+    /// Returns the line number of the record that failed.
+    pub fn line(&self) -> usize {
+        match self {
+            ParseErrorWithLine::Parse { line, .. } | ParseErrorWithLine::Io { line, .. } => *line,
+        }
+    }
+
+    /// Returns `true` if the line was read but is not a valid record, as
+    /// opposed to an I/O error that ends the stream.
+    fn is_malformed(&self) -> bool {
+        match self {
+            ParseErrorWithLine::Parse { .. } => true,
+            ParseErrorWithLine::Io { error, .. } => error.kind() == io::ErrorKind::InvalidData,
+        }
+    }
+}
+
+impl Error for ParseErrorWithLine {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseErrorWithLine::Parse { error, .. } => Some(error),
+            ParseErrorWithLine::Io { error, .. } => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorWithLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorWithLine::Parse { line, error } => write!(f, "{} on line {}", error, line),
+            ParseErrorWithLine::Io { line, error } => write!(f, "{} on line {}", error, line),
+        }
+    }
+}
+
+/// Returns an iterator over the JSON values in `reader`, one per line.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json;
+/// let log = "{\"level\":\"info\"}\nnot json\n\n{\"level\":\"warn\"}\n";
+/// let mut records = json::lines(log.as_bytes()).skip_malformed(true);
+/// let levels: Vec<String> = records.by_ref().map(|r| r.unwrap()["level"].to_string()).collect();
+/// assert_eq!(levels, ["\"info\"", "\"warn\""]);
+/// assert_eq!(records.skipped()[0].line(), 2);
+/// ```
+pub fn lines<R: BufRead>(reader: R) -> Lines<R> {
+    Lines {
+        reader,
+        options: ParserOptions::default(),
+        skip_malformed: false,
+        skipped: Vec::new(),
+        skipped_count: 0,
+        line: 0,
+        buf: Vec::new(),
+        done: false,
+    }
+}
+
+/// Iterator returned by [`lines`].
+pub struct Lines<R> {
+    reader: R,
+    options: ParserOptions,
+    skip_malformed: bool,
+    skipped: Vec<ParseErrorWithLine>,
+    skipped_count: usize,
+    line: usize,
+    buf: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> Lines<R> {
+    /// Parses each record with the given options.
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// If `true`, malformed records, including lines that are not valid
+    /// UTF-8, are skipped instead of being yielded as errors. Other I/O
+    /// errors are always yielded.
+    pub fn skip_malformed(mut self, skip: bool) -> Self {
+        self.skip_malformed = skip;
+        self
+    }
+
+    /// Returns the errors of the first 100 records skipped.
+    pub fn skipped(&self) -> &[ParseErrorWithLine] {
+        &self.skipped
+    }
+
+    /// Returns the number of records skipped so far, including those whose
+    /// errors `skipped` no longer keeps.
+    pub fn skipped_count(&self) -> usize {
+        self.skipped_count
+    }
+
+    /// Returns the number of lines read so far.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Reads and parses the next non-blank line.
+    fn read_record(&mut self) -> Option<Result<Value, ParseErrorWithLine>> {
+        loop {
+            self.buf.clear();
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(ParseErrorWithLine::Io { line: self.line + 1, error }));
+                }
+            }
+            let line = self.line;
+            let text = match std::str::from_utf8(&self.buf) {
+                Ok(text) => text.trim(),
+                Err(e) => {
+                    let error = io::Error::new(io::ErrorKind::InvalidData, e);
+                    return Some(Err(ParseErrorWithLine::Io { line, error }));
+                }
+            };
+            if !text.is_empty() {
+                return Some(from_str_with(text, self.options).map_err(|error| ParseErrorWithLine::Parse { line, error }));
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<Value, ParseErrorWithLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.read_record()? {
+                Err(e) if self.skip_malformed && e.is_malformed() => {
+                    self.skipped_count += 1;
+                    if self.skipped.len() < MAX_SKIPPED {
+                        self.skipped.push(e);
+                    }
+                }
+                record => return Some(record),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_malformed_lines_and_continues() {
+        let input = b"1\r\n[2,\n  \n\xff\n{\"a\":3}";
+        let records: Vec<_> = lines(&input[..]).collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].as_ref().unwrap(), &Value::from(1));
+        assert!(matches!(
+            records[1],
+            Err(ParseErrorWithLine::Parse { line: 2, error: ParseError::UnexpectedEndOfInput })
+        ));
+        assert!(matches!(&records[2], Err(ParseErrorWithLine::Io { line: 4, error }) if error.kind() == io::ErrorKind::InvalidData));
+        assert_eq!(records[3].as_ref().unwrap()["a"], Value::from(3));
+        assert_eq!(records[1].as_ref().unwrap_err().to_string(), "Unexpected end of input on line 2");
+    }
+
+    #[test]
+    fn skips_malformed_lines_and_stops_on_io_errors() {
+        let mut it = lines("{}\n{x}\n// c\n[]\n".as_bytes()).skip_malformed(true);
+        assert_eq!(it.by_ref().count(), 2);
+        let skipped: Vec<usize> = it.skipped().iter().map(|e| e.line()).collect();
+        assert_eq!(skipped, [2, 3]);
+        assert_eq!(it.line(), 4);
+
+        // Undecodable lines are skipped too, and only the first errors are kept.
+        let input = [&b"\xff\n"[..], &b"x\n".repeat(MAX_SKIPPED), b"1\n"].concat();
+        let mut it = lines(&input[..]).skip_malformed(true);
+        assert_eq!(it.by_ref().map(Result::unwrap).collect::<Vec<_>>(), [Value::from(1)]);
+        assert_eq!(it.skipped_count(), MAX_SKIPPED + 1);
+        assert_eq!(it.skipped().len(), MAX_SKIPPED);
+        assert!(matches!(&it.skipped()[0], ParseErrorWithLine::Io { line: 1, .. }));
+
+        let opts = ParserOptions { allow_comments: true, ..Default::default() };
+        let mut it = lines("// c\n".as_bytes()).with_options(opts);
+        assert!(matches!(it.next(), Some(Err(ParseErrorWithLine::Parse { line: 1, .. }))));
+
+        struct Broken;
+        impl io::Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk gone"))
+            }
+        }
+        let mut it = lines(io::BufReader::new(Broken));
+        assert!(matches!(it.next(), Some(Err(ParseErrorWithLine::Io { line: 1, .. }))));
+        assert!(it.next().is_none());
+    }
+}
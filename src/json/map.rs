@@ -0,0 +1,444 @@
+//! Defines `Map`, the insertion-ordered container backing `Value::Object`.
+//!
+//! Entries live in a `Vec` in the order they were first inserted, so parsing
+//! and serializing a document keeps its keys where the author put them. A side
+//! index from key to position keeps lookups O(1). Keys are stored as
+//! `Arc<str>` so the ordered storage and the index share one allocation.
+
+use super::value::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::FromIterator;
+use std::sync::Arc;
+
+/// An insertion-ordered map from `String` keys to `Value`s.
+///
+/// Inserting an existing key replaces its value but keeps its original
+/// position. Two maps compare equal when they hold the same key-value pairs,
+/// regardless of order.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json::{Map, Value};
+/// let mut map = Map::new();
+/// map.insert("b", Value::from(1));
+/// map.insert("a", Value::from(2));
+/// let keys: Vec<&str> = map.keys().collect();
+/// assert_eq!(keys, ["b", "a"]);
+/// ```
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: Vec<(Arc<str>, Value)>,
+    index: HashMap<Arc<str>, usize>,
+}
+
+impl Map {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Map::default()
+    }
+
+    /// Creates an empty map with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Map {
+            entries: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.index.clear();
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Returns a reference to the value stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.index.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    /// Returns a mutable reference to the value stored under `key`.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        match self.index.get(key) {
+            Some(&i) => Some(&mut self.entries[i].1),
+            None => None,
+        }
+    }
+
+    /// Inserts a key-value pair, returning the previous value if the key was
+    /// already present. An existing key keeps its position.
+    pub fn insert<K: Into<Arc<str>>>(&mut self, key: K, value: Value) -> Option<Value> {
+        let key = key.into();
+        match self.index.get(&key) {
+            Some(&i) => Some(std::mem::replace(&mut self.entries[i].1, value)),
+            None => {
+                self.index.insert(Arc::clone(&key), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes `key` from the map, returning its value. The relative order of
+    /// the remaining entries is preserved.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let i = self.index.remove(key)?;
+        let (_, value) = self.entries.remove(i);
+        for (k, _) in &self.entries[i..] {
+            if let Some(pos) = self.index.get_mut(k) {
+                *pos -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    /// Gets the entry for `key`, for in-place insertion or update.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::{Map, Value};
+    /// let mut counts = Map::new();
+    /// for word in ["a", "b", "a"] {
+    ///     let n = counts.entry(word).or_insert(Value::from(0));
+    ///     *n = Value::from(n.as_f64().unwrap() + 1.0);
+    /// }
+    /// assert_eq!(counts.get("a"), Some(&Value::from(2)));
+    /// ```
+    pub fn entry<K: Into<Arc<str>>>(&mut self, key: K) -> Entry<'_> {
+        let key = key.into();
+        match self.index.get(&key) {
+            Some(&index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+
+    /// Iterates over the entries in insertion order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { inner: self.entries.iter() }
+    }
+
+    /// Iterates mutably over the entries in insertion order.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut { inner: self.entries.iter_mut() }
+    }
+
+    /// Iterates over the keys in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(k, _)| &**k)
+    }
+
+    /// Iterates over the values in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Iterates mutably over the values in insertion order.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Approximates the bytes this map owns on the heap, excluding the heap
+    /// memory of its values: the entry vector, the shared key allocations,
+    /// and the index table.
+    pub(crate) fn heap_size(&self) -> usize {
+        let entries = self.entries.capacity() * std::mem::size_of::<(Arc<str>, Value)>();
+        // Each Arc<str> allocation holds the strong and weak counts before the bytes.
+        let keys: usize = self.entries.iter().map(|(k, _)| 2 * std::mem::size_of::<usize>() + k.len()).sum();
+        // One control byte per bucket, in addition to the slot itself.
+        let index = self.index.capacity() * (std::mem::size_of::<(Arc<str>, usize)>() + 1);
+        entries + keys + index
+    }
+}
+
+/// A view into a single entry of a `Map`, obtained from [`Map::entry`].
+pub enum Entry<'a> {
+    /// The key is present.
+    Occupied(OccupiedEntry<'a>),
+    /// The key is absent.
+    Vacant(VacantEntry<'a>),
+}
+
+/// An entry whose key is present in the map.
+pub struct OccupiedEntry<'a> {
+    map: &'a mut Map,
+    index: usize,
+}
+
+/// An entry whose key is absent from the map.
+pub struct VacantEntry<'a> {
+    map: &'a mut Map,
+    key: Arc<str>,
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the entry's key.
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    /// Inserts `default` if the key is absent, then returns the value.
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the key is absent, then returns the value.
+    pub fn or_insert_with<F: FnOnce() -> Value>(self, default: F) -> &'a mut Value {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    /// Calls `f` on the value if the key is present.
+    pub fn and_modify<F: FnOnce(&mut Value)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    /// Returns the entry's key.
+    pub fn key(&self) -> &str {
+        &self.map.entries[self.index].0
+    }
+
+    /// Returns a reference to the value.
+    pub fn get(&self) -> &Value {
+        &self.map.entries[self.index].1
+    }
+
+    /// Returns a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut Value {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Converts the entry into a mutable reference tied to the map.
+    pub fn into_mut(self) -> &'a mut Value {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: Value) -> Value {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map, returning its value.
+    pub fn remove(self) -> Value {
+        let key = Arc::clone(&self.map.entries[self.index].0);
+        self.map.remove(&key).unwrap()
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Returns the key that would be inserted.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Inserts `value` at the end of the map and returns a reference to it.
+    pub fn insert(self, value: Value) -> &'a mut Value {
+        let index = self.map.entries.len();
+        self.map.insert(self.key, value);
+        &mut self.map.entries[index].1
+    }
+}
+
+impl PartialEq for Map {
+    /// Two maps are equal when they contain the same pairs, in any order.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Into<Arc<str>>> FromIterator<(K, Value)> for Map {
+    fn from_iter<I: IntoIterator<Item = (K, Value)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Into<Arc<str>>> Extend<(K, Value)> for Map {
+    fn extend<I: IntoIterator<Item = (K, Value)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+/// Borrowing iterator over the entries of a [`Map`].
+pub struct Iter<'a> {
+    inner: std::slice::Iter<'a, (Arc<str>, Value)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a str, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (&**k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Mutable iterator over the entries of a [`Map`].
+pub struct IterMut<'a> {
+    inner: std::slice::IterMut<'a, (Arc<str>, Value)>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a str, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (&**k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Owning iterator over the entries of a [`Map`].
+pub struct IntoIter {
+    inner: std::vec::IntoIter<(Arc<str>, Value)>,
+}
+
+impl Iterator for IntoIter {
+    type Item = (String, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (k.to_string(), v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Value);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter { inner: self.entries.into_iter() }
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (&'a str, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Map {
+    type Item = (&'a str, &'a mut Value);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_insertion_order() {
+        let mut m = Map::new();
+        m.insert("z", Value::Null);
+        m.insert("a", Value::Null);
+        m.insert("m", Value::Null);
+        assert_eq!(m.keys().collect::<Vec<_>>(), ["z", "a", "m"]);
+    }
+
+    #[test]
+    fn insert_existing_key_replaces_in_place() {
+        let mut m = Map::new();
+        m.insert("a", Value::Number(1.0));
+        m.insert("b", Value::Number(2.0));
+        let old = m.insert("a", Value::Number(3.0));
+        assert_eq!(old, Some(Value::Number(1.0)));
+        assert_eq!(m.len(), 2);
+        assert_eq!(m.keys().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(m.get("a"), Some(&Value::Number(3.0)));
+    }
+
+    #[test]
+    fn remove_preserves_order_and_lookups() {
+        let mut m: Map = ["a", "b", "c", "d"]
+            .iter()
+            .map(|k| (*k, Value::from(*k)))
+            .collect();
+        assert_eq!(m.remove("b"), Some(Value::from("b")));
+        assert_eq!(m.remove("b"), None);
+        assert_eq!(m.keys().collect::<Vec<_>>(), ["a", "c", "d"]);
+        assert_eq!(m.get("c"), Some(&Value::from("c")));
+        assert_eq!(m.get("d"), Some(&Value::from("d")));
+    }
+
+    #[test]
+    fn entry_inserts_updates_and_removes() {
+        let mut m = Map::new();
+        m.entry("a").or_insert(Value::from(1));
+        m.entry("b").or_insert_with(|| Value::from(2));
+        m.entry("a").and_modify(|v| *v = Value::from(10)).or_insert(Value::Null);
+        assert_eq!(m.get("a"), Some(&Value::from(10)));
+
+        match m.entry("a") {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.key(), "a");
+                assert_eq!(e.insert(Value::from(11)), Value::from(10));
+                assert_eq!(e.remove(), Value::from(11));
+            }
+            Entry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+        assert_eq!(m.entry("c").key(), "c");
+        m.entry("c").or_insert(Value::Null);
+        assert_eq!(m.keys().collect::<Vec<_>>(), ["b", "c"]);
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let a: Map = vec![("x", Value::Bool(true)), ("y", Value::Null)].into_iter().collect();
+        let b: Map = vec![("y", Value::Null), ("x", Value::Bool(true))].into_iter().collect();
+        assert_eq!(a, b);
+
+        let c: Map = vec![("x", Value::Bool(false)), ("y", Value::Null)].into_iter().collect();
+        assert_ne!(a, c);
+    }
+}
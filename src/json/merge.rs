@@ -0,0 +1,142 @@
+//! Deep merging of `json::Value` documents.
+//!
+//! Merging `other` into `self` walks both documents together: objects are
+//! merged key by key, and any other pair of values is resolved by
+//! `MergeStrategy`. This is the usual way to layer configuration files, e.g.
+//! defaults, then a site file, then per-user overrides.
+
+use super::value::Value;
+
+/// How two arrays at the same location are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayMerge {
+    /// The incoming array replaces the existing one.
+    #[default]
+    Replace,
+    /// The incoming elements are appended to the existing ones.
+    Concat,
+    /// Incoming elements are appended unless an equal element is already present.
+    Union,
+}
+
+/// Options controlling `Value::merge`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MergeStrategy {
+    /// How arrays are combined. Defaults to `ArrayMerge::Replace`.
+    pub arrays: ArrayMerge,
+    /// If `true`, a `null` member in the incoming object removes the key,
+    /// and `null` members of incoming objects that are added whole are
+    /// dropped, as in JSON Merge Patch (RFC 7396). Defaults to `false`,
+    /// which stores the `null`.
+    pub null_removes: bool,
+}
+
+impl Value {
+    /// Merges `other` into `self`.
+    ///
+    /// Objects are merged recursively: keys only in `other` are appended,
+    /// and keys present in both are merged again. Arrays are combined
+    /// according to `strategy.arrays`. In every other case the value from
+    /// `other` wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::{self, ArrayMerge, MergeStrategy};
+    /// let mut config = json::from_str(r#"{"db":{"host":"localhost","port":5432},"tags":["a"]}"#).unwrap();
+    /// let site = json::from_str(r#"{"db":{"host":"db.internal"},"tags":["b"]}"#).unwrap();
+    /// config.merge(site, MergeStrategy { arrays: ArrayMerge::Concat, ..Default::default() });
+    /// assert_eq!(config.to_string(), r#"{"db":{"host":"db.internal","port":5432},"tags":["a","b"]}"#);
+    /// ```
+    pub fn merge(&mut self, other: Value, strategy: MergeStrategy) {
+        match (self, other) {
+            (Value::Object(base), Value::Object(overlay)) => {
+                for (key, value) in overlay {
+                    if strategy.null_removes && value.is_null() {
+                        base.remove(&key);
+                        continue;
+                    }
+                    match base.get_mut(&key) {
+                        Some(existing) => existing.merge(value, strategy),
+                        None => {
+                            let mut value = value;
+                            if strategy.null_removes {
+                                strip_nulls(&mut value);
+                            }
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (Value::Array(base), Value::Array(overlay)) => match strategy.arrays {
+                ArrayMerge::Replace => *base = overlay,
+                ArrayMerge::Concat => base.extend(overlay),
+                ArrayMerge::Union => {
+                    for value in overlay {
+                        if !base.contains(&value) {
+                            base.push(value);
+                        }
+                    }
+                }
+            },
+            (this, mut other) => {
+                if strategy.null_removes {
+                    strip_nulls(&mut other);
+                }
+                *this = other;
+            }
+        }
+    }
+}
+
+/// Removes `null` members from the objects in `value`, at any depth, as a
+/// merge patch does for values it adds. Arrays are kept as they are.
+fn strip_nulls(value: &mut Value) {
+    if let Value::Object(map) = value {
+        *map = std::mem::take(map).into_iter().filter(|(_, v)| !v.is_null()).collect();
+        map.values_mut().for_each(strip_nulls);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    fn merged(base: &str, overlay: &str, strategy: MergeStrategy) -> String {
+        let mut v = from_str(base).unwrap();
+        v.merge(from_str(overlay).unwrap(), strategy);
+        v.to_string()
+    }
+
+    #[test]
+    fn objects_merge_recursively() {
+        let s = MergeStrategy::default();
+        assert_eq!(merged(r#"{"a":{"x":1,"y":2},"b":1}"#, r#"{"a":{"y":3,"z":4},"c":5}"#, s),
+            r#"{"a":{"x":1,"y":3,"z":4},"b":1,"c":5}"#);
+        assert_eq!(merged(r#"{"a":{"x":1}}"#, r#"{"a":7}"#, s), r#"{"a":7}"#);
+        assert_eq!(merged(r#"{"a":1}"#, r#"{"a":null}"#, s), r#"{"a":null}"#);
+        assert_eq!(merged("1", r#"{"a":1}"#, s), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn array_strategies() {
+        let with = |arrays| MergeStrategy { arrays, ..Default::default() };
+        assert_eq!(merged("[1,2]", "[2,3]", with(ArrayMerge::Replace)), "[2,3]");
+        assert_eq!(merged("[1,2]", "[2,3]", with(ArrayMerge::Concat)), "[1,2,2,3]");
+        assert_eq!(merged("[1,2]", "[2,3,3]", with(ArrayMerge::Union)), "[1,2,3]");
+        assert_eq!(merged(r#"{"l":[{"a":1}]}"#, r#"{"l":[{"a":1},{"a":2}]}"#, with(ArrayMerge::Union)),
+            r#"{"l":[{"a":1},{"a":2}]}"#);
+    }
+
+    #[test]
+    fn null_removes_keys_when_enabled() {
+        let s = MergeStrategy { null_removes: true, ..Default::default() };
+        assert_eq!(merged(r#"{"a":1,"b":{"c":2,"d":3}}"#, r#"{"a":null,"b":{"c":null}}"#, s), r#"{"b":{"d":3}}"#);
+        // Nulls inside newly added or replaced objects are dropped too.
+        assert_eq!(merged(r#"{"a":1}"#, r#"{"b":{"c":null,"d":{"e":null,"f":[null]}}}"#, s),
+            r#"{"a":1,"b":{"d":{"f":[null]}}}"#);
+        assert_eq!(merged(r#"{"a":[1]}"#, r#"{"a":{"b":null,"c":1}}"#, s), r#"{"a":{"c":1}}"#);
+        assert_eq!(merged("1", r#"{"a":null}"#, s), "{}");
+    }
+}
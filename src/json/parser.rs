@@ -4,17 +4,19 @@
 //! This module provides the `from_str` function, which serves as the public
 //! entry point for parsing a JSON string into a `json::Value`. It defines
 //! a `ParseError` enum for detailed error reporting and a `Parser` struct
-//! that implements a recursive descent parser.
+//! that implements a descent parser driven by an explicit stack. `from_str_with` accepts
+//! `ParserOptions` to tune limits such as the maximum nesting depth.
 
+use super::map::Map;
 use super::value::Value;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt;
-use std::iter::Peekable;
-use std::str::Chars;
+use std::sync::Arc;
 
 /// An error that can occur during JSON parsing.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParseError {
     /// The input ended unexpectedly.
     UnexpectedEndOfInput,
@@ -30,6 +32,20 @@ pub enum ParseError {
     InvalidLiteral(String),
     /// Trailing characters were found after a valid JSON value.
     TrailingCharacters,
+    /// Arrays and objects were nested deeper than `ParserOptions::max_depth`.
+    MaxDepthExceeded,
+    /// A `\uXXXX` escape encoded half of a UTF-16 surrogate pair without the
+    /// other half (see `ParserOptions::lone_surrogates`).
+    LoneSurrogate(u16),
+    /// A string or key was longer than `ParserOptions::max_string_len`.
+    MaxStringLengthExceeded,
+    /// An array or object had more items than `ParserOptions::max_elements`.
+    MaxElementsExceeded,
+    /// The document had more values than `ParserOptions::max_nodes`.
+    MaxNodesExceeded,
+    /// The input to `from_slice` was not valid UTF-8. Holds the byte offset
+    /// of the first invalid sequence.
+    InvalidUtf8(usize),
 }
 
 // By implementing the std::error::Error trait, ParseError becomes a type
@@ -46,19 +62,200 @@ impl fmt::Display for ParseError {
             ParseError::InvalidNumber => write!(f, "Invalid number format"),
             ParseError::InvalidLiteral(s) => write!(f, "Invalid literal: {}", s),
             ParseError::TrailingCharacters => write!(f, "Trailing characters after valid JSON"),
+            ParseError::MaxDepthExceeded => write!(f, "Maximum nesting depth exceeded"),
+            ParseError::LoneSurrogate(u) => write!(f, "Unpaired UTF-16 surrogate: '\\u{:04x}'", u),
+            ParseError::MaxStringLengthExceeded => write!(f, "Maximum string length exceeded"),
+            ParseError::MaxElementsExceeded => write!(f, "Maximum number of elements exceeded"),
+            ParseError::MaxNodesExceeded => write!(f, "Maximum number of values exceeded"),
+            ParseError::InvalidUtf8(at) => write!(f, "Invalid UTF-8 at byte {}", at),
         }
     }
 }
 
-/// Parses a JSON string slice into a `Value`.
+/// Options controlling how `from_str_with` parses its input.
+///
+/// The default is strict RFC 8259 JSON. The `allow_*` flags opt into the
+/// JSON5/JSONC extensions commonly found in hand-written config files;
+/// [`ParserOptions::lenient`] enables all of them at once.
+///
+/// The `max_*` limits bound the memory an untrusted document can make the
+/// parser allocate. Apart from `max_depth` they are unlimited by default.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json::{from_str_with, ParseError, ParserOptions};
+/// let opts = ParserOptions { max_depth: 2, ..Default::default() };
+/// assert!(from_str_with("[[1]]", opts).is_ok());
+/// assert_eq!(from_str_with("[[[1]]]", opts), Err(ParseError::MaxDepthExceeded));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Maximum nesting depth of arrays and objects. Defaults to 128.
+    pub max_depth: usize,
+    /// Maximum length in bytes of a string or object key, after decoding
+    /// escapes. Defaults to `usize::MAX`.
+    pub max_string_len: usize,
+    /// Maximum number of elements in a single array, or members in a single
+    /// object. Defaults to `usize::MAX`.
+    pub max_elements: usize,
+    /// Maximum number of values in the whole document, counting every
+    /// array, object, and scalar (but not object keys). Defaults to
+    /// `usize::MAX`.
+    pub max_nodes: usize,
+    /// Accept `// line` and `/* block */` comments wherever whitespace is allowed.
+    pub allow_comments: bool,
+    /// Accept a trailing comma after the last array item or object member.
+    pub allow_trailing_commas: bool,
+    /// Accept object keys written as bare identifiers (`{ name: 1 }`).
+    pub allow_unquoted_keys: bool,
+    /// Accept strings delimited by single quotes (`'text'`).
+    pub allow_single_quotes: bool,
+    /// What to do with a `\uXXXX` escape that is half of a UTF-16 surrogate
+    /// pair but is not paired. Defaults to `LoneSurrogate::Error`.
+    pub lone_surrogates: LoneSurrogate,
+    /// Make `from_slice_with` replace invalid UTF-8 sequences with U+FFFD
+    /// REPLACEMENT CHARACTER instead of failing with `ParseError::InvalidUtf8`.
+    pub lossy_utf8: bool,
+    /// Share one allocation between all equal object keys in the parsed
+    /// `Value`, shrinking large arrays of uniform records. Defaults to `false`.
+    pub intern_keys: bool,
+}
+
+/// Policy for unpaired UTF-16 surrogates in `\uXXXX` escapes.
+///
+/// A well-formed pair such as `\ud83d\ude00` always decodes to one character
+/// (here U+1F600). A lone half cannot be stored in a Rust `String`, so it is
+/// either rejected or replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoneSurrogate {
+    /// Fail with `ParseError::LoneSurrogate`.
+    #[default]
+    Error,
+    /// Substitute U+FFFD REPLACEMENT CHARACTER.
+    Replace,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            max_depth: 128,
+            max_string_len: usize::MAX,
+            max_elements: usize::MAX,
+            max_nodes: usize::MAX,
+            allow_comments: false,
+            allow_trailing_commas: false,
+            allow_unquoted_keys: false,
+            allow_single_quotes: false,
+            lone_surrogates: LoneSurrogate::Error,
+            lossy_utf8: false,
+            intern_keys: false,
+        }
+    }
+}
+
+impl ParserOptions {
+    /// Options accepting comments, trailing commas, unquoted keys, and
+    /// single-quoted strings, for reading tsconfig-style config files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::{from_str_with, ParserOptions};
+    /// let src = r#"{
+    ///     // compiler settings
+    ///     target: 'es2020',
+    ///     strict: true, /* trailing comma below */
+    /// }"#;
+    /// let v = from_str_with(src, ParserOptions::lenient()).unwrap();
+    /// assert_eq!(v.to_string(), r#"{"target":"es2020","strict":true}"#);
+    /// ```
+    pub fn lenient() -> Self {
+        ParserOptions {
+            allow_comments: true,
+            allow_trailing_commas: true,
+            allow_unquoted_keys: true,
+            allow_single_quotes: true,
+            ..ParserOptions::default()
+        }
+    }
+}
+
+/// Parses a JSON string slice into a `Value`, using `ParserOptions::default()`.
 ///
 /// # Errors
 ///
 /// Returns a `ParseError` if the input string is not valid JSON.
 pub fn from_str(s: &str) -> Result<Value, ParseError> {
-    let mut parser = Parser::new(s);
-    let value = parser.parse_value()?;
-    parser.consume_whitespace();
+    from_str_with(s, ParserOptions::default())
+}
+
+/// Parses a JSON string slice into a `Value` with the given options.
+///
+/// # Errors
+///
+/// Returns a `ParseError` if the input string is not valid JSON or violates
+/// one of the limits set in `options`.
+pub fn from_str_with(s: &str, options: ParserOptions) -> Result<Value, ParseError> {
+    let mut builder = ValueBuilder { keys: options.intern_keys.then(HashSet::new) };
+    parse_document(s, options, &mut builder)
+}
+
+/// Parses UTF-8 encoded bytes into a `Value`, using `ParserOptions::default()`.
+///
+/// # Errors
+///
+/// Returns `ParseError::InvalidUtf8` if the input is not valid UTF-8, or
+/// another `ParseError` if it is not valid JSON.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json::{self, ParseError};
+/// let v = json::from_slice(b"{\"ok\":true}").unwrap();
+/// assert_eq!(v.to_string(), r#"{"ok":true}"#);
+/// assert_eq!(json::from_slice(b"[\"\xff\"]"), Err(ParseError::InvalidUtf8(2)));
+/// ```
+pub fn from_slice(bytes: &[u8]) -> Result<Value, ParseError> {
+    from_slice_with(bytes, ParserOptions::default())
+}
+
+/// Parses UTF-8 encoded bytes into a `Value` with the given options.
+///
+/// With `ParserOptions::lossy_utf8` set, invalid sequences are decoded as
+/// U+FFFD REPLACEMENT CHARACTER before parsing.
+///
+/// # Errors
+///
+/// Returns a `ParseError` if the input is not valid UTF-8 (unless decoding
+/// lossily), is not valid JSON, or violates one of the limits set in `options`.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json::{from_slice_with, ParserOptions};
+/// let opts = ParserOptions { lossy_utf8: true, ..Default::default() };
+/// let v = from_slice_with(b"[\"caf\xe9\"]", opts).unwrap();
+/// assert_eq!(v[0].as_str(), Some("caf\u{fffd}"));
+/// ```
+pub fn from_slice_with(bytes: &[u8], options: ParserOptions) -> Result<Value, ParseError> {
+    let s = if options.lossy_utf8 {
+        String::from_utf8_lossy(bytes)
+    } else {
+        Cow::Borrowed(std::str::from_utf8(bytes).map_err(|e| ParseError::InvalidUtf8(e.valid_up_to()))?)
+    };
+    from_str_with(&s, options)
+}
+
+/// Parses a complete document with `builder`, rejecting trailing input.
+pub(crate) fn parse_document<'a, B: Builder<'a>>(
+    s: &'a str,
+    options: ParserOptions,
+    builder: &mut B,
+) -> Result<B::Value, ParseError> {
+    let mut parser = Parser::new(s, options);
+    let value = parser.parse_value(builder)?;
+    parser.consume_whitespace()?;
     if parser.peek().is_some() {
         // If there's more content after a valid value, it's an error.
         Err(ParseError::TrailingCharacters)
@@ -67,179 +264,529 @@ pub fn from_str(s: &str) -> Result<Value, ParseError> {
     }
 }
 
+/// Assembles the values recognized by the parser into a document tree.
+///
+/// The parser drives a `Builder` instead of constructing `Value`s itself, so
+/// the same grammar, options, and errors serve every document representation.
+/// Strings arrive as `Cow::Borrowed` slices of the input whenever they contain
+/// no escape sequences.
+pub(crate) trait Builder<'a> {
+    /// A finished value of any kind.
+    type Value;
+    /// An array whose elements are still being pushed.
+    type Array;
+    /// An object whose members are still being inserted.
+    type Object;
+
+    fn null(&mut self) -> Self::Value;
+    fn bool(&mut self, b: bool) -> Self::Value;
+    fn number(&mut self, n: f64) -> Self::Value;
+    fn string(&mut self, s: Cow<'a, str>) -> Self::Value;
+    fn array_start(&mut self) -> Self::Array;
+    fn array_push(&mut self, array: &mut Self::Array, value: Self::Value);
+    fn array_end(&mut self, array: Self::Array) -> Self::Value;
+    fn object_start(&mut self) -> Self::Object;
+    fn object_insert(&mut self, object: &mut Self::Object, key: Cow<'a, str>, value: Self::Value);
+    fn object_end(&mut self, object: Self::Object) -> Self::Value;
+}
+
+/// Builds owned `Value`s.
+struct ValueBuilder {
+    /// Keys seen so far, when `ParserOptions::intern_keys` is set.
+    keys: Option<HashSet<Arc<str>>>,
+}
+
+impl<'a> Builder<'a> for ValueBuilder {
+    type Value = Value;
+    type Array = Vec<Value>;
+    type Object = Map;
+
+    fn null(&mut self) -> Value {
+        Value::Null
+    }
+
+    fn bool(&mut self, b: bool) -> Value {
+        Value::Bool(b)
+    }
+
+    fn number(&mut self, n: f64) -> Value {
+        Value::Number(n)
+    }
+
+    fn string(&mut self, s: Cow<'a, str>) -> Value {
+        Value::String(s.into_owned())
+    }
+
+    fn array_start(&mut self) -> Vec<Value> {
+        Vec::new()
+    }
+
+    fn array_push(&mut self, array: &mut Vec<Value>, value: Value) {
+        array.push(value);
+    }
+
+    fn array_end(&mut self, array: Vec<Value>) -> Value {
+        Value::Array(array)
+    }
+
+    fn object_start(&mut self) -> Map {
+        Map::new()
+    }
+
+    fn object_insert(&mut self, object: &mut Map, key: Cow<'a, str>, value: Value) {
+        let key: Arc<str> = match &mut self.keys {
+            Some(keys) => match keys.get(&*key) {
+                Some(shared) => Arc::clone(shared),
+                None => {
+                    let shared: Arc<str> = key.into();
+                    keys.insert(Arc::clone(&shared));
+                    shared
+                }
+            },
+            None => key.into(),
+        };
+        object.insert(key, value);
+    }
+
+    fn object_end(&mut self, object: Map) -> Value {
+        Value::Object(object)
+    }
+}
+
+/// An array or object whose items are still being parsed.
+enum Frame<'a, A, O> {
+    /// The array so far and its element count.
+    Array(A, usize),
+    /// The object so far, the key awaiting its value, and the member count.
+    Object(O, Cow<'a, str>, usize),
+}
+
+/// Returns `true` if `c` may start an unquoted object key.
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
 struct Parser<'a> {
-    chars: Peekable<Chars<'a>>,
+    input: &'a str,
+    /// `input` as bytes. Everything the grammar cares about is ASCII, so the
+    /// parser scans bytes and only decodes characters for error messages and
+    /// the Unicode-aware checks in whitespace, literals, and unquoted keys.
+    bytes: &'a [u8],
+    /// Byte offset of the next unconsumed character; always a char boundary.
+    pos: usize,
+    options: ParserOptions,
+    depth: usize,
+    /// Values parsed so far, checked against `max_nodes`.
+    nodes: usize,
 }
 
 impl<'a> Parser<'a> {
     /// Creates a new parser for the given input string.
-    fn new(input: &'a str) -> Self {
+    fn new(input: &'a str, options: ParserOptions) -> Self {
         Parser {
-            chars: input.chars().peekable(),
+            input,
+            bytes: input.as_bytes(),
+            pos: 0,
+            options,
+            depth: 0,
+            nodes: 0,
         }
     }
 
+    /// Enters one level of array/object nesting, enforcing `max_depth`.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        if self.depth >= self.options.max_depth {
+            return Err(ParseError::MaxDepthExceeded);
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
     /// Retrieves the next character from the input stream.
     fn next(&mut self) -> Option<char> {
-        self.chars.next()
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
     }
 
     /// Peeks at the next character without consuming it.
-    fn peek(&mut self) -> Option<&char> {
-        self.chars.peek()
+    fn peek(&self) -> Option<char> {
+        match *self.bytes.get(self.pos)? {
+            b if b.is_ascii() => Some(b as char),
+            _ => self.input[self.pos..].chars().next(),
+        }
     }
 
-    /// Consumes whitespace characters until a non-whitespace character is found.
-    fn consume_whitespace(&mut self) {
-        while let Some(&c) = self.peek() {
-            if c.is_whitespace() {
-                self.next();
-            } else {
+    /// Peeks at the next byte without consuming it.
+    fn peek_byte(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Consumes `b` if it is the next byte.
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek_byte() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes characters while `pred` holds, returning the consumed slice.
+    fn eat_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !pred(c) {
                 break;
             }
+            self.pos += c.len_utf8();
         }
+        &self.input[start..self.pos]
     }
 
-    /// The main dispatch function for parsing any JSON value.
-    fn parse_value(&mut self) -> Result<Value, ParseError> {
-        self.consume_whitespace();
+    /// The error for the character at the current position.
+    fn unexpected(&self) -> ParseError {
         match self.peek() {
-            Some('{') => self.parse_object(),
-            Some('[') => self.parse_array(),
-            Some('"') => self.parse_string(),
-            Some('t') | Some('f') | Some('n') => self.parse_literal(),
-            Some(c) if c.is_digit(10) || *c == '-' => self.parse_number(),
-            Some(&c) => Err(ParseError::UnexpectedToken(c)),
-            None => Err(ParseError::UnexpectedEndOfInput),
+            Some(c) => ParseError::UnexpectedToken(c),
+            None => ParseError::UnexpectedEndOfInput,
         }
     }
 
-    /// Parses a JSON string literal: "..."
-    fn parse_string(&mut self) -> Result<Value, ParseError> {
-        self.next(); // Consume opening '"'
-        let mut s = String::new();
-        while let Some(c) = self.next() {
-            match c {
-                '"' => return Ok(Value::String(s)),
-                '\\' => {
-                    let escaped = self.next().ok_or(ParseError::UnterminatedString)?;
-                    match escaped {
-                        '"' | '\\' | '/' => s.push(escaped),
-                        'b' => s.push('\u{0008}'),
-                        'f' => s.push('\u{000C}'),
-                        'n' => s.push('\n'),
-                        'r' => s.push('\r'),
-                        't' => s.push('\t'),
-                        'u' => {
-                            let mut hex = String::with_capacity(4);
-                            for _ in 0..4 {
-                                hex.push(self.next().ok_or(ParseError::UnterminatedString)?);
-                            }
-                            let code = u32::from_str_radix(&hex, 16)
-                                .map_err(|_| ParseError::InvalidEscapeSequence('u'))?;
-                            s.push(
-                                std::char::from_u32(code)
-                                    .ok_or(ParseError::InvalidEscapeSequence('u'))?,
-                            );
-                        }
-                        _ => return Err(ParseError::InvalidEscapeSequence(escaped)),
+    /// Consumes whitespace characters until a non-whitespace character is found.
+    /// Comments count as whitespace when `allow_comments` is set; an
+    /// unterminated `/*` comment is an error.
+    fn consume_whitespace(&mut self) -> Result<(), ParseError> {
+        while let Some(b) = self.peek_byte() {
+            match b {
+                b' ' | b'\t'..=b'\r' => self.pos += 1,
+                b'/' if self.options.allow_comments && self.skip_comment()? => {}
+                b if b.is_ascii() => break,
+                _ => {
+                    if self.eat_while(char::is_whitespace).is_empty() {
+                        break;
                     }
                 }
-                _ => s.push(c),
             }
         }
-        Err(ParseError::UnterminatedString)
+        Ok(())
     }
 
-    /// Parses a JSON number (integer or float).
-    fn parse_number(&mut self) -> Result<Value, ParseError> {
-        let mut num_str = String::new();
-        if let Some(&'-') = self.peek() {
-            num_str.push(self.next().unwrap());
+    /// Skips a `//` or `/* */` comment starting at the current position.
+    /// Returns `false`, consuming nothing, if no comment starts here.
+    fn skip_comment(&mut self) -> Result<bool, ParseError> {
+        let body = self.pos + 2;
+        match self.bytes.get(self.pos + 1) {
+            Some(b'/') => {
+                self.pos = match self.bytes[body..].iter().position(|&b| b == b'\n') {
+                    Some(i) => body + i + 1,
+                    None => self.bytes.len(),
+                };
+                Ok(true)
+            }
+            Some(b'*') => match self.bytes[body..].windows(2).position(|w| w == b"*/") {
+                Some(i) => {
+                    self.pos = body + i + 2;
+                    Ok(true)
+                }
+                None => Err(ParseError::UnexpectedEndOfInput),
+            },
+            _ => Ok(false),
         }
+    }
 
-        while let Some(&c) = self.peek() {
-            if c.is_digit(10) || c == '.' || c == 'e' || c == 'E' || c == '+' || c == '-' {
-                num_str.push(self.next().unwrap());
-            } else {
-                break;
+    /// Returns `true` if `b` opens a string under the current options.
+    fn is_quote(&self, b: u8) -> bool {
+        b == b'"' || (b == b'\'' && self.options.allow_single_quotes)
+    }
+
+    /// The main dispatch function for parsing any JSON value.
+    ///
+    /// Nesting is tracked on an explicit stack of open containers rather than
+    /// through recursion, so deeply nested input cannot overflow the call
+    /// stack; `max_depth` is the only bound on nesting.
+    fn parse_value<B: Builder<'a>>(&mut self, builder: &mut B) -> Result<B::Value, ParseError> {
+        let mut stack: Vec<Frame<'a, B::Array, B::Object>> = Vec::new();
+        'value: loop {
+            // Parse the next value, or open a container and go parse its first item.
+            self.consume_whitespace()?;
+            self.nodes += 1;
+            if self.nodes > self.options.max_nodes {
+                return Err(ParseError::MaxNodesExceeded);
+            }
+            let mut value = match self.peek_byte() {
+                Some(b'[') => {
+                    self.pos += 1; // Consume '['
+                    self.enter()?;
+                    self.consume_whitespace()?;
+                    if self.eat(b']') {
+                        self.depth -= 1;
+                        let array = builder.array_start();
+                        builder.array_end(array)
+                    } else {
+                        stack.push(Frame::Array(builder.array_start(), 0));
+                        continue 'value;
+                    }
+                }
+                Some(b'{') => {
+                    self.pos += 1; // Consume '{'
+                    self.enter()?;
+                    self.consume_whitespace()?;
+                    if self.eat(b'}') {
+                        self.depth -= 1;
+                        let object = builder.object_start();
+                        builder.object_end(object)
+                    } else {
+                        let key = self.parse_key()?;
+                        stack.push(Frame::Object(builder.object_start(), key, 0));
+                        continue 'value;
+                    }
+                }
+                Some(b) if self.is_quote(b) => builder.string(self.parse_string()?),
+                Some(b't' | b'f' | b'n') => match self.parse_literal()? {
+                    Some(b) => builder.bool(b),
+                    None => builder.null(),
+                },
+                Some(b'0'..=b'9' | b'-') => builder.number(self.parse_number()?),
+                _ => return Err(self.unexpected()),
+            };
+
+            // Hand the finished value to its parent, closing containers as they end.
+            loop {
+                let closed = match stack.last_mut() {
+                    None => return Ok(value),
+                    Some(Frame::Array(arr, len)) => {
+                        if *len >= self.options.max_elements {
+                            return Err(ParseError::MaxElementsExceeded);
+                        }
+                        *len += 1;
+                        builder.array_push(arr, value);
+                        self.consume_whitespace()?;
+                        if self.eat(b',') {
+                            if !self.at_trailing_comma(b']')? {
+                                continue 'value;
+                            }
+                        } else if !self.eat(b']') {
+                            return Err(self.unexpected());
+                        }
+                        match stack.pop() {
+                            Some(Frame::Array(arr, _)) => builder.array_end(arr),
+                            _ => unreachable!(),
+                        }
+                    }
+                    Some(Frame::Object(obj, key, len)) => {
+                        if *len >= self.options.max_elements {
+                            return Err(ParseError::MaxElementsExceeded);
+                        }
+                        *len += 1;
+                        builder.object_insert(obj, std::mem::take(key), value);
+                        self.consume_whitespace()?;
+                        if self.eat(b',') {
+                            if !self.at_trailing_comma(b'}')? {
+                                *key = self.parse_key()?;
+                                continue 'value;
+                            }
+                        } else if !self.eat(b'}') {
+                            return Err(self.unexpected());
+                        }
+                        match stack.pop() {
+                            Some(Frame::Object(obj, ..)) => builder.object_end(obj),
+                            _ => unreachable!(),
+                        }
+                    }
+                };
+                self.depth -= 1;
+                value = closed;
             }
         }
-        num_str
-            .parse::<f64>()
-            .map(Value::Number)
-            .map_err(|_| ParseError::InvalidNumber)
     }
 
-    /// Parses a JSON array literal: [...]
-    fn parse_array(&mut self) -> Result<Value, ParseError> {
-        self.next(); // Consume '['
-        let mut arr = Vec::new();
-        self.consume_whitespace();
-        if self.peek() == Some(&']') {
-            self.next(); // Consume ']'
-            return Ok(Value::Array(arr));
+    /// After a `,`, checks whether `close` follows and trailing commas are
+    /// allowed. If so, consumes `close` and returns `true`.
+    fn at_trailing_comma(&mut self, close: u8) -> Result<bool, ParseError> {
+        if !self.options.allow_trailing_commas {
+            return Ok(false);
         }
-        loop {
-            arr.push(self.parse_value()?);
-            self.consume_whitespace();
-            match self.next() {
-                Some(']') => return Ok(Value::Array(arr)),
-                Some(',') => continue,
-                Some(c) => return Err(ParseError::UnexpectedToken(c)),
-                None => return Err(ParseError::UnexpectedEndOfInput),
-            }
+        self.consume_whitespace()?;
+        Ok(self.eat(close))
+    }
+
+    /// Parses an object key and the `:` that follows it.
+    fn parse_key(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        self.consume_whitespace()?;
+        let key = match self.peek_byte() {
+            Some(b) if self.is_quote(b) => self.parse_string()?,
+            None => return Err(ParseError::UnexpectedEndOfInput),
+            Some(_) => match self.peek() {
+                Some(c) if self.options.allow_unquoted_keys && is_identifier_start(c) => {
+                    let key = self.eat_while(|c| is_identifier_start(c) || c.is_ascii_digit());
+                    if key.len() > self.options.max_string_len {
+                        return Err(ParseError::MaxStringLengthExceeded);
+                    }
+                    Cow::Borrowed(key)
+                }
+                // Keys must be strings
+                Some(c) if matches!(c, '{' | '[' | 't' | 'f' | 'n' | '-') || c.is_ascii_digit() => {
+                    return Err(ParseError::UnexpectedToken('"'));
+                }
+                _ => return Err(self.unexpected()),
+            },
+        };
+
+        self.consume_whitespace()?;
+        if !self.eat(b':') {
+            return Err(ParseError::UnexpectedToken(':'));
         }
+        Ok(key)
+    }
+
+    /// Returns the offset of the next `quote` or `\` at or after `from`.
+    fn find_string_stop(&self, quote: u8, from: usize) -> Option<usize> {
+        self.bytes[from..].iter().position(|&b| b == quote || b == b'\\').map(|i| from + i)
     }
 
-    /// Parses a JSON object literal: {...}
-    fn parse_object(&mut self) -> Result<Value, ParseError> {
-        self.next(); // Consume '{'
-        let mut obj = HashMap::new();
-        self.consume_whitespace();
-        if self.peek() == Some(&'}') {
-            self.next(); // Consume '}'
-            return Ok(Value::Object(obj));
+    /// Parses a JSON string literal: "..." (or '...' in lenient mode)
+    ///
+    /// A string without escape sequences is borrowed from the input; the
+    /// first escape switches to building an owned copy. Runs of plain
+    /// characters are found with a byte scan and copied as whole slices.
+    fn parse_string(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        let quote = self.bytes[self.pos];
+        let start = self.pos + 1; // Skip opening quote
+        let stop = self.find_string_stop(quote, start);
+        if stop.unwrap_or(self.bytes.len()) - start > self.options.max_string_len {
+            return Err(ParseError::MaxStringLengthExceeded);
         }
-        loop {
-            let key = match self.parse_value()? {
-                Value::String(s) => s,
-                _ => return Err(ParseError::UnexpectedToken('"')), // Keys must be strings
-            };
+        let end = stop.ok_or(ParseError::UnterminatedString)?;
+        self.pos = end + 1;
+        if self.bytes[end] == quote {
+            return Ok(Cow::Borrowed(&self.input[start..end]));
+        }
+        let mut s = String::from(&self.input[start..end]);
+        self.parse_escape(&mut s)?;
+        self.parse_string_owned(quote, s).map(Cow::Owned)
+    }
 
-            self.consume_whitespace();
-            if self.next() != Some(':') {
-                return Err(ParseError::UnexpectedToken(':'));
+    /// Finishes a string containing escapes, appending to `s`.
+    fn parse_string_owned(&mut self, quote: u8, mut s: String) -> Result<String, ParseError> {
+        loop {
+            let stop = self.find_string_stop(quote, self.pos);
+            let end = stop.unwrap_or(self.bytes.len());
+            s.push_str(&self.input[self.pos..end]);
+            self.pos = end;
+            if s.len() > self.options.max_string_len {
+                return Err(ParseError::MaxStringLengthExceeded);
+            }
+            match stop {
+                Some(_) if self.eat(quote) => return Ok(s),
+                Some(_) => {
+                    self.pos += 1; // Consume '\'
+                    self.parse_escape(&mut s)?;
+                }
+                None => return Err(ParseError::UnterminatedString),
             }
+        }
+    }
 
-            let value = self.parse_value()?;
-            obj.insert(key, value);
+    /// Decodes the escape sequence after a `\` into `s`.
+    fn parse_escape(&mut self, s: &mut String) -> Result<(), ParseError> {
+        let escaped = self.next().ok_or(ParseError::UnterminatedString)?;
+        match escaped {
+            '"' | '\\' | '/' => s.push(escaped),
+            '\'' if self.options.allow_single_quotes => s.push(escaped),
+            'b' => s.push('\u{0008}'),
+            'f' => s.push('\u{000C}'),
+            'n' => s.push('\n'),
+            'r' => s.push('\r'),
+            't' => s.push('\t'),
+            'u' => s.push(self.parse_unicode_escape()?),
+            _ => return Err(ParseError::InvalidEscapeSequence(escaped)),
+        }
+        Ok(())
+    }
 
-            self.consume_whitespace();
-            match self.next() {
-                Some('}') => return Ok(Value::Object(obj)),
-                Some(',') => continue,
-                Some(c) => return Err(ParseError::UnexpectedToken(c)),
-                None => return Err(ParseError::UnexpectedEndOfInput),
+    /// Decodes the character of a `\u` escape whose `\u` has been consumed,
+    /// combining a high surrogate with an immediately following low one.
+    fn parse_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let mut unit = 0u16;
+        for _ in 0..4 {
+            let c = self.next().ok_or(ParseError::UnterminatedString)?;
+            let digit = c.to_digit(16).ok_or(ParseError::InvalidEscapeSequence('u'))?;
+            unit = unit * 16 + digit as u16;
+        }
+        match unit {
+            0xD800..=0xDBFF => {
+                // Only consume the next escape if it completes the pair;
+                // otherwise it is decoded on its own afterwards.
+                let ahead = &self.bytes[self.pos..];
+                if ahead.len() >= 6 && ahead.starts_with(b"\\u") && ahead[2..6].iter().all(u8::is_ascii_hexdigit) {
+                    let low = &self.input[self.pos + 2..self.pos + 6];
+                    if let Ok(low @ 0xDC00..=0xDFFF) = u32::from_str_radix(low, 16) {
+                        self.pos += 6;
+                        let code = 0x10000 + ((u32::from(unit) - 0xD800) << 10) + (low - 0xDC00);
+                        return std::char::from_u32(code).ok_or(ParseError::InvalidEscapeSequence('u'));
+                    }
+                }
+                self.lone_surrogate(unit)
             }
+            0xDC00..=0xDFFF => self.lone_surrogate(unit),
+            _ => std::char::from_u32(u32::from(unit)).ok_or(ParseError::InvalidEscapeSequence('u')),
         }
     }
 
-    /// Parses the literals: true, false, null.
-    fn parse_literal(&mut self) -> Result<Value, ParseError> {
-        let mut literal = String::new();
-        while let Some(&c) = self.peek() {
-            if c.is_alphabetic() {
-                literal.push(self.next().unwrap());
-            } else {
-                break;
+    fn lone_surrogate(&self, unit: u16) -> Result<char, ParseError> {
+        match self.options.lone_surrogates {
+            LoneSurrogate::Error => Err(ParseError::LoneSurrogate(unit)),
+            LoneSurrogate::Replace => Ok(char::REPLACEMENT_CHARACTER),
+        }
+    }
+
+    /// Parses a JSON number following the RFC 8259 grammar:
+    /// `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`.
+    ///
+    /// Number characters left over after a complete number (as in `01`,
+    /// `1.2.3`, or `1e5e`) are reported as `InvalidNumber` rather than as an
+    /// unexpected token.
+    fn parse_number(&mut self) -> Result<f64, ParseError> {
+        let start = self.pos;
+        self.eat(b'-');
+        match self.peek_byte() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => {
+                self.digits();
+            }
+            _ => return Err(ParseError::InvalidNumber),
+        }
+        if self.eat(b'.') && self.digits() == 0 {
+            return Err(ParseError::InvalidNumber);
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            let _ = self.eat(b'+') || self.eat(b'-');
+            if self.digits() == 0 {
+                return Err(ParseError::InvalidNumber);
             }
         }
-        match literal.as_str() {
-            "true" => Ok(Value::Bool(true)),
-            "false" => Ok(Value::Bool(false)),
-            "null" => Ok(Value::Null),
-            _ => Err(ParseError::InvalidLiteral(literal)),
+        if matches!(self.peek_byte(), Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')) {
+            return Err(ParseError::InvalidNumber);
+        }
+        self.input[start..self.pos]
+            .parse::<f64>()
+            .map_err(|_| ParseError::InvalidNumber)
+    }
+
+    /// Consumes a run of ASCII digits, returning how many there were.
+    fn digits(&mut self) -> usize {
+        let count = self.bytes[self.pos..].iter().take_while(|b| b.is_ascii_digit()).count();
+        self.pos += count;
+        count
+    }
+
+    /// Parses the literals: true, false, null. Returns `None` for `null`.
+    fn parse_literal(&mut self) -> Result<Option<bool>, ParseError> {
+        match self.eat_while(char::is_alphabetic) {
+            "true" => Ok(Some(true)),
+            "false" => Ok(Some(false)),
+            "null" => Ok(None),
+            literal => Err(ParseError::InvalidLiteral(literal.to_string())),
         }
     }
 }
@@ -247,13 +794,12 @@ impl<'a> Parser<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::*; 
-    use std::collections::HashMap;
+    use super::*;
 
     fn obj(pairs: &[(&str, Value)]) -> Value {
-        let mut m = HashMap::new();
+        let mut m = Map::new();
         for (k, v) in pairs {
-            m.insert((*k).to_string(), v.clone());
+            m.insert(*k, v.clone());
         }
         Value::Object(m)
     }
@@ -272,6 +818,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn parses_numbers_int_float_exp() {
         assert_eq!(from_str("0").unwrap(), Value::Number(0.0));
         assert_eq!(from_str("-42").unwrap(), Value::Number(-42.0));
@@ -331,6 +878,17 @@ mod tests {
         assert_eq!(from_str("  null  ").unwrap(), Value::Null);
     }
 
+    #[test]
+    fn object_keys_keep_document_order() {
+        let v = from_str(r#"{"zeta":1,"alpha":2,"mid":3}"#).unwrap();
+        match v {
+            Value::Object(m) => {
+                assert_eq!(m.keys().collect::<Vec<_>>(), ["zeta", "alpha", "mid"])
+            }
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
     // --- Common Errors
     #[test]
     fn error_trailing_characters() {
@@ -350,18 +908,86 @@ mod tests {
         assert_eq!(err, ParseError::InvalidEscapeSequence('q'));
     }
 
+    #[test]
+    fn decodes_surrogate_pairs() {
+        assert_eq!(from_str(r#""\ud83d\ude00!""#).unwrap(), Value::from("\u{1F600}!"));
+        assert_eq!(from_str(r#""\u00e9\u20AC""#).unwrap(), Value::from("é€"));
+        assert_eq!(from_str(r#""\u+041""#).unwrap_err(), ParseError::InvalidEscapeSequence('u'));
+    }
+
+    #[test]
+    fn lone_surrogates_follow_policy() {
+        assert_eq!(from_str(r#""\ud83d""#).unwrap_err(), ParseError::LoneSurrogate(0xd83d));
+        assert_eq!(from_str(r#""\ude00x""#).unwrap_err(), ParseError::LoneSurrogate(0xde00));
+        assert_eq!(from_str(r#""\ud83d\u0041""#).unwrap_err(), ParseError::LoneSurrogate(0xd83d));
+
+        let opts = ParserOptions { lone_surrogates: LoneSurrogate::Replace, ..Default::default() };
+        assert_eq!(from_str_with(r#""\ud83d\u0041""#, opts).unwrap(), Value::from("\u{FFFD}A"));
+        assert_eq!(from_str_with(r#""\ud83d\ud83d\ude00""#, opts).unwrap(), Value::from("\u{FFFD}\u{1F600}"));
+        assert_eq!(from_str_with(r#""a\ude00""#, opts).unwrap(), Value::from("a\u{FFFD}"));
+    }
+
     #[test]
     fn error_invalid_number() {
         let err = from_str("--1").unwrap_err();
         assert_eq!(err, ParseError::InvalidNumber);
     }
 
+    #[test]
+    fn error_numbers_outside_rfc_8259_grammar() {
+        for bad in ["01", "-01", "1.2.3", "1e", "1e+", "1.", "-", "-.5", "1.e3", "00", "1e5e", "[1-2]", "-Infinity"] {
+            assert_eq!(from_str(bad), Err(ParseError::InvalidNumber), "{} should be rejected", bad);
+        }
+        for good in ["-0", "0.5", "10", "1E+2", "1e-0", "0e0", "-0.0e00"] {
+            assert!(from_str(good).is_ok(), "{} should parse", good);
+        }
+        assert_eq!(from_str("[0,1]").unwrap(), Value::Array(vec![Value::from(0), Value::from(1)]));
+        assert_eq!(from_str(".5"), Err(ParseError::UnexpectedToken('.')));
+        assert_eq!(from_str("+1"), Err(ParseError::UnexpectedToken('+')));
+    }
+
     #[test]
     fn error_invalid_literal() {
         let err = from_str("tru").unwrap_err();
         assert_eq!(err, ParseError::InvalidLiteral("tru".into()));
     }
 
+    #[test]
+    fn non_ascii_input_is_handled_per_character() {
+        assert_eq!(from_str("\u{a0}[1,\u{2028}2]\u{3000}").unwrap(), Value::Array(vec![Value::from(1), Value::from(2)]));
+        assert_eq!(from_str("[é]"), Err(ParseError::UnexpectedToken('é')));
+        assert_eq!(from_str("nullé"), Err(ParseError::InvalidLiteral("nullé".into())));
+        assert_eq!(from_str(r#""\é""#), Err(ParseError::InvalidEscapeSequence('é')));
+        let unquoted = ParserOptions { allow_unquoted_keys: true, ..Default::default() };
+        assert_eq!(from_str_with("{clé_1: 'x'}", unquoted), Err(ParseError::UnexpectedToken('\'')));
+        assert_eq!(from_str_with("{clé_1: \"ü\"}", unquoted).unwrap()["clé_1"].as_str(), Some("ü"));
+    }
+
+    #[test]
+    fn intern_keys_shares_key_allocations() {
+        let src = r#"[{"id":1,"tag":"a"},{"id":2,"tag":"b"},{"tag":"c","id":3}]"#;
+        let key_ptrs = |v: &Value| -> Vec<Vec<*const u8>> {
+            let rows = v.as_array().unwrap().iter();
+            rows.map(|row| row.as_object().unwrap().keys().map(str::as_ptr).collect()).collect()
+        };
+        let interned = from_str_with(src, ParserOptions { intern_keys: true, ..Default::default() }).unwrap();
+        let plain = from_str(src).unwrap();
+        assert_eq!(interned, plain);
+        let ptrs = key_ptrs(&interned);
+        assert_eq!(ptrs[0], ptrs[1]);
+        assert_eq!(ptrs[2], [ptrs[0][1], ptrs[0][0]]);
+        assert_ne!(key_ptrs(&plain)[0], key_ptrs(&plain)[1]);
+    }
+
+    #[test]
+    fn from_slice_validates_or_replaces_invalid_utf8() {
+        assert_eq!(from_slice("{\"k\":\"é\"}".as_bytes()).unwrap()["k"].as_str(), Some("é"));
+        assert_eq!(from_slice(b"[1,\xc3]"), Err(ParseError::InvalidUtf8(3)));
+        let lossy = ParserOptions { lossy_utf8: true, ..Default::default() };
+        assert_eq!(from_slice_with(b"\"a\xc3\"", lossy).unwrap().as_str(), Some("a\u{fffd}"));
+        assert_eq!(from_slice_with(b"[1]\xff", lossy), Err(ParseError::TrailingCharacters));
+    }
+
     #[test]
     fn error_object_key_must_be_string() {
         let err = from_str("{1:2}").unwrap_err();
@@ -374,6 +1000,107 @@ mod tests {
         assert_eq!(err, ParseError::UnexpectedToken(':'));
     }
 
+    #[test]
+    fn error_max_depth_exceeded() {
+        let opts = ParserOptions { max_depth: 3, ..Default::default() };
+        assert!(from_str_with(r#"[{"a":[1]}]"#, opts).is_ok());
+        assert_eq!(from_str_with(r#"[{"a":[[1]]}]"#, opts), Err(ParseError::MaxDepthExceeded));
+
+        let deep = "[".repeat(100_000);
+        assert_eq!(from_str(&deep), Err(ParseError::MaxDepthExceeded));
+    }
+
+    #[test]
+    fn error_resource_limits_exceeded() {
+        let opts = ParserOptions { max_string_len: 3, ..Default::default() };
+        assert!(from_str_with(r#"{"abc":"d\n"}"#, opts).is_ok());
+        assert_eq!(from_str_with(r#"["abcd"]"#, opts), Err(ParseError::MaxStringLengthExceeded));
+        assert_eq!(from_str_with(r#"["ab\n\t"]"#, opts), Err(ParseError::MaxStringLengthExceeded));
+        assert_eq!(from_str_with(r#"{"long":1}"#, opts), Err(ParseError::MaxStringLengthExceeded));
+        // The limit counts bytes, not characters.
+        assert_eq!(from_str_with(r#""éé""#, opts), Err(ParseError::MaxStringLengthExceeded));
+
+        let opts = ParserOptions { max_elements: 2, ..Default::default() };
+        assert!(from_str_with(r#"[[1,2],{"a":1,"b":[]}]"#, opts).is_ok());
+        assert_eq!(from_str_with("[1,2,3]", opts), Err(ParseError::MaxElementsExceeded));
+        assert_eq!(from_str_with(r#"{"a":1,"b":2,"a":3}"#, opts), Err(ParseError::MaxElementsExceeded));
+
+        let opts = ParserOptions { max_nodes: 4, ..Default::default() };
+        assert!(from_str_with(r#"{"a":[1,null]}"#, opts).is_ok());
+        assert_eq!(from_str_with(r#"[[1],[2]]"#, opts), Err(ParseError::MaxNodesExceeded));
+    }
+
+    #[test]
+    fn deep_nesting_does_not_overflow_the_stack() {
+        let depth = 20_000;
+        let src = format!("{}1{}", "[".repeat(depth), "]".repeat(depth));
+        let opts = ParserOptions { max_depth: usize::MAX, ..Default::default() };
+        let mut v = from_str_with(&src, opts).unwrap();
+
+        let mut levels = 0;
+        while let Value::Array(mut arr) = v {
+            levels += 1;
+            v = arr.pop().unwrap();
+        }
+        assert_eq!(levels, depth);
+        assert_eq!(v, Value::Number(1.0));
+    }
+
+    #[test]
+    fn error_trailing_comma_and_unclosed_containers() {
+        assert_eq!(from_str("[1,]").unwrap_err(), ParseError::UnexpectedToken(']'));
+        assert_eq!(from_str(r#"{"a":1,}"#).unwrap_err(), ParseError::UnexpectedToken('}'));
+        assert_eq!(from_str(r#"{"a":[1"#).unwrap_err(), ParseError::UnexpectedEndOfInput);
+        assert_eq!(from_str(r#"{"a""#).unwrap_err(), ParseError::UnexpectedToken(':'));
+    }
+
+    #[test]
+    fn lenient_mode_accepts_json5_extensions() {
+        let src = r#"
+            // leading comment
+            {
+                name: 'stdt', /* inline */
+                "list": [1, 2, 3,],
+                $id: 'it\'s',
+                nested: { a: null, },
+            }
+        "#;
+        let v = from_str_with(src, ParserOptions::lenient()).unwrap();
+        assert_eq!(
+            v.to_string(),
+            r#"{"name":"stdt","list":[1,2,3],"$id":"it's","nested":{"a":null}}"#
+        );
+    }
+
+    #[test]
+    fn lenient_flags_are_independent() {
+        let comments_only = ParserOptions { allow_comments: true, ..Default::default() };
+        assert!(from_str_with("[1 /* c */, 2] // end", comments_only).is_ok());
+        assert_eq!(
+            from_str_with("[1, 2,]", comments_only).unwrap_err(),
+            ParseError::UnexpectedToken(']')
+        );
+        assert_eq!(from_str_with("{a: 1}", comments_only).unwrap_err(), ParseError::UnexpectedToken('a'));
+    }
+
+    #[test]
+    fn lenient_rejects_unterminated_block_comments() {
+        let lenient = ParserOptions::lenient();
+        assert_eq!(from_str_with("1 /* oops", lenient).unwrap_err(), ParseError::UnexpectedEndOfInput);
+        assert_eq!(from_str_with("[1] /* x", lenient).unwrap_err(), ParseError::UnexpectedEndOfInput);
+        assert_eq!(from_str_with("[1, /* x", lenient).unwrap_err(), ParseError::UnexpectedEndOfInput);
+        assert_eq!(from_str_with("[1] /* x */", lenient).unwrap(), from_str("[1]").unwrap());
+        assert_eq!(from_str_with("[1] // x", lenient).unwrap(), from_str("[1]").unwrap());
+    }
+
+    #[test]
+    fn strict_mode_rejects_json5_extensions() {
+        assert_eq!(from_str("[1] // c").unwrap_err(), ParseError::TrailingCharacters);
+        assert_eq!(from_str("'x'").unwrap_err(), ParseError::UnexpectedToken('\''));
+        assert_eq!(from_str("/* c */ 1").unwrap_err(), ParseError::UnexpectedToken('/'));
+        assert_eq!(from_str("{a:1}").unwrap_err(), ParseError::UnexpectedToken('a'));
+    }
+
     #[test]
     fn error_array_missing_comma_or_closing() {
         let err = from_str(r#"[1 "a"]"#).unwrap_err();
@@ -0,0 +1,418 @@
+//! Implements JSON Patch (RFC 6902) for `json::Value`.
+//!
+//! A patch is an array of operations (`add`, `remove`, `replace`, `move`,
+//! `copy`, `test`), each addressing its target with a JSON Pointer. Use
+//! [`parse`] to turn a patch document into [`PatchOp`]s and [`apply`] to run
+//! them against a `Value`. Application is atomic: if any operation fails the
+//! document is left untouched.
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json::{self, patch};
+//!
+//! let mut doc = json::from_str(r#"{"name":"app","tags":["a"]}"#).unwrap();
+//! let ops = patch::parse(&json::from_str(r#"[
+//!     {"op": "replace", "path": "/name", "value": "api"},
+//!     {"op": "add", "path": "/tags/-", "value": "b"}
+//! ]"#).unwrap()).unwrap();
+//!
+//! patch::apply(&mut doc, &ops).unwrap();
+//! assert_eq!(doc.to_string(), r#"{"name":"api","tags":["a","b"]}"#);
+//! ```
+
+use super::map::Map;
+use super::pointer::{parse_index, split_pointer};
+use super::value::Value;
+use std::error::Error;
+use std::fmt;
+
+/// A single JSON Patch operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp {
+    /// Inserts `value` at `path` (appends when the last token is `-`).
+    Add { path: String, value: Value },
+    /// Removes the value at `path`.
+    Remove { path: String },
+    /// Replaces the existing value at `path`.
+    Replace { path: String, value: Value },
+    /// Removes the value at `from` and adds it at `path`.
+    Move { from: String, path: String },
+    /// Copies the value at `from` to `path`.
+    Copy { from: String, path: String },
+    /// Checks that the value at `path` equals `value`.
+    Test { path: String, value: Value },
+}
+
+/// An error that can occur while parsing or applying a patch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// The patch document is not an array of valid operation objects.
+    /// `index` is the position of the offending operation.
+    Malformed { index: usize, reason: String },
+    /// A `path` or `from` member is not a valid JSON Pointer.
+    InvalidPointer(String),
+    /// The pointer does not resolve to a location in the document.
+    PathNotFound(String),
+    /// A `move` tried to relocate a value into one of its own children.
+    MoveIntoChild { from: String, path: String },
+    /// A `test` operation found a different value than expected.
+    TestFailed {
+        index: usize,
+        path: String,
+        expected: Box<Value>,
+        actual: Option<Box<Value>>,
+    },
+}
+
+impl Error for PatchError {}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::Malformed { index, reason } => {
+                write!(f, "Malformed patch operation at index {}: {}", index, reason)
+            }
+            PatchError::InvalidPointer(p) => write!(f, "Invalid JSON Pointer: '{}'", p),
+            PatchError::PathNotFound(p) => write!(f, "Path not found: '{}'", p),
+            PatchError::MoveIntoChild { from, path } => {
+                write!(f, "Cannot move '{}' into its own child '{}'", from, path)
+            }
+            PatchError::TestFailed { index, path, expected, actual } => match actual {
+                Some(actual) => write!(
+                    f,
+                    "Test failed at index {}: expected {} at '{}', found {}",
+                    index, expected, path, actual
+                ),
+                None => write!(
+                    f,
+                    "Test failed at index {}: expected {} at '{}', found nothing",
+                    index, expected, path
+                ),
+            },
+        }
+    }
+}
+
+/// Parses a patch document (a JSON array of operation objects).
+///
+/// # Errors
+///
+/// Returns `PatchError::Malformed` if the document is not an array, an
+/// operation has an unknown `op`, or a required member is missing.
+pub fn parse(patch: &Value) -> Result<Vec<PatchOp>, PatchError> {
+    let ops = match patch {
+        Value::Array(ops) => ops,
+        _ => {
+            return Err(PatchError::Malformed {
+                index: 0,
+                reason: "patch document must be an array".into(),
+            });
+        }
+    };
+    ops.iter()
+        .enumerate()
+        .map(|(index, op)| parse_op(index, op))
+        .collect()
+}
+
+/// Converts `ops` back into a patch document, the inverse of [`parse`].
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json::{self, patch};
+/// let doc = json::from_str(r#"[{"op":"move","from":"/a","path":"/b"}]"#).unwrap();
+/// assert_eq!(patch::to_value(&patch::parse(&doc).unwrap()), doc);
+/// ```
+pub fn to_value(ops: &[PatchOp]) -> Value {
+    Value::Array(ops.iter().map(op_to_value).collect())
+}
+
+fn op_to_value(op: &PatchOp) -> Value {
+    let mut obj = Map::new();
+    let (name, path) = match op {
+        PatchOp::Add { path, .. } => ("add", path),
+        PatchOp::Remove { path } => ("remove", path),
+        PatchOp::Replace { path, .. } => ("replace", path),
+        PatchOp::Move { path, .. } => ("move", path),
+        PatchOp::Copy { path, .. } => ("copy", path),
+        PatchOp::Test { path, .. } => ("test", path),
+    };
+    obj.insert("op", Value::from(name));
+    match op {
+        PatchOp::Move { from, .. } | PatchOp::Copy { from, .. } => {
+            obj.insert("from", Value::from(from.as_str()));
+        }
+        _ => {}
+    }
+    obj.insert("path", Value::from(path.as_str()));
+    match op {
+        PatchOp::Add { value, .. } | PatchOp::Replace { value, .. } | PatchOp::Test { value, .. } => {
+            obj.insert("value", value.clone());
+        }
+        _ => {}
+    }
+    Value::Object(obj)
+}
+
+fn parse_op(index: usize, op: &Value) -> Result<PatchOp, PatchError> {
+    let malformed = |reason: String| PatchError::Malformed { index, reason };
+
+    let obj = match op {
+        Value::Object(obj) => obj,
+        _ => return Err(malformed("operation must be an object".into())),
+    };
+    let member = |name: &str| -> Result<String, PatchError> {
+        match obj.get(name) {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(_) => Err(malformed(format!("'{}' must be a string", name))),
+            None => Err(malformed(format!("missing '{}'", name))),
+        }
+    };
+    let value = || -> Result<Value, PatchError> {
+        obj.get("value")
+            .cloned()
+            .ok_or_else(|| malformed("missing 'value'".into()))
+    };
+
+    let op = member("op")?;
+    let path = member("path")?;
+    match op.as_str() {
+        "add" => Ok(PatchOp::Add { path, value: value()? }),
+        "remove" => Ok(PatchOp::Remove { path }),
+        "replace" => Ok(PatchOp::Replace { path, value: value()? }),
+        "move" => Ok(PatchOp::Move { from: member("from")?, path }),
+        "copy" => Ok(PatchOp::Copy { from: member("from")?, path }),
+        "test" => Ok(PatchOp::Test { path, value: value()? }),
+        other => Err(malformed(format!("unknown op '{}'", other))),
+    }
+}
+
+/// Applies `ops` to `doc` in order.
+///
+/// The operations run against a copy of the document, which replaces `doc`
+/// only if every operation succeeds.
+///
+/// # Errors
+///
+/// Returns the first `PatchError` encountered; `doc` is left unchanged.
+pub fn apply(doc: &mut Value, ops: &[PatchOp]) -> Result<(), PatchError> {
+    let mut working = doc.clone();
+    for (index, op) in ops.iter().enumerate() {
+        apply_op(&mut working, index, op)?;
+    }
+    *doc = working;
+    Ok(())
+}
+
+fn apply_op(doc: &mut Value, index: usize, op: &PatchOp) -> Result<(), PatchError> {
+    match op {
+        PatchOp::Add { path, value } => add(doc, path, value.clone()),
+        PatchOp::Remove { path } => remove(doc, path).map(|_| ()),
+        PatchOp::Replace { path, value } => {
+            check_pointer(path)?;
+            let target = doc
+                .pointer_mut(path)
+                .ok_or_else(|| PatchError::PathNotFound(path.clone()))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOp::Move { from, path } => {
+            if from == path {
+                return Ok(());
+            }
+            if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                return Err(PatchError::MoveIntoChild { from: from.clone(), path: path.clone() });
+            }
+            let value = remove(doc, from)?;
+            add(doc, path, value)
+        }
+        PatchOp::Copy { from, path } => {
+            check_pointer(from)?;
+            let value = doc
+                .pointer(from)
+                .cloned()
+                .ok_or_else(|| PatchError::PathNotFound(from.clone()))?;
+            add(doc, path, value)
+        }
+        PatchOp::Test { path, value } => {
+            check_pointer(path)?;
+            let actual = doc.pointer(path);
+            if actual == Some(value) {
+                Ok(())
+            } else {
+                Err(PatchError::TestFailed {
+                    index,
+                    path: path.clone(),
+                    expected: Box::new(value.clone()),
+                    actual: actual.cloned().map(Box::new),
+                })
+            }
+        }
+    }
+}
+
+/// Rejects a malformed `path` before it is looked up, so it is not reported
+/// as a missing location.
+fn check_pointer(path: &str) -> Result<(), PatchError> {
+    match split_pointer(path) {
+        Some(_) => Ok(()),
+        None => Err(PatchError::InvalidPointer(path.to_string())),
+    }
+}
+
+/// Splits `path` into the pointer of its parent and its last token.
+fn split_last(path: &str) -> Result<(String, String), PatchError> {
+    let mut tokens =
+        split_pointer(path).ok_or_else(|| PatchError::InvalidPointer(path.to_string()))?;
+    let last = tokens.pop().ok_or_else(|| PatchError::InvalidPointer(path.to_string()))?;
+    let parent = path[..path.rfind('/').unwrap_or(0)].to_string();
+    Ok((parent, last))
+}
+
+fn add(doc: &mut Value, path: &str, value: Value) -> Result<(), PatchError> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+    let (parent, last) = split_last(path)?;
+    let not_found = || PatchError::PathNotFound(path.to_string());
+    match doc.pointer_mut(&parent).ok_or_else(not_found)? {
+        Value::Object(map) => {
+            map.insert(last, value);
+            Ok(())
+        }
+        Value::Array(arr) => {
+            if last == "-" {
+                arr.push(value);
+                return Ok(());
+            }
+            match parse_index(&last) {
+                Some(i) if i <= arr.len() => {
+                    arr.insert(i, value);
+                    Ok(())
+                }
+                _ => Err(not_found()),
+            }
+        }
+        _ => Err(not_found()),
+    }
+}
+
+fn remove(doc: &mut Value, path: &str) -> Result<Value, PatchError> {
+    let (parent, last) = split_last(path)?;
+    let not_found = || PatchError::PathNotFound(path.to_string());
+    match doc.pointer_mut(&parent).ok_or_else(not_found)? {
+        Value::Object(map) => map.remove(&last).ok_or_else(not_found),
+        Value::Array(arr) => match parse_index(&last) {
+            Some(i) if i < arr.len() => Ok(arr.remove(i)),
+            _ => Err(not_found()),
+        },
+        _ => Err(not_found()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    fn run(doc: &str, patch: &str) -> Result<Value, PatchError> {
+        let mut doc = from_str(doc).unwrap();
+        let ops = parse(&from_str(patch).unwrap())?;
+        apply(&mut doc, &ops)?;
+        Ok(doc)
+    }
+
+    #[test]
+    fn add_into_object_and_array() {
+        let out = run(
+            r#"{"foo":["bar","baz"]}"#,
+            r#"[{"op":"add","path":"/foo/1","value":"qux"},{"op":"add","path":"/n","value":1}]"#,
+        )
+        .unwrap();
+        assert_eq!(out.to_string(), r#"{"foo":["bar","qux","baz"],"n":1}"#);
+    }
+
+    #[test]
+    fn remove_and_replace() {
+        let out = run(
+            r#"{"a":1,"b":[1,2,3]}"#,
+            r#"[{"op":"remove","path":"/b/1"},{"op":"replace","path":"/a","value":"x"}]"#,
+        )
+        .unwrap();
+        assert_eq!(out.to_string(), r#"{"a":"x","b":[1,3]}"#);
+    }
+
+    #[test]
+    fn move_and_copy() {
+        let out = run(
+            r#"{"a":{"b":1},"c":[]}"#,
+            r#"[{"op":"copy","from":"/a/b","path":"/c/-"},{"op":"move","from":"/a","path":"/d"}]"#,
+        )
+        .unwrap();
+        assert_eq!(out.to_string(), r#"{"c":[1],"d":{"b":1}}"#);
+    }
+
+    #[test]
+    fn move_into_own_child_is_rejected() {
+        let err = run(r#"{"a":{"b":1}}"#, r#"[{"op":"move","from":"/a","path":"/a/b/c"}]"#)
+            .unwrap_err();
+        assert!(matches!(err, PatchError::MoveIntoChild { .. }));
+    }
+
+    #[test]
+    fn failed_test_reports_details_and_leaves_document_untouched() {
+        let mut doc = from_str(r#"{"v":1}"#).unwrap();
+        let ops = parse(
+            &from_str(r#"[{"op":"replace","path":"/v","value":2},{"op":"test","path":"/v","value":3}]"#)
+                .unwrap(),
+        )
+        .unwrap();
+        let err = apply(&mut doc, &ops).unwrap_err();
+        assert_eq!(
+            err,
+            PatchError::TestFailed {
+                index: 1,
+                path: "/v".into(),
+                expected: Box::new(Value::Number(3.0)),
+                actual: Some(Box::new(Value::Number(2.0))),
+            }
+        );
+        assert_eq!(doc.to_string(), r#"{"v":1}"#);
+    }
+
+    #[test]
+    fn missing_targets_are_errors() {
+        let err = run(r#"{"a":1}"#, r#"[{"op":"remove","path":"/b"}]"#).unwrap_err();
+        assert_eq!(err, PatchError::PathNotFound("/b".into()));
+
+        let err = run(r#"[1]"#, r#"[{"op":"add","path":"/5","value":0}]"#).unwrap_err();
+        assert_eq!(err, PatchError::PathNotFound("/5".into()));
+    }
+
+    #[test]
+    fn malformed_pointers_are_invalid_not_missing() {
+        for patch in [
+            r#"[{"op":"replace","path":"a","value":0}]"#,
+            r#"[{"op":"replace","path":"/a~2","value":0}]"#,
+            r#"[{"op":"copy","from":"a","path":"/b"}]"#,
+            r#"[{"op":"test","path":"a","value":1}]"#,
+        ] {
+            let err = run(r#"{"a":1}"#, patch).unwrap_err();
+            assert!(matches!(err, PatchError::InvalidPointer(_)), "{}", patch);
+        }
+    }
+
+    #[test]
+    fn malformed_operations_are_rejected() {
+        let err = parse(&from_str(r#"[{"op":"jump","path":"/a"}]"#).unwrap()).unwrap_err();
+        assert!(matches!(err, PatchError::Malformed { index: 0, .. }));
+
+        let err = parse(&from_str(r#"[{"op":"test","path":"/a"},{}]"#).unwrap()).unwrap_err();
+        assert!(matches!(err, PatchError::Malformed { index: 0, .. }));
+
+        assert!(parse(&Value::Null).is_err());
+    }
+}
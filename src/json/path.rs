@@ -0,0 +1,659 @@
+//! Dotted path editing for `json::Value`.
+//!
+//! A path is a list of object keys separated by `.`, where any key may be
+//! followed by one or more `[n]` array indices: `servers[0].host`. The empty
+//! path refers to the value itself, and a path may start with an index
+//! (`[2].name`) when the document is an array. Inside a key, `\.`, `\[`,
+//! `\]`, and `\\` stand for the literal characters. A key may also be
+//! written in brackets as a quoted string, `a["b"]`, with `\"` and `\\`
+//! escapes; the empty key can only be written this way (`[""]`).
+//!
+//! This module provides in-place editing helpers (`set_path`, `insert`,
+//! `remove`, `push`) so documents can be built and modified without matching
+//! on `Value` variants by hand, and `flatten`/`unflatten` to convert between
+//! nested documents and single-level objects keyed by path.
+
+use super::map::Map;
+use super::value::Value;
+use std::error::Error;
+use std::fmt;
+
+/// An error that can occur while editing a `Value` through a path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathError {
+    /// The path string is malformed.
+    InvalidPath(String),
+    /// A value along the path has the wrong type for the requested step.
+    TypeMismatch {
+        path: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// An array index along the path is past the end of the array.
+    IndexOutOfBounds { path: String, index: usize, len: usize },
+}
+
+impl Error for PathError {}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::InvalidPath(p) => write!(f, "Invalid path: '{}'", p),
+            PathError::TypeMismatch { path, expected, found } => {
+                write!(f, "Expected {} at '{}', found {}", expected, path, found)
+            }
+            PathError::IndexOutOfBounds { path, index, len } => write!(
+                f,
+                "Index {} out of bounds at '{}' (length {})",
+                index, path, len
+            ),
+        }
+    }
+}
+
+/// One step of a parsed path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses `a.b[2].c` into its segments.
+///
+/// Returns `None` for malformed paths such as empty keys, unclosed brackets,
+/// or unknown escapes.
+pub(crate) fn parse_path(path: &str) -> Option<Vec<Segment>> {
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return Some(segments);
+    }
+    let mut chars = path.chars().peekable();
+    let mut first = true;
+    loop {
+        let mut key = String::new();
+        while let Some(&c) = chars.peek() {
+            match c {
+                '.' | '[' => break,
+                ']' => return None,
+                '\\' => {
+                    chars.next();
+                    match chars.next()? {
+                        c @ ('.' | '[' | ']' | '\\') => key.push(c),
+                        _ => return None,
+                    }
+                }
+                c => {
+                    key.push(c);
+                    chars.next();
+                }
+            }
+        }
+        if !key.is_empty() {
+            segments.push(Segment::Key(key));
+        } else if !(first && chars.peek() == Some(&'[')) {
+            // Only a leading `[n]` may stand without a key.
+            return None;
+        }
+        first = false;
+        while chars.next_if_eq(&'[').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                let mut key = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '\\') => key.push(c),
+                            _ => return None,
+                        },
+                        c => key.push(c),
+                    }
+                }
+                chars.next_if_eq(&']')?;
+                segments.push(Segment::Key(key));
+                continue;
+            }
+            let mut digits = String::new();
+            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                digits.push(c);
+            }
+            chars.next_if_eq(&']')?;
+            segments.push(Segment::Index(digits.parse().ok()?));
+        }
+        match chars.next() {
+            None => return Some(segments),
+            Some('.') => continue,
+            Some(_) => return None,
+        }
+    }
+}
+
+/// Escapes the characters that have a meaning in path syntax.
+pub(crate) fn escape_key(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '.' | '[' | ']' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+impl Value {
+    /// Looks up a nested value by path, e.g. `servers[0].host`.
+    ///
+    /// Returns `None` if the path is malformed or does not resolve. This is a
+    /// more compact alternative to [`Value::pointer`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let doc = json!({"servers": [{"host": "a"}], "v1.2": true});
+    /// assert_eq!(doc.at("servers[0].host"), Some(&json!("a")));
+    /// assert_eq!(doc.at(r"v1\.2"), Some(&json!(true)));
+    /// assert_eq!(doc.at("servers[1]"), None);
+    /// ```
+    pub fn at(&self, path: &str) -> Option<&Value> {
+        let mut target = self;
+        for segment in parse_path(path)? {
+            target = match segment {
+                Segment::Key(key) => target.get(&key)?,
+                Segment::Index(index) => target.get_index(index)?,
+            };
+        }
+        Some(target)
+    }
+
+    /// Mutable variant of [`Value::at`].
+    pub fn at_mut(&mut self, path: &str) -> Option<&mut Value> {
+        let mut target = self;
+        for segment in parse_path(path)? {
+            target = match segment {
+                Segment::Key(key) => target.get_mut(&key)?,
+                Segment::Index(index) => target.get_index_mut(index)?,
+            };
+        }
+        Some(target)
+    }
+
+    /// Sets the value at `path`, creating missing intermediate objects (and
+    /// arrays for `[n]` steps through `null`). Returns the value previously
+    /// stored there, if any.
+    ///
+    /// An index equal to the array length appends a new element.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PathError` if the path is malformed, walks through a value
+    /// of the wrong type, or uses an index past the end of an array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::Value;
+    /// let mut v = Value::Null;
+    /// v.set_path("db.hosts[0]", "primary").unwrap();
+    /// v.set_path("db.port", 5432).unwrap();
+    /// assert_eq!(v.to_string(), r#"{"db":{"hosts":["primary"],"port":5432}}"#);
+    /// ```
+    pub fn set_path<T: Into<Value>>(&mut self, path: &str, value: T) -> Result<Option<Value>, PathError> {
+        let segments = parse_path(path).ok_or_else(|| PathError::InvalidPath(path.to_string()))?;
+        let (target, created) = walk_creating(self, &segments)?;
+        let old = std::mem::replace(target, value.into());
+        Ok(if created { None } else { Some(old) })
+    }
+
+    /// Returns the object at `path`, creating it and any missing
+    /// intermediate objects (or arrays for `[n]` steps) along the way. A
+    /// `null` found at `path` is replaced by an empty object.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PathError` if the path is malformed, walks through a value
+    /// of the wrong type, uses an index past the end of an array, or ends at
+    /// a value that is neither an object nor `null`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::Value;
+    /// let mut config = Value::Null;
+    /// let db = config.ensure_object_path("services.db").unwrap();
+    /// db.insert("host", "localhost").unwrap();
+    /// db.insert("port", 5432).unwrap();
+    /// assert_eq!(config.to_string(), r#"{"services":{"db":{"host":"localhost","port":5432}}}"#);
+    /// ```
+    pub fn ensure_object_path(&mut self, path: &str) -> Result<&mut Value, PathError> {
+        let segments = parse_path(path).ok_or_else(|| PathError::InvalidPath(path.to_string()))?;
+        let (target, _) = walk_creating(self, &segments)?;
+        if target.is_null() {
+            *target = Value::Object(Map::new());
+        }
+        if !target.is_object() {
+            return Err(PathError::TypeMismatch {
+                path: render(&segments),
+                expected: "object",
+                found: target.type_name(),
+            });
+        }
+        Ok(target)
+    }
+
+    /// Inserts `key` into an object (a `null` receiver becomes an empty
+    /// object), returning the previous value under that key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError::TypeMismatch` if `self` is neither an object nor `null`.
+    pub fn insert<T: Into<Value>>(&mut self, key: &str, value: T) -> Result<Option<Value>, PathError> {
+        if self.is_null() {
+            *self = Value::Object(Map::new());
+        }
+        let found = self.type_name();
+        match self.as_object_mut() {
+            Some(map) => Ok(map.insert(key, value.into())),
+            None => Err(PathError::TypeMismatch { path: String::new(), expected: "object", found }),
+        }
+    }
+
+    /// Removes `key` from an object, returning its value. Returns `None` if
+    /// `self` is not an object or has no such key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let mut v = json!({"a": 1, "b": 2});
+    /// assert_eq!(v.remove("a"), Some(json!(1)));
+    /// assert_eq!(v.to_string(), r#"{"b":2}"#);
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.as_object_mut()?.remove(key)
+    }
+
+    /// Appends `value` to an array (a `null` receiver becomes an empty array).
+    ///
+    /// # Errors
+    ///
+    /// Returns `PathError::TypeMismatch` if `self` is neither an array nor `null`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::Value;
+    /// let mut v = Value::Null;
+    /// v.push(1).unwrap();
+    /// v.push("two").unwrap();
+    /// assert_eq!(v.to_string(), r#"[1,"two"]"#);
+    /// ```
+    pub fn push<T: Into<Value>>(&mut self, value: T) -> Result<(), PathError> {
+        if self.is_null() {
+            *self = Value::Array(Vec::new());
+        }
+        let found = self.type_name();
+        match self.as_array_mut() {
+            Some(arr) => {
+                arr.push(value.into());
+                Ok(())
+            }
+            None => Err(PathError::TypeMismatch { path: String::new(), expected: "array", found }),
+        }
+    }
+}
+
+impl Value {
+    /// Flattens the document into a single-level object whose keys are the
+    /// paths of its leaves, e.g. `{"a":{"b":[1]}}` becomes `{"a.b[0]":1}`.
+    ///
+    /// Empty arrays and objects are kept as leaves so that `unflatten`
+    /// restores them. A scalar document flattens to `{"": value}`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let doc = json!({"db": {"hosts": ["a", "b"], "port": 5432}});
+    /// let flat = doc.flatten();
+    /// assert_eq!(flat.to_string(), r#"{"db.hosts[0]":"a","db.hosts[1]":"b","db.port":5432}"#);
+    /// assert_eq!(flat.unflatten().unwrap(), doc);
+    /// ```
+    pub fn flatten(&self) -> Value {
+        let mut out = Map::new();
+        flatten_into(self, &mut Vec::new(), &mut out);
+        Value::Object(out)
+    }
+
+    /// Rebuilds a nested document from an object produced by `flatten`.
+    ///
+    /// Keys are applied in order with `set_path`, so array indices must
+    /// appear in ascending order without gaps.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PathError` if `self` is not an object, a key is not a
+    /// valid path, or two keys conflict (e.g. `a` and `a.b`).
+    pub fn unflatten(&self) -> Result<Value, PathError> {
+        let map = self.as_object().ok_or_else(|| PathError::TypeMismatch {
+            path: String::new(),
+            expected: "object",
+            found: self.type_name(),
+        })?;
+        let mut out = Value::Null;
+        for (key, value) in map {
+            out.set_path(key, value.clone())?;
+        }
+        Ok(out)
+    }
+}
+
+/// Walks `segments` from `root`, creating missing object members and
+/// appending array elements as `set_path` does. Returns the target and
+/// whether it was freshly created.
+///
+/// The path is validated before anything is created, so on error `root` is
+/// left unchanged.
+fn walk_creating<'a>(root: &'a mut Value, segments: &[Segment]) -> Result<(&'a mut Value, bool), PathError> {
+    check_creatable(root, segments)?;
+    let mut target = root;
+    let mut created = false;
+    for (depth, segment) in segments.iter().enumerate() {
+        let here = || render(&segments[..depth]);
+        target = match segment {
+            Segment::Key(key) => {
+                if target.is_null() {
+                    *target = Value::Object(Map::new());
+                }
+                let found = target.type_name();
+                let map = target.as_object_mut().ok_or_else(|| PathError::TypeMismatch {
+                    path: here(),
+                    expected: "object",
+                    found,
+                })?;
+                created = !map.contains_key(key);
+                if created {
+                    map.insert(key.as_str(), Value::Null);
+                }
+                map.get_mut(key).unwrap()
+            }
+            Segment::Index(index) => {
+                if target.is_null() {
+                    *target = Value::Array(Vec::new());
+                }
+                let found = target.type_name();
+                let arr = target.as_array_mut().ok_or_else(|| PathError::TypeMismatch {
+                    path: here(),
+                    expected: "array",
+                    found,
+                })?;
+                if *index > arr.len() {
+                    return Err(PathError::IndexOutOfBounds { path: here(), index: *index, len: arr.len() });
+                }
+                created = *index == arr.len();
+                if created {
+                    arr.push(Value::Null);
+                }
+                &mut arr[*index]
+            }
+        };
+    }
+    Ok((target, created))
+}
+
+/// Reports the error `walk_creating` would hit on `root`, without creating
+/// anything. Past the existing document (or a `null`), every step lands in a
+/// fresh container, where only an index past the end of an empty array fails.
+fn check_creatable(root: &Value, segments: &[Segment]) -> Result<(), PathError> {
+    let mut target = Some(root);
+    for (depth, segment) in segments.iter().enumerate() {
+        let here = || render(&segments[..depth]);
+        let existing = target.filter(|value| !value.is_null());
+        target = match segment {
+            Segment::Key(key) => match existing {
+                Some(value) => {
+                    let map = value.as_object().ok_or_else(|| PathError::TypeMismatch {
+                        path: here(),
+                        expected: "object",
+                        found: value.type_name(),
+                    })?;
+                    map.get(key)
+                }
+                None => None,
+            },
+            Segment::Index(index) => {
+                let arr: &[Value] = match existing {
+                    Some(value) => value.as_array().ok_or_else(|| PathError::TypeMismatch {
+                        path: here(),
+                        expected: "array",
+                        found: value.type_name(),
+                    })?,
+                    None => &[],
+                };
+                if *index > arr.len() {
+                    return Err(PathError::IndexOutOfBounds { path: here(), index: *index, len: arr.len() });
+                }
+                arr.get(*index)
+            }
+        };
+    }
+    Ok(())
+}
+
+fn flatten_into(value: &Value, prefix: &mut Vec<Segment>, out: &mut Map) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                prefix.push(Segment::Key(key.to_string()));
+                flatten_into(child, prefix, out);
+                prefix.pop();
+            }
+        }
+        Value::Array(arr) if !arr.is_empty() => {
+            for (i, child) in arr.iter().enumerate() {
+                prefix.push(Segment::Index(i));
+                flatten_into(child, prefix, out);
+                prefix.pop();
+            }
+        }
+        leaf => {
+            out.insert(render(prefix), leaf.clone());
+        }
+    }
+}
+
+/// Renders segments back into path syntax.
+pub(crate) fn render(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            // The empty key has no dotted form.
+            Segment::Key(key) if key.is_empty() => out.push_str("[\"\"]"),
+            Segment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(&escape_key(key));
+            }
+            Segment::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    #[test]
+    fn parses_dotted_and_bracketed_paths() {
+        assert_eq!(parse_path(""), Some(vec![]));
+        assert_eq!(
+            parse_path("a.b[2][0].c"),
+            Some(vec![
+                Segment::Key("a".into()),
+                Segment::Key("b".into()),
+                Segment::Index(2),
+                Segment::Index(0),
+                Segment::Key("c".into()),
+            ])
+        );
+        assert_eq!(parse_path("[1].x"), Some(vec![Segment::Index(1), Segment::Key("x".into())]));
+        assert_eq!(parse_path("a..b"), None);
+        assert_eq!(parse_path("a[x]"), None);
+        assert_eq!(parse_path("a[1"), None);
+        assert_eq!(parse_path("a."), None);
+        assert_eq!(parse_path("a.[1]"), None);
+        assert_eq!(parse_path("a[1]x"), None);
+        assert_eq!(parse_path("a[]"), None);
+        assert_eq!(parse_path("a]"), None);
+        assert_eq!(
+            parse_path(r#"a["b.c"][""]["\"\\"]"#),
+            Some(vec![
+                Segment::Key("a".into()),
+                Segment::Key("b.c".into()),
+                Segment::Key("".into()),
+                Segment::Key("\"\\".into()),
+            ])
+        );
+        assert_eq!(parse_path(r#"a["b"#), None);
+        assert_eq!(parse_path(r#"a["b"x]"#), None);
+        assert_eq!(parse_path(r#"a["\x"]"#), None);
+    }
+
+    #[test]
+    fn escapes_round_trip_through_render() {
+        assert_eq!(
+            parse_path(r"a\.b.c\[0\]\\"),
+            Some(vec![Segment::Key("a.b".into()), Segment::Key("c[0]\\".into())])
+        );
+        assert_eq!(parse_path(r"a\x"), None);
+        let segments = vec![Segment::Key("x.y[z]".into()), Segment::Index(3)];
+        assert_eq!(parse_path(&render(&segments)), Some(segments));
+
+        let v = from_str(r#"{"a.b":{"c":[1,2]}}"#).unwrap();
+        assert_eq!(v.flatten().to_string(), r#"{"a\\.b.c[0]":1,"a\\.b.c[1]":2}"#);
+        assert_eq!(v.flatten().unflatten().unwrap(), v);
+    }
+
+    #[test]
+    fn at_resolves_paths() {
+        let mut v = from_str(r#"[{"a":{"b":[true]}}]"#).unwrap();
+        assert_eq!(v.at("[0].a.b[0]"), Some(&Value::Bool(true)));
+        assert_eq!(v.at(""), Some(&v.clone()));
+        assert_eq!(v.at("[0].x"), None);
+        assert_eq!(v.at("[0]..a"), None);
+        *v.at_mut("[0].a").unwrap() = Value::Null;
+        assert_eq!(v.to_string(), r#"[{"a":null}]"#);
+    }
+
+    #[test]
+    fn set_path_creates_and_replaces() {
+        let mut v = from_str(r#"{"a":{"b":1}}"#).unwrap();
+        assert_eq!(v.set_path("a.b", 2).unwrap(), Some(Value::Number(1.0)));
+        assert_eq!(v.set_path("a.c.d", true).unwrap(), None);
+        v.set_path("list[0]", "x").unwrap();
+        v.set_path("list[1]", "y").unwrap();
+        assert_eq!(v.to_string(), r#"{"a":{"b":2,"c":{"d":true}},"list":["x","y"]}"#);
+    }
+
+    #[test]
+    fn set_path_reports_errors_with_location() {
+        let mut v = from_str(r#"{"a":{"b":1},"l":[]}"#).unwrap();
+        assert_eq!(
+            v.set_path("a.b.c", 0).unwrap_err(),
+            PathError::TypeMismatch { path: "a.b".into(), expected: "object", found: "number" }
+        );
+        assert_eq!(
+            v.set_path("l[3]", 0).unwrap_err(),
+            PathError::IndexOutOfBounds { path: "l".into(), index: 3, len: 0 }
+        );
+        assert_eq!(v.set_path("a..b", 0).unwrap_err(), PathError::InvalidPath("a..b".into()));
+    }
+
+    #[test]
+    fn failed_edits_leave_the_document_unchanged() {
+        let mut v = from_str("{}").unwrap();
+        assert_eq!(
+            v.set_path("new.l[3]", 0).unwrap_err(),
+            PathError::IndexOutOfBounds { path: "new.l".into(), index: 3, len: 0 }
+        );
+        assert_eq!(v.to_string(), "{}");
+
+        let mut v = from_str(r#"{"a":[{"b":1}]}"#).unwrap();
+        assert!(v.set_path("a[1].x.y.z", 0).is_ok());
+        assert!(v.set_path("a[0].c[0].d.e", 0).is_ok());
+        let before = v.clone();
+        assert!(v.set_path("a[0].b.c", 0).is_err());
+        assert!(v.set_path("a[3].x", 0).is_err());
+        assert!(v.ensure_object_path("q.r[1]").is_err());
+        assert_eq!(v, before);
+    }
+
+    #[test]
+    fn flatten_round_trips() {
+        for src in [
+            r#"{"a":{"b":[1,{"c":null}],"e":{},"f":[]},"g":"x"}"#,
+            r#"[[1,2],{"k":true}]"#,
+            "3",
+            "{}",
+        ] {
+            let v = from_str(src).unwrap();
+            assert_eq!(v.flatten().unflatten().unwrap(), v, "{}", src);
+        }
+        assert_eq!(from_str("[[1]]").unwrap().flatten().to_string(), r#"{"[0][0]":1}"#);
+    }
+
+    #[test]
+    fn flatten_round_trips_empty_keys() {
+        for src in [r#"{"":1}"#, r#"{"a":{"":1}}"#, r#"{"":{"":[{"":null}],"b":2}}"#] {
+            let v = from_str(src).unwrap();
+            assert_eq!(v.flatten().unflatten().unwrap(), v, "{}", src);
+        }
+        let v = from_str(r#"{"a":{"":[1]}}"#).unwrap();
+        assert_eq!(v.flatten().to_string(), r#"{"a[\"\"][0]":1}"#);
+        assert_eq!(v.at(r#"a[""][0]"#), Some(&Value::Number(1.0)));
+    }
+
+    #[test]
+    fn unflatten_reports_conflicts() {
+        let conflict = from_str(r#"{"a":1,"a.b":2}"#).unwrap();
+        assert!(matches!(conflict.unflatten(), Err(PathError::TypeMismatch { .. })));
+        let gap = from_str(r#"{"a[1]":1}"#).unwrap();
+        assert!(matches!(gap.unflatten(), Err(PathError::IndexOutOfBounds { .. })));
+        assert!(Value::from(1).unflatten().is_err());
+    }
+
+    #[test]
+    fn ensure_object_path_creates_nested_objects() {
+        let mut v = from_str(r#"{"a":{"x":1},"l":[]}"#).unwrap();
+        v.ensure_object_path("a.b.c").unwrap().insert("k", true).unwrap();
+        v.ensure_object_path("a.b").unwrap().insert("m", 2).unwrap();
+        v.ensure_object_path("l[0].n").unwrap();
+        assert_eq!(v.to_string(), r#"{"a":{"x":1,"b":{"c":{"k":true},"m":2}},"l":[{"n":{}}]}"#);
+        assert_eq!(
+            v.ensure_object_path("a.x").unwrap_err(),
+            PathError::TypeMismatch { path: "a.x".into(), expected: "object", found: "number" }
+        );
+        assert_eq!(
+            v.ensure_object_path("a.x.y").unwrap_err(),
+            PathError::TypeMismatch { path: "a.x".into(), expected: "object", found: "number" }
+        );
+        assert!(matches!(v.ensure_object_path(""), Ok(Value::Object(_))));
+    }
+
+    #[test]
+    fn insert_remove_push() {
+        let mut v = Value::Null;
+        v.insert("k", 1).unwrap();
+        assert_eq!(v.insert("k", 2).unwrap(), Some(Value::Number(1.0)));
+        assert_eq!(v.remove("k"), Some(Value::Number(2.0)));
+        assert_eq!(v.remove("k"), None);
+        assert!(v.push(1).is_err());
+
+        let mut arr = Value::Array(vec![]);
+        arr.push(Value::Null).unwrap();
+        assert_eq!(arr.to_string(), "[null]");
+        assert!(arr.insert("k", 1).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Implements JSON Pointer (RFC 6901) navigation over `json::Value`.
+//!
+//! A pointer is either the empty string, which refers to the whole document,
+//! or a sequence of `/`-prefixed reference tokens. Inside a token `~1` stands
+//! for `/` and `~0` stands for `~`.
+
+use super::value::Value;
+
+impl Value {
+    /// Looks up a nested value by JSON Pointer.
+    ///
+    /// Returns `None` if the pointer is malformed or does not resolve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::{self, Value};
+    /// let doc = json::from_str(r#"{"a":{"b":[10,20]},"c/d":true}"#).unwrap();
+    /// assert_eq!(doc.pointer("/a/b/1"), Some(&Value::Number(20.0)));
+    /// assert_eq!(doc.pointer("/c~1d"), Some(&Value::Bool(true)));
+    /// assert_eq!(doc.pointer(""), Some(&doc));
+    /// assert_eq!(doc.pointer("/a/x"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        let tokens = split_pointer(pointer)?;
+        let mut target = self;
+        for token in tokens {
+            target = match target {
+                Value::Object(map) => map.get(&token)?,
+                Value::Array(arr) => arr.get(parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+
+    /// Looks up a nested value by JSON Pointer, returning a mutable reference.
+    ///
+    /// Returns `None` if the pointer is malformed or does not resolve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::{self, Value};
+    /// let mut doc = json::from_str(r#"{"a":[1,2]}"#).unwrap();
+    /// *doc.pointer_mut("/a/0").unwrap() = Value::from("one");
+    /// assert_eq!(doc.to_string(), r#"{"a":["one",2]}"#);
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        let tokens = split_pointer(pointer)?;
+        let mut target = self;
+        for token in tokens {
+            target = match target {
+                Value::Object(map) => map.get_mut(&token)?,
+                Value::Array(arr) => arr.get_mut(parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+}
+
+/// Splits a pointer into its unescaped reference tokens.
+///
+/// Returns `None` if the pointer is neither empty nor starts with `/`, or if it
+/// contains an invalid `~` escape.
+pub(crate) fn split_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/')?;
+    rest.split('/').map(unescape_token).collect()
+}
+
+/// Encodes `~` as `~0` and `/` as `~1`, the inverse of `unescape_token`.
+pub(crate) fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Decodes `~1` into `/` and `~0` into `~`.
+pub(crate) fn unescape_token(token: &str) -> Option<String> {
+    let mut out = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c == '~' {
+            match chars.next() {
+                Some('0') => out.push('~'),
+                Some('1') => out.push('/'),
+                _ => return None,
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    Some(out)
+}
+
+/// Parses an array index token: decimal digits without leading zeros.
+///
+/// The special `-` token (one past the end) never resolves to an existing
+/// element, so it is rejected here.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty() || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    token.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    fn rfc_doc() -> Value {
+        // Example document from RFC 6901, section 5.
+        from_str(
+            r#"{
+                "foo": ["bar", "baz"],
+                "": 0,
+                "a/b": 1,
+                "c%d": 2,
+                "e^f": 3,
+                "g|h": 4,
+                "i\\j": 5,
+                "k\"l": 6,
+                " ": 7,
+                "m~n": 8
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_rfc_examples() {
+        let doc = rfc_doc();
+        assert_eq!(doc.pointer(""), Some(&doc));
+        assert_eq!(
+            doc.pointer("/foo"),
+            Some(&Value::Array(vec!["bar".into(), "baz".into()]))
+        );
+        assert_eq!(doc.pointer("/foo/0"), Some(&Value::from("bar")));
+        assert_eq!(doc.pointer("/"), Some(&Value::Number(0.0)));
+        assert_eq!(doc.pointer("/a~1b"), Some(&Value::Number(1.0)));
+        assert_eq!(doc.pointer("/c%d"), Some(&Value::Number(2.0)));
+        assert_eq!(doc.pointer("/i\\j"), Some(&Value::Number(5.0)));
+        assert_eq!(doc.pointer("/k\"l"), Some(&Value::Number(6.0)));
+        assert_eq!(doc.pointer("/ "), Some(&Value::Number(7.0)));
+        assert_eq!(doc.pointer("/m~0n"), Some(&Value::Number(8.0)));
+    }
+
+    #[test]
+    fn rejects_malformed_pointers_and_indices() {
+        let doc = rfc_doc();
+        assert_eq!(doc.pointer("foo"), None);
+        assert_eq!(doc.pointer("/m~2n"), None);
+        assert_eq!(doc.pointer("/foo/01"), None);
+        assert_eq!(doc.pointer("/foo/-"), None);
+        assert_eq!(doc.pointer("/foo/2"), None);
+        assert_eq!(doc.pointer("/foo/0/x"), None);
+    }
+
+    #[test]
+    fn escape_token_round_trips() {
+        let token = "a/b~c";
+        assert_eq!(escape_token(token), "a~1b~0c");
+        assert_eq!(unescape_token(&escape_token(token)).unwrap(), token);
+    }
+
+    #[test]
+    fn pointer_mut_edits_in_place() {
+        let mut doc = rfc_doc();
+        *doc.pointer_mut("/foo/1").unwrap() = Value::Null;
+        assert_eq!(doc.pointer("/foo/1"), Some(&Value::Null));
+        assert!(doc.pointer_mut("/missing").is_none());
+    }
+}
@@ -0,0 +1,633 @@
+//! A JSONPath-style query engine over `json::Value`.
+//!
+//! Supported syntax:
+//!
+//! * `$` the root node, `@` the current node (inside filters)
+//! * `.name`, `['name']`, `["name"]` child members
+//! * `[0]`, `[-1]` array indices (negative counts from the end)
+//! * `[0:4:2]` array slices with optional start, end, and step
+//! * `[0,2]`, `['a','b']` unions
+//! * `*`, `[*]` wildcards
+//! * `..name`, `..*`, `..[0]` recursive descent
+//! * `[?(@.price < 10)]` filters with `== != < <= > >=`, `&&`, `||`, `!`,
+//!   parentheses, and bare paths as existence tests (`[?(@.isbn)]`)
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json::{self, query};
+//!
+//! let doc = json::from_str(r#"{"store":{"book":[
+//!     {"author":"Rees","price":8.95},
+//!     {"author":"Waugh","price":12.99}
+//! ]}}"#).unwrap();
+//!
+//! let authors = query::query(&doc, "$.store.book[*].author").unwrap();
+//! assert_eq!(authors.len(), 2);
+//!
+//! let cheap = query::query(&doc, "$..book[?(@.price < 10)].author").unwrap();
+//! assert_eq!(cheap[0].to_string(), "\"Rees\"");
+//! ```
+
+use super::value::Value;
+use std::error::Error;
+use std::fmt;
+
+/// An error that can occur while parsing a query expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryError {
+    /// The expression ended while more input was expected.
+    UnexpectedEnd,
+    /// An unexpected character was found at the given character position.
+    UnexpectedChar(char, usize),
+    /// A number (index, slice bound, or literal) was malformed.
+    InvalidNumber(String),
+}
+
+impl Error for QueryError {}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::UnexpectedEnd => write!(f, "Unexpected end of query"),
+            QueryError::UnexpectedChar(c, pos) => {
+                write!(f, "Unexpected character '{}' at position {}", c, pos)
+            }
+            QueryError::InvalidNumber(s) => write!(f, "Invalid number in query: {}", s),
+        }
+    }
+}
+
+/// A compiled query, reusable across documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(Selector),
+    Descendant(Selector),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Index(i64),
+    Wildcard,
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Union(Vec<Selector>),
+    Filter(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CmpOp, Operand),
+    Exists(Operand),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Current(Vec<Segment>),
+    Root(Vec<Segment>),
+    Literal(Value),
+}
+
+/// Parses `expr` and returns references to every matching node of `root`,
+/// in document order.
+///
+/// # Errors
+///
+/// Returns a `QueryError` if the expression is malformed.
+pub fn query<'a>(root: &'a Value, expr: &str) -> Result<Vec<&'a Value>, QueryError> {
+    Ok(Query::parse(expr)?.select(root))
+}
+
+impl Query {
+    /// Compiles a query expression such as `$.store.book[*].author`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `QueryError` if the expression is malformed.
+    pub fn parse(expr: &str) -> Result<Self, QueryError> {
+        let mut cursor = Cursor::new(expr);
+        cursor.skip_ws();
+        cursor.expect('$')?;
+        let segments = cursor.parse_segments()?;
+        cursor.skip_ws();
+        match cursor.peek() {
+            None => Ok(Query { segments }),
+            Some(c) => Err(QueryError::UnexpectedChar(c, cursor.pos)),
+        }
+    }
+
+    /// Returns references to every node of `root` matched by this query.
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        select_segments(&self.segments, root, root)
+    }
+}
+
+// --- Evaluation ---
+
+fn select_segments<'a>(segments: &[Segment], start: &'a Value, root: &'a Value) -> Vec<&'a Value> {
+    let mut nodes = vec![start];
+    for segment in segments {
+        let mut next = Vec::new();
+        for node in nodes {
+            match segment {
+                Segment::Child(sel) => apply_selector(sel, node, root, &mut next),
+                Segment::Descendant(sel) => descend(sel, node, root, &mut next),
+            }
+        }
+        nodes = next;
+    }
+    nodes
+}
+
+fn descend<'a>(sel: &Selector, node: &'a Value, root: &'a Value, out: &mut Vec<&'a Value>) {
+    apply_selector(sel, node, root, out);
+    for child in children(node) {
+        descend(sel, child, root, out);
+    }
+}
+
+fn children(node: &Value) -> Box<dyn Iterator<Item = &Value> + '_> {
+    match node {
+        Value::Array(arr) => Box::new(arr.iter()),
+        Value::Object(map) => Box::new(map.values()),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+fn apply_selector<'a>(sel: &Selector, node: &'a Value, root: &'a Value, out: &mut Vec<&'a Value>) {
+    match sel {
+        Selector::Name(name) => {
+            if let Value::Object(map) = node {
+                out.extend(map.get(name));
+            }
+        }
+        Selector::Index(i) => {
+            if let Value::Array(arr) = node {
+                let len = arr.len() as i64;
+                let i = if *i < 0 { len + i } else { *i };
+                if (0..len).contains(&i) {
+                    out.push(&arr[i as usize]);
+                }
+            }
+        }
+        Selector::Wildcard => out.extend(children(node)),
+        Selector::Slice(start, end, step) => {
+            if let Value::Array(arr) = node {
+                for i in slice_indices(arr.len() as i64, *start, *end, step.unwrap_or(1)) {
+                    out.push(&arr[i]);
+                }
+            }
+        }
+        Selector::Union(sels) => {
+            for s in sels {
+                apply_selector(s, node, root, out);
+            }
+        }
+        Selector::Filter(expr) => {
+            out.extend(children(node).filter(|child| eval(expr, child, root)));
+        }
+    }
+}
+
+/// Computes slice positions with Python semantics.
+fn slice_indices(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let normalize = |i: i64| if i < 0 { len.saturating_add(i) } else { i };
+    // `step_by` never steps past the range, so huge steps cannot overflow.
+    let step_size = step.unsigned_abs() as usize;
+    if step > 0 {
+        let lo = start.map(normalize).unwrap_or(0).clamp(0, len);
+        let hi = end.map(normalize).unwrap_or(len).clamp(0, len);
+        (lo..hi).step_by(step_size).map(|i| i as usize).collect()
+    } else if step < 0 {
+        let hi = start.map(normalize).unwrap_or(len - 1).clamp(-1, len - 1);
+        let lo = end.map(normalize).unwrap_or(-1).clamp(-1, len - 1);
+        (lo + 1..=hi).rev().step_by(step_size).map(|i| i as usize).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+fn eval(expr: &Expr, current: &Value, root: &Value) -> bool {
+    match expr {
+        Expr::Or(a, b) => eval(a, current, root) || eval(b, current, root),
+        Expr::And(a, b) => eval(a, current, root) && eval(b, current, root),
+        Expr::Not(e) => !eval(e, current, root),
+        Expr::Exists(op) => resolve(op, current, root).is_some(),
+        Expr::Compare(lhs, op, rhs) => {
+            let lhs = resolve(lhs, current, root);
+            let rhs = resolve(rhs, current, root);
+            match op {
+                CmpOp::Eq => lhs == rhs,
+                CmpOp::Ne => lhs != rhs,
+                _ => match (lhs, rhs) {
+                    (Some(Value::Number(a)), Some(Value::Number(b))) => ordered(*op, a.partial_cmp(b)),
+                    (Some(Value::String(a)), Some(Value::String(b))) => ordered(*op, a.partial_cmp(b)),
+                    _ => false,
+                },
+            }
+        }
+    }
+}
+
+fn ordered(op: CmpOp, ord: Option<std::cmp::Ordering>) -> bool {
+    use std::cmp::Ordering::*;
+    matches!(
+        (op, ord),
+        (CmpOp::Lt, Some(Less))
+            | (CmpOp::Le, Some(Less | Equal))
+            | (CmpOp::Gt, Some(Greater))
+            | (CmpOp::Ge, Some(Greater | Equal))
+    )
+}
+
+/// Resolves an operand to a single value. Paths yield their first match.
+fn resolve<'a>(op: &'a Operand, current: &'a Value, root: &'a Value) -> Option<&'a Value> {
+    match op {
+        Operand::Literal(v) => Some(v),
+        Operand::Current(segs) => select_segments(segs, current, root).first().copied(),
+        Operand::Root(segs) => select_segments(segs, root, root).first().copied(),
+    }
+}
+
+// --- Parsing ---
+
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Cursor {
+    fn new(s: &str) -> Self {
+        Cursor { chars: s.chars().collect(), pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), QueryError> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(QueryError::UnexpectedChar(c, self.pos - 1)),
+            None => Err(QueryError::UnexpectedEnd),
+        }
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let n = s.chars().count();
+        if self.chars[self.pos..].iter().take(n).copied().eq(s.chars()) {
+            self.pos += n;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unexpected(&self) -> QueryError {
+        match self.peek() {
+            Some(c) => QueryError::UnexpectedChar(c, self.pos),
+            None => QueryError::UnexpectedEnd,
+        }
+    }
+
+    fn parse_segments(&mut self) -> Result<Vec<Segment>, QueryError> {
+        let mut segments = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') if self.peek_at(1) == Some('.') => {
+                    self.pos += 2;
+                    let sel = if self.peek() == Some('[') {
+                        self.parse_bracket()?
+                    } else {
+                        self.parse_dot_selector()?
+                    };
+                    segments.push(Segment::Descendant(sel));
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    segments.push(Segment::Child(self.parse_dot_selector()?));
+                }
+                Some('[') => segments.push(Segment::Child(self.parse_bracket()?)),
+                _ => return Ok(segments),
+            }
+        }
+    }
+
+    fn parse_dot_selector(&mut self) -> Result<Selector, QueryError> {
+        if self.peek() == Some('*') {
+            self.pos += 1;
+            return Ok(Selector::Wildcard);
+        }
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_' || c == '-' || c == '$') {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.unexpected());
+        }
+        Ok(Selector::Name(self.chars[start..self.pos].iter().collect()))
+    }
+
+    fn parse_bracket(&mut self) -> Result<Selector, QueryError> {
+        self.expect('[')?;
+        self.skip_ws();
+        let sel = match self.peek() {
+            Some('?') => {
+                self.pos += 1;
+                self.skip_ws();
+                Selector::Filter(Box::new(self.parse_or()?))
+            }
+            Some('*') => {
+                self.pos += 1;
+                Selector::Wildcard
+            }
+            _ => {
+                let mut items = vec![self.parse_bracket_item()?];
+                self.skip_ws();
+                while self.peek() == Some(',') {
+                    self.pos += 1;
+                    self.skip_ws();
+                    items.push(self.parse_bracket_item()?);
+                    self.skip_ws();
+                }
+                if items.len() == 1 { items.pop().unwrap() } else { Selector::Union(items) }
+            }
+        };
+        self.skip_ws();
+        self.expect(']')?;
+        Ok(sel)
+    }
+
+    fn parse_bracket_item(&mut self) -> Result<Selector, QueryError> {
+        match self.peek() {
+            Some('\'') | Some('"') => Ok(Selector::Name(self.parse_quoted()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            _ => {
+                let start = self.parse_opt_int()?;
+                self.skip_ws();
+                if self.peek() != Some(':') {
+                    return start.map(Selector::Index).ok_or_else(|| self.unexpected());
+                }
+                self.pos += 1;
+                self.skip_ws();
+                let end = self.parse_opt_int()?;
+                self.skip_ws();
+                let step = if self.peek() == Some(':') {
+                    self.pos += 1;
+                    self.skip_ws();
+                    self.parse_opt_int()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice(start, end, step))
+            }
+        }
+    }
+
+    fn parse_opt_int(&mut self) -> Result<Option<i64>, QueryError> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Ok(None);
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse().map(Some).map_err(|_| QueryError::InvalidNumber(text))
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, QueryError> {
+        let quote = self.next().ok_or(QueryError::UnexpectedEnd)?;
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('\\') => match self.next() {
+                    Some(c) => s.push(c),
+                    None => return Err(QueryError::UnexpectedEnd),
+                },
+                Some(c) if c == quote => return Ok(s),
+                Some(c) => s.push(c),
+                None => return Err(QueryError::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut lhs = self.parse_and()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("||") {
+                return Ok(lhs);
+            }
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("&&") {
+                return Ok(lhs);
+            }
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        self.skip_ws();
+        match self.peek() {
+            Some('!') if self.peek_at(1) != Some('=') => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let e = self.parse_or()?;
+                self.skip_ws();
+                self.expect(')')?;
+                Ok(e)
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, QueryError> {
+        let lhs = self.parse_operand()?;
+        self.skip_ws();
+        let op = if self.eat("==") {
+            CmpOp::Eq
+        } else if self.eat("!=") {
+            CmpOp::Ne
+        } else if self.eat("<=") {
+            CmpOp::Le
+        } else if self.eat(">=") {
+            CmpOp::Ge
+        } else if self.eat("<") {
+            CmpOp::Lt
+        } else if self.eat(">") {
+            CmpOp::Gt
+        } else {
+            return Ok(Expr::Exists(lhs));
+        };
+        self.skip_ws();
+        let rhs = self.parse_operand()?;
+        Ok(Expr::Compare(lhs, op, rhs))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, QueryError> {
+        self.skip_ws();
+        match self.peek() {
+            Some('@') => {
+                self.pos += 1;
+                Ok(Operand::Current(self.parse_segments()?))
+            }
+            Some('$') => {
+                self.pos += 1;
+                Ok(Operand::Root(self.parse_segments()?))
+            }
+            Some('\'') | Some('"') => Ok(Operand::Literal(Value::String(self.parse_quoted()?))),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                while matches!(self.peek(), Some(c) if c.is_ascii_digit() || "+-.eE".contains(c)) {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                text.parse()
+                    .map(|n| Operand::Literal(Value::Number(n)))
+                    .map_err(|_| QueryError::InvalidNumber(text))
+            }
+            _ if self.eat("true") => Ok(Operand::Literal(Value::Bool(true))),
+            _ if self.eat("false") => Ok(Operand::Literal(Value::Bool(false))),
+            _ if self.eat("null") => Ok(Operand::Literal(Value::Null)),
+            _ => Err(self.unexpected()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    fn store() -> Value {
+        from_str(
+            r#"{"store":{
+                "book":[
+                    {"category":"reference","author":"Nigel Rees","title":"Sayings","price":8.95},
+                    {"category":"fiction","author":"Evelyn Waugh","title":"Sword","price":12.99},
+                    {"category":"fiction","author":"Herman Melville","title":"Moby Dick","isbn":"0-553","price":8.99},
+                    {"category":"fiction","author":"J. R. R. Tolkien","title":"LOTR","isbn":"0-395","price":22.99}
+                ],
+                "bicycle":{"color":"red","price":19.95}
+            }}"#,
+        )
+        .unwrap()
+    }
+
+    fn strings(nodes: Vec<&Value>) -> Vec<String> {
+        nodes.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn child_wildcard_and_index() {
+        let doc = store();
+        assert_eq!(query(&doc, "$.store.book[*].author").unwrap().len(), 4);
+        assert_eq!(strings(query(&doc, "$.store.book[0].title").unwrap()), ["\"Sayings\""]);
+        assert_eq!(strings(query(&doc, "$['store']['bicycle'].color").unwrap()), ["\"red\""]);
+        assert_eq!(strings(query(&doc, "$.store.book[-1].title").unwrap()), ["\"LOTR\""]);
+    }
+
+    #[test]
+    fn slices_and_unions() {
+        let doc = from_str("[0,1,2,3,4,5]").unwrap();
+        assert_eq!(strings(query(&doc, "$[1:3]").unwrap()), ["1", "2"]);
+        assert_eq!(strings(query(&doc, "$[::2]").unwrap()), ["0", "2", "4"]);
+        assert_eq!(strings(query(&doc, "$[-2:]").unwrap()), ["4", "5"]);
+        assert_eq!(strings(query(&doc, "$[::-2]").unwrap()), ["5", "3", "1"]);
+        assert_eq!(strings(query(&doc, "$[0,5]").unwrap()), ["0", "5"]);
+    }
+
+    #[test]
+    fn slices_with_extreme_steps_and_bounds() {
+        let doc = from_str("[1,2,3]").unwrap();
+        assert_eq!(strings(query(&doc, "$[1::9223372036854775807]").unwrap()), ["2"]);
+        assert_eq!(strings(query(&doc, "$[::-9223372036854775808]").unwrap()), ["3"]);
+        assert_eq!(strings(query(&doc, "$[-9223372036854775808:9223372036854775807]").unwrap()), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn recursive_descent() {
+        let doc = store();
+        assert_eq!(query(&doc, "$..price").unwrap().len(), 5);
+        assert_eq!(query(&doc, "$..book[2].author").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn filters() {
+        let doc = store();
+        let cheap = query(&doc, "$.store.book[?(@.price < 10)].title").unwrap();
+        assert_eq!(strings(cheap), ["\"Sayings\"", "\"Moby Dick\""]);
+
+        let with_isbn = query(&doc, "$..book[?(@.isbn)].title").unwrap();
+        assert_eq!(with_isbn.len(), 2);
+
+        let combo = query(
+            &doc,
+            "$..book[?(@.category == 'fiction' && !(@.price > 20 || @.isbn))].author",
+        )
+        .unwrap();
+        assert_eq!(strings(combo), ["\"Evelyn Waugh\""]);
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(Query::parse("store").unwrap_err(), QueryError::UnexpectedChar('s', 0));
+        assert_eq!(Query::parse("$.a[").unwrap_err(), QueryError::UnexpectedEnd);
+        assert!(Query::parse("$.a[?(@.b <)]").is_err());
+    }
+}
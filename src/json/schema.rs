@@ -0,0 +1,364 @@
+//! Validates `json::Value` documents against a JSON Schema.
+//!
+//! A schema is compiled once with `Schema::compile` and can then validate
+//! any number of documents. The supported keywords are a draft 2020-12
+//! subset: `type`, `properties`, `required`, `enum`, `minimum`, `maximum`,
+//! `items`, and `pattern`. Unknown keywords are ignored, as the
+//! specification requires; `true` and `false` are accepted as schemas that
+//! allow or reject everything.
+//!
+//! Validation reports every violation, each with the JSON Pointer of the
+//! offending value.
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json;
+//! use stdt::json::schema::Schema;
+//!
+//! let schema = Schema::compile(&json!({
+//!     "type": "object",
+//!     "required": ["name"],
+//!     "properties": {
+//!         "name": {"type": "string", "pattern": "^[a-z]+$"},
+//!         "ports": {"type": "array", "items": {"type": "integer", "minimum": 1, "maximum": 65535}}
+//!     }
+//! })).unwrap();
+//!
+//! assert!(schema.is_valid(&json!({"name": "web", "ports": [80, 443]})));
+//!
+//! let violations = schema.validate(&json!({"name": "Web", "ports": [0]}));
+//! let pointers: Vec<&str> = violations.iter().map(|v| v.pointer.as_str()).collect();
+//! assert_eq!(pointers, ["/name", "/ports/0"]);
+//! ```
+
+mod regex;
+
+use self::regex::Regex;
+use super::pointer::escape_token;
+use super::value::Value;
+use std::error::Error;
+use std::fmt;
+
+/// An error that can occur while compiling a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    /// A keyword has a value of the wrong shape, e.g. `"minimum": "3"`.
+    InvalidKeyword {
+        /// JSON Pointer to the keyword within the schema.
+        pointer: String,
+        reason: String,
+    },
+    /// A `pattern` is not a regular expression this module supports.
+    InvalidPattern {
+        /// JSON Pointer to the keyword within the schema.
+        pointer: String,
+        pattern: String,
+        reason: String,
+    },
+}
+
+impl Error for SchemaError {}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::InvalidKeyword { pointer, reason } => {
+                write!(f, "Invalid schema keyword at '{}': {}", pointer, reason)
+            }
+            SchemaError::InvalidPattern { pointer, pattern, reason } => {
+                write!(f, "Invalid pattern '{}' at '{}': {}", pattern, pointer, reason)
+            }
+        }
+    }
+}
+
+/// A single way in which a document fails a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// JSON Pointer to the offending value in the document.
+    pub pointer: String,
+    /// The schema keyword that failed, e.g. `"required"`.
+    pub keyword: &'static str,
+    /// A human-readable description of the failure.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", if self.pointer.is_empty() { "/" } else { &self.pointer }, self.message)
+    }
+}
+
+/// A compiled schema.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    root: Node,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Set by the `false` schema; `true` compiles to an empty node.
+    reject_all: bool,
+    types: Option<Vec<String>>,
+    properties: Vec<(String, Node)>,
+    required: Vec<String>,
+    allowed: Option<Vec<Value>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    items: Option<Box<Node>>,
+    pattern: Option<(String, Regex)>,
+}
+
+const TYPE_NAMES: [&str; 7] = ["null", "boolean", "object", "array", "number", "string", "integer"];
+
+impl Schema {
+    /// Compiles a schema document.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SchemaError` if a supported keyword is malformed.
+    pub fn compile(schema: &Value) -> Result<Schema, SchemaError> {
+        Ok(Schema { root: compile_node(schema, "")? })
+    }
+
+    /// Validates `document`, returning every violation found (empty if valid).
+    pub fn validate(&self, document: &Value) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut pointer = String::new();
+        validate_node(&self.root, document, &mut pointer, &mut violations);
+        violations
+    }
+
+    /// Returns `true` if `document` satisfies the schema.
+    pub fn is_valid(&self, document: &Value) -> bool {
+        self.validate(document).is_empty()
+    }
+}
+
+fn invalid(pointer: &str, keyword: &str, reason: &str) -> SchemaError {
+    SchemaError::InvalidKeyword { pointer: format!("{}/{}", pointer, keyword), reason: reason.to_string() }
+}
+
+fn compile_node(schema: &Value, pointer: &str) -> Result<Node, SchemaError> {
+    let map = match schema {
+        Value::Bool(allow) => return Ok(Node { reject_all: !allow, ..Default::default() }),
+        Value::Object(map) => map,
+        _ => {
+            return Err(SchemaError::InvalidKeyword {
+                pointer: pointer.to_string(),
+                reason: "a schema must be an object or a boolean".to_string(),
+            })
+        }
+    };
+    let mut node = Node::default();
+
+    if let Some(types) = map.get("type") {
+        let names: Vec<&Value> = match types {
+            Value::Array(arr) => arr.iter().collect(),
+            single => vec![single],
+        };
+        let mut parsed = Vec::new();
+        for name in names {
+            match name.as_str() {
+                Some(name) if TYPE_NAMES.contains(&name) => parsed.push(name.to_string()),
+                _ => return Err(invalid(pointer, "type", "expected a type name or an array of type names")),
+            }
+        }
+        node.types = Some(parsed);
+    }
+
+    if let Some(properties) = map.get("properties") {
+        let properties = properties
+            .as_object()
+            .ok_or_else(|| invalid(pointer, "properties", "expected an object"))?;
+        for (key, sub) in properties {
+            let sub_pointer = format!("{}/properties/{}", pointer, escape_token(key));
+            node.properties.push((key.to_string(), compile_node(sub, &sub_pointer)?));
+        }
+    }
+
+    if let Some(required) = map.get("required") {
+        let names = required.as_array().and_then(|arr| {
+            arr.iter().map(|v| v.as_str().map(str::to_string)).collect::<Option<Vec<_>>>()
+        });
+        node.required = names.ok_or_else(|| invalid(pointer, "required", "expected an array of strings"))?;
+    }
+
+    if let Some(allowed) = map.get("enum") {
+        let allowed = allowed.as_array().ok_or_else(|| invalid(pointer, "enum", "expected an array"))?;
+        node.allowed = Some(allowed.clone());
+    }
+
+    for (keyword, slot) in [("minimum", &mut node.minimum), ("maximum", &mut node.maximum)] {
+        if let Some(bound) = map.get(keyword) {
+            *slot = Some(bound.as_f64().ok_or_else(|| invalid(pointer, keyword, "expected a number"))?);
+        }
+    }
+
+    if let Some(items) = map.get("items") {
+        node.items = Some(Box::new(compile_node(items, &format!("{}/items", pointer))?));
+    }
+
+    if let Some(pattern) = map.get("pattern") {
+        let pattern = pattern.as_str().ok_or_else(|| invalid(pointer, "pattern", "expected a string"))?;
+        let regex = Regex::new(pattern).map_err(|reason| SchemaError::InvalidPattern {
+            pointer: format!("{}/pattern", pointer),
+            pattern: pattern.to_string(),
+            reason,
+        })?;
+        node.pattern = Some((pattern.to_string(), regex));
+    }
+
+    Ok(node)
+}
+
+fn type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        // Every integer is also a number.
+        name => value.type_name() == name,
+    }
+}
+
+fn validate_node(node: &Node, value: &Value, pointer: &mut String, out: &mut Vec<Violation>) {
+    let mut report = |keyword, message: String| {
+        out.push(Violation { pointer: pointer.clone(), keyword, message });
+    };
+
+    if node.reject_all {
+        report("false", "no value is allowed here".to_string());
+        return;
+    }
+
+    if let Some(types) = &node.types
+        && !types.iter().any(|t| type_matches(t, value))
+    {
+        report("type", format!("expected {}, found {}", types.join(" or "), value.type_name()));
+    }
+
+    if let Some(allowed) = &node.allowed
+        && !allowed.contains(value)
+    {
+        report("enum", format!("{} is not one of the allowed values", value));
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = node.minimum.filter(|min| n < *min) {
+            report("minimum", format!("{} is less than the minimum of {}", n, min));
+        }
+        if let Some(max) = node.maximum.filter(|max| n > *max) {
+            report("maximum", format!("{} is greater than the maximum of {}", n, max));
+        }
+    }
+
+    if let (Some((source, regex)), Some(s)) = (&node.pattern, value.as_str())
+        && !regex.is_match(s)
+    {
+        report("pattern", format!("{:?} does not match the pattern {:?}", s, source));
+    }
+
+    if let Some(map) = value.as_object() {
+        for name in &node.required {
+            if !map.contains_key(name) {
+                report("required", format!("missing required property {:?}", name));
+            }
+        }
+        for (key, sub) in &node.properties {
+            if let Some(member) = map.get(key) {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&escape_token(key));
+                validate_node(sub, member, pointer, out);
+                pointer.truncate(len);
+            }
+        }
+    }
+
+    if let (Some(items), Some(arr)) = (&node.items, value.as_array()) {
+        for (i, element) in arr.iter().enumerate() {
+            let len = pointer.len();
+            pointer.push('/');
+            pointer.push_str(&i.to_string());
+            validate_node(items, element, pointer, out);
+            pointer.truncate(len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    fn violations(schema: &str, doc: &str) -> Vec<(String, &'static str)> {
+        let schema = Schema::compile(&from_str(schema).unwrap()).unwrap();
+        schema
+            .validate(&from_str(doc).unwrap())
+            .into_iter()
+            .map(|v| (v.pointer, v.keyword))
+            .collect()
+    }
+
+    #[test]
+    fn type_and_enum() {
+        assert!(violations(r#"{"type":"integer"}"#, "3").is_empty());
+        assert_eq!(violations(r#"{"type":"integer"}"#, "3.5"), [("".to_string(), "type")]);
+        assert!(violations(r#"{"type":["string","null"]}"#, "null").is_empty());
+        assert!(violations(r#"{"type":"number"}"#, "3").is_empty());
+        assert_eq!(violations(r#"{"enum":["a",1,{"x":null}]}"#, r#""b""#), [("".to_string(), "enum")]);
+        assert!(violations(r#"{"enum":["a",1,{"x":null}]}"#, r#"{"x":null}"#).is_empty());
+        assert_eq!(violations("false", "1"), [("".to_string(), "false")]);
+        assert!(violations("true", "1").is_empty());
+    }
+
+    #[test]
+    fn nested_violations_carry_pointers() {
+        let schema = r#"{
+            "type": "object",
+            "required": ["id", "tags"],
+            "properties": {
+                "id": {"minimum": 1},
+                "a/b": {"type": "string"},
+                "tags": {"items": {"type": "string", "pattern": "^[a-z]+$"}}
+            }
+        }"#;
+        let doc = r#"{"id":0,"a/b":1,"tags":["ok","NO",3]}"#;
+        assert_eq!(
+            violations(schema, doc),
+            [
+                ("/id".to_string(), "minimum"),
+                ("/a~1b".to_string(), "type"),
+                ("/tags/1".to_string(), "pattern"),
+                ("/tags/2".to_string(), "type"),
+            ]
+        );
+        assert_eq!(violations(schema, "{}"), [("".to_string(), "required"), ("".to_string(), "required")]);
+        // Keywords for other types do not apply.
+        assert!(violations(r#"{"minimum":5,"pattern":"x","items":false}"#, r#""xyz""#).is_empty());
+        assert!(violations(r#"{"minimum":5,"pattern":"x","required":["a"]}"#, "[]").is_empty());
+    }
+
+    #[test]
+    fn pattern_validates_very_long_strings() {
+        let schema = Schema::compile(&from_str(r#"{"pattern":"^[a-z]+$"}"#).unwrap()).unwrap();
+        assert!(schema.validate(&Value::String("a".repeat(100_000))).is_empty());
+        assert_eq!(schema.validate(&Value::String("a".repeat(100_000) + "!")).len(), 1);
+    }
+
+    #[test]
+    fn compile_errors_point_into_the_schema() {
+        let err = Schema::compile(&from_str(r#"{"properties":{"a":{"maximum":"9"}}}"#).unwrap()).unwrap_err();
+        assert_eq!(
+            err,
+            SchemaError::InvalidKeyword {
+                pointer: "/properties/a/maximum".into(),
+                reason: "expected a number".into()
+            }
+        );
+        let err = Schema::compile(&from_str(r#"{"pattern":"(a"}"#).unwrap()).unwrap_err();
+        assert!(matches!(err, SchemaError::InvalidPattern { ref pointer, .. } if pointer == "/pattern"));
+        assert!(Schema::compile(&from_str(r#"{"type":"text"}"#).unwrap()).is_err());
+        assert!(Schema::compile(&from_str("1").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,516 @@
+//! A small regular expression engine for the `pattern` keyword.
+//!
+//! Supports the subset of ECMA-262 syntax that schemas use in practice:
+//! literals, `.`, character classes (`[a-z]`, `[^0-9]`), the `\d \w \s`
+//! shorthands and their negations, anchors `^` and `$`, groups `(...)` and
+//! `(?:...)`, alternation `|`, and the quantifiers `* + ? {n} {n,} {n,m}`.
+//! Lazy quantifiers (`*?`) are accepted; since only a yes/no answer is
+//! needed, they behave like greedy ones. Counted bounds are limited to 1000.
+//!
+//! Patterns are compiled to a small instruction program and run as a Pike VM
+//! (a breadth-first NFA simulation), so matching takes time linear in the
+//! input and never recurses, however the pattern is written.
+//!
+//! As in JSON Schema, a pattern matches if it matches anywhere in the input.
+
+/// The largest program a pattern may compile to. Counted quantifiers are
+/// expanded, so this bounds patterns like `(a{1000}){1000}`.
+const MAX_PROGRAM_LEN: usize = 10_000;
+
+/// The largest bound allowed in a `{n,m}` quantifier.
+const MAX_REPEAT: usize = 1_000;
+
+/// A compiled pattern.
+#[derive(Debug, Clone)]
+pub(crate) struct Regex {
+    program: Vec<Inst>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class { items: Vec<ClassItem>, negated: bool },
+    Start,
+    End,
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat { node: Box<Node>, min: usize, max: Option<usize> },
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    /// One of the `\d \D \w \W \s \S` shorthands.
+    Shorthand(char),
+}
+
+/// One instruction of a compiled program. Jump targets are program indices.
+#[derive(Debug, Clone)]
+enum Inst {
+    Char(char),
+    Any,
+    Class { items: Vec<ClassItem>, negated: bool },
+    Start,
+    End,
+    Split(usize, usize),
+    Jmp(usize),
+    Match,
+}
+
+/// Whether the simulation is at the start and/or end of the input.
+#[derive(Clone, Copy)]
+struct Position {
+    start: bool,
+    end: bool,
+}
+
+impl Regex {
+    /// Compiles `pattern`, returning a description of the problem if it is
+    /// malformed, uses unsupported syntax, or is too large.
+    pub(crate) fn new(pattern: &str) -> Result<Regex, String> {
+        let mut parser = RegexParser { chars: pattern.chars().collect(), pos: 0 };
+        let root = parser.parse_alt()?;
+        if let Some(c) = parser.peek() {
+            return Err(format!("unexpected '{}' at offset {}", c, parser.pos));
+        }
+        let mut compiler = Compiler { program: Vec::new() };
+        compiler.compile(&root)?;
+        compiler.emit(Inst::Match)?;
+        Ok(Regex { program: compiler.program })
+    }
+
+    /// Returns `true` if the pattern matches anywhere in `text`.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        let mut stack = Vec::new();
+        // `seen[pc] == generation` marks `pc` as already queued for this step.
+        let mut seen = vec![0usize; self.program.len()];
+        let mut generation = 1;
+        let mut chars = text.chars().peekable();
+        let mut at = Position { start: true, end: chars.peek().is_none() };
+        loop {
+            // Starting a fresh thread at every position makes the search
+            // unanchored.
+            if self.add_thread(0, at, generation, &mut seen, &mut stack, &mut current) {
+                return true;
+            }
+            let Some(c) = chars.next() else {
+                return false;
+            };
+            generation += 1;
+            at = Position { start: false, end: chars.peek().is_none() };
+            for &pc in &current {
+                let hit = match &self.program[pc] {
+                    Inst::Char(expected) => *expected == c,
+                    Inst::Any => c != '\n',
+                    Inst::Class { items, negated } => {
+                        items.iter().any(|item| class_item_matches(item, c)) != *negated
+                    }
+                    _ => false,
+                };
+                if hit && self.add_thread(pc + 1, at, generation, &mut seen, &mut stack, &mut next) {
+                    return true;
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+    }
+
+    /// Follows the empty transitions from `pc`, queueing every reachable
+    /// character-consuming instruction on `list`. Returns `true` as soon as
+    /// `Match` is reachable.
+    fn add_thread(
+        &self,
+        pc: usize,
+        at: Position,
+        generation: usize,
+        seen: &mut [usize],
+        stack: &mut Vec<usize>,
+        list: &mut Vec<usize>,
+    ) -> bool {
+        stack.clear();
+        stack.push(pc);
+        while let Some(pc) = stack.pop() {
+            if seen[pc] == generation {
+                continue;
+            }
+            seen[pc] = generation;
+            match self.program[pc] {
+                Inst::Match => return true,
+                Inst::Jmp(target) => stack.push(target),
+                Inst::Split(a, b) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                Inst::Start if at.start => stack.push(pc + 1),
+                Inst::End if at.end => stack.push(pc + 1),
+                Inst::Start | Inst::End => {}
+                Inst::Char(_) | Inst::Any | Inst::Class { .. } => list.push(pc),
+            }
+        }
+        false
+    }
+}
+
+/// Lowers a parsed pattern to a Pike VM program.
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn emit(&mut self, inst: Inst) -> Result<usize, String> {
+        if self.program.len() >= MAX_PROGRAM_LEN {
+            return Err("pattern is too large".to_string());
+        }
+        self.program.push(inst);
+        Ok(self.program.len() - 1)
+    }
+
+    fn compile(&mut self, node: &Node) -> Result<(), String> {
+        match node {
+            Node::Char(c) => {
+                self.emit(Inst::Char(*c))?;
+            }
+            Node::Any => {
+                self.emit(Inst::Any)?;
+            }
+            Node::Class { items, negated } => {
+                self.emit(Inst::Class { items: items.clone(), negated: *negated })?;
+            }
+            Node::Start => {
+                self.emit(Inst::Start)?;
+            }
+            Node::End => {
+                self.emit(Inst::End)?;
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node)?;
+                }
+            }
+            Node::Alt(alts) => {
+                let mut exits = Vec::new();
+                let (last, rest) = alts.split_last().expect("alternation has branches");
+                for alt in rest {
+                    let split = self.emit(Inst::Split(0, 0))?;
+                    self.compile(alt)?;
+                    exits.push(self.emit(Inst::Jmp(0))?);
+                    self.program[split] = Inst::Split(split + 1, self.program.len());
+                }
+                self.compile(last)?;
+                let end = self.program.len();
+                for exit in exits {
+                    self.program[exit] = Inst::Jmp(end);
+                }
+            }
+            Node::Repeat { node, min, max } => {
+                let start = self.program.len();
+                for _ in 0..*min {
+                    self.compile(node)?;
+                    // A body that compiles to nothing matches only the empty
+                    // string, however often it is repeated.
+                    if self.program.len() == start {
+                        return Ok(());
+                    }
+                }
+                match max {
+                    None => {
+                        let split = self.emit(Inst::Split(0, 0))?;
+                        self.compile(node)?;
+                        self.emit(Inst::Jmp(split))?;
+                        self.program[split] = Inst::Split(split + 1, self.program.len());
+                    }
+                    Some(max) => {
+                        let mut splits = Vec::new();
+                        for _ in *min..*max {
+                            splits.push(self.emit(Inst::Split(0, 0))?);
+                            self.compile(node)?;
+                        }
+                        let end = self.program.len();
+                        for split in splits {
+                            self.program[split] = Inst::Split(split + 1, end);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn class_item_matches(item: &ClassItem, c: char) -> bool {
+    match *item {
+        ClassItem::Range(lo, hi) => lo <= c && c <= hi,
+        ClassItem::Shorthand(kind) => {
+            let hit = match kind.to_ascii_lowercase() {
+                'd' => c.is_ascii_digit(),
+                'w' => c.is_ascii_alphanumeric() || c == '_',
+                _ => c.is_whitespace(),
+            };
+            hit != kind.is_ascii_uppercase()
+        }
+    }
+}
+
+struct RegexParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl RegexParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_alt(&mut self) -> Result<Node, String> {
+        let mut alts = vec![self.parse_concat()?];
+        while self.eat('|') {
+            alts.push(self.parse_concat()?);
+        }
+        Ok(if alts.len() == 1 { alts.pop().unwrap() } else { Node::Alt(alts) })
+    }
+
+    fn parse_concat(&mut self) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            nodes.push(self.parse_quantifier(atom)?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some('{') => match self.parse_braces() {
+                Some(bounds) => bounds,
+                // Not a valid quantifier: ECMA-262 treats `{` as a literal.
+                None => return Ok(atom),
+            },
+            Some(c @ ('*' | '+' | '?')) => {
+                self.pos += 1;
+                match c {
+                    '*' => (0, None),
+                    '+' => (1, None),
+                    _ => (0, Some(1)),
+                }
+            }
+            _ => return Ok(atom),
+        };
+        if matches!(atom, Node::Start | Node::End) {
+            return Err("nothing to repeat".to_string());
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err("quantifier range out of order".to_string());
+        }
+        if min > MAX_REPEAT || max.is_some_and(|max| max > MAX_REPEAT) {
+            return Err(format!("quantifier bound above {}", MAX_REPEAT));
+        }
+        self.eat('?');
+        Ok(Node::Repeat { node: Box::new(atom), min, max })
+    }
+
+    /// Parses `{n}`, `{n,}`, or `{n,m}`, consuming it only on success.
+    fn parse_braces(&mut self) -> Option<(usize, Option<usize>)> {
+        let start = self.pos;
+        self.pos += 1;
+        let result = (|| {
+            let min = self.parse_digits()?;
+            if self.eat('}') {
+                return Some((min, Some(min)));
+            }
+            if !self.eat(',') {
+                return None;
+            }
+            if self.eat('}') {
+                return Some((min, None));
+            }
+            let max = self.parse_digits()?;
+            self.eat('}').then_some((min, Some(max)))
+        })();
+        if result.is_none() {
+            self.pos = start;
+        }
+        result
+    }
+
+    fn parse_digits(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return None;
+        }
+        // Bounds too large for `usize` saturate, so they are rejected as too large.
+        Some(self.chars[start..self.pos].iter().collect::<String>().parse().unwrap_or(usize::MAX))
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let c = self.next().ok_or("unexpected end of pattern")?;
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                if self.eat('?') && !self.eat(':') {
+                    return Err("only (?:...) groups are supported".to_string());
+                }
+                let inner = self.parse_alt()?;
+                if !self.eat(')') {
+                    return Err("unclosed group".to_string());
+                }
+                inner
+            }
+            '[' => self.parse_class()?,
+            '\\' => match self.parse_escape()? {
+                ClassItem::Range(c, _) => Node::Char(c),
+                shorthand => Node::Class { items: vec![shorthand], negated: false },
+            },
+            '*' | '+' | '?' => return Err("nothing to repeat".to_string()),
+            ')' => return Err("unmatched ')'".to_string()),
+            c => Node::Char(c),
+        })
+    }
+
+    fn parse_class(&mut self) -> Result<Node, String> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        loop {
+            let c = self.next().ok_or("unclosed character class")?;
+            let lo = match c {
+                ']' => break,
+                '\\' => match self.parse_escape()? {
+                    ClassItem::Range(c, _) => c,
+                    shorthand => {
+                        items.push(shorthand);
+                        continue;
+                    }
+                },
+                c => c,
+            };
+            if self.peek() == Some('-') && !matches!(self.chars.get(self.pos + 1), Some(']') | None) {
+                self.pos += 1;
+                let hi = match self.next().ok_or("unclosed character class")? {
+                    '\\' => match self.parse_escape()? {
+                        ClassItem::Range(c, _) => c,
+                        _ => return Err("invalid range in character class".to_string()),
+                    },
+                    c => c,
+                };
+                if hi < lo {
+                    return Err("range out of order in character class".to_string());
+                }
+                items.push(ClassItem::Range(lo, hi));
+            } else {
+                items.push(ClassItem::Range(lo, lo));
+            }
+        }
+        Ok(Node::Class { items, negated })
+    }
+
+    /// Parses the character after a `\`, returning a single-character range
+    /// for literal escapes.
+    fn parse_escape(&mut self) -> Result<ClassItem, String> {
+        let c = self.next().ok_or("pattern ends with '\\'")?;
+        let literal = match c {
+            'd' | 'D' | 'w' | 'W' | 's' | 'S' => return Ok(ClassItem::Shorthand(c)),
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'f' => '\u{000C}',
+            'v' => '\u{000B}',
+            '0' => '\0',
+            'u' => {
+                let hex: String = (0..4).filter_map(|_| self.next()).collect();
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 4)
+                    .and_then(char::from_u32)
+                    .ok_or("invalid \\u escape")?
+            }
+            c if c.is_ascii_alphanumeric() => return Err(format!("unsupported escape '\\{}'", c)),
+            c => c,
+        };
+        Ok(ClassItem::Range(literal, literal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Regex::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn matches_common_schema_patterns() {
+        assert!(is_match("^[a-z][a-z0-9_-]{2,15}$", "user_01"));
+        assert!(!is_match("^[a-z][a-z0-9_-]{2,15}$", "1user"));
+        assert!(is_match(r"^\d{3}-\d{4}$", "555-1234"));
+        assert!(!is_match(r"^\d{3}-\d{4}$", "555-12345"));
+        assert!(is_match("^(\\([0-9]{3}\\))?[0-9]{3}-[0-9]{4}$", "(888)555-1212"));
+        assert!(is_match("^(?:https?|ftp)://", "https://example.com"));
+        assert!(!is_match("^(?:https?|ftp)://", "mailto:x"));
+        assert!(is_match("[^\\s]+@[^\\s]+", "mail me at a@b.c"));
+    }
+
+    #[test]
+    fn matches_anywhere_unless_anchored() {
+        assert!(is_match("es", "test"));
+        assert!(!is_match("^es", "test"));
+        assert!(is_match("", "anything"));
+        assert!(is_match("a|b$", "xxb"));
+    }
+
+    #[test]
+    fn handles_backtracking_and_empty_loops() {
+        assert!(is_match("^a*ab$", "aaab"));
+        assert!(is_match("^(a|ab)(c|bcd)$", "abcd"));
+        assert!(is_match("^(a*)*$", "aaa"));
+        assert!(!is_match("^(a*)*$", "aab"));
+        assert!(is_match("^(a?){2}b$", "b"));
+        assert!(is_match("^.+?x$", "abx"));
+        assert!(is_match("^a{,2}$", "a{,2}"));
+        assert!(is_match("^(){1000}$", ""));
+        assert!(is_match("^((){1000}){1000}a$", "a"));
+    }
+
+    #[test]
+    fn long_inputs_and_nested_quantifiers_stay_linear() {
+        let long = "a".repeat(200_000);
+        assert!(is_match("^[a-z]+$", &long));
+        assert!(!is_match("^[a-z]+$", &format!("{}1", long)));
+        assert!(!is_match("(a+)+$", &format!("{}!", "a".repeat(5_000))));
+        assert!(is_match("(a|aa)*b", &format!("{}b", long)));
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        for bad in ["(ab", "ab)", "[a-", "*a", "a{3,1}", "[z-a]", "\\", "\\b", "(?=a)", "(a{1000}){1000}",
+                    "(){4000000000000}", "a{1001}", "a{0,1001}", "a{99999999999999999999}"] {
+            assert!(Regex::new(bad).is_err(), "{} should not compile", bad);
+        }
+    }
+}
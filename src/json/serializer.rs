@@ -3,78 +3,388 @@
 //! Serialization is handled by implementing the `std::fmt::Display` trait
 //! for the `Value` enum. This allows any `Value` to be converted to a string
 //! representation using methods like `to_string()` or by including it in
-//! formatting macros like `format!` and `println!`.
+//! formatting macros like `format!` and `println!`. The alternate form
+//! `{:#}` pretty-prints.
+//!
+//! `to_writer` and `to_writer_pretty` stream the same output straight into
+//! any `std::io::Write` without building an intermediate `String`. The `_with`
+//! variants take a `SerializerOptions` for finer control over the output.
 
 use super::value::Value;
 use std::fmt;
+use std::io;
 
 impl fmt::Display for Value {
     /// Formats a `Value` enum into its JSON string representation.
+    ///
+    /// The alternate flag (`{:#}`) selects pretty-printed output, using the
+    /// same layout as `to_writer_pretty`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let v = json!({"a": [1, 2]});
+    /// assert_eq!(format!("{}", v), r#"{"a":[1,2]}"#);
+    /// assert_eq!(format!("{:#}", v), "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Value::Null => write!(f, "null"),
-            Value::Bool(b) => write!(f, "{}", b),
+        let options = if f.alternate() { SerializerOptions::pretty() } else { SerializerOptions::default() };
+        Serializer::new(f, options).write_value(self)
+    }
+}
+
+/// Options controlling the JSON text produced by the `_with` functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializerOptions {
+    /// Puts each array element and object member on its own line.
+    /// Defaults to `false`.
+    pub pretty: bool,
+    /// Number of spaces per nesting level in pretty output. Defaults to 2.
+    pub indent: usize,
+    /// Escapes every non-ASCII character as `\uXXXX`, using a surrogate
+    /// pair for characters outside the Basic Multilingual Plane, so the
+    /// output is pure ASCII. Defaults to `false`.
+    pub ensure_ascii: bool,
+    /// In pretty output, keeps an array or object on a single line, as
+    /// `[1, 2]` or `{"a": 1}`, when it fits within this many columns
+    /// (counting indentation, its key, and a trailing comma). Defaults to
+    /// `None`, which puts every item on its own line. The streaming
+    /// `stream::Writer` ignores it.
+    pub line_width: Option<usize>,
+}
+
+impl Default for SerializerOptions {
+    fn default() -> Self {
+        SerializerOptions { pretty: false, indent: 2, ensure_ascii: false, line_width: None }
+    }
+}
+
+impl SerializerOptions {
+    /// Returns the options used by `to_writer_pretty`.
+    pub fn pretty() -> Self {
+        SerializerOptions { pretty: true, ..Default::default() }
+    }
+
+    /// Returns pretty options that pack arrays and objects onto one line
+    /// when they fit within `width` columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// use stdt::json::SerializerOptions;
+    /// let v = json!({"name": "grid", "rows": [[1, 0], [0, 1]]});
+    /// assert_eq!(
+    ///     json::to_string_with(&v, SerializerOptions::pretty_width(30)),
+    ///     "{\n  \"name\": \"grid\",\n  \"rows\": [[1, 0], [0, 1]]\n}"
+    /// );
+    /// assert_eq!(
+    ///     json::to_string_with(&v, SerializerOptions::pretty_width(16)),
+    ///     "{\n  \"name\": \"grid\",\n  \"rows\": [\n    [1, 0],\n    [0, 1]\n  ]\n}"
+    /// );
+    /// ```
+    pub fn pretty_width(width: usize) -> Self {
+        SerializerOptions { line_width: Some(width), ..SerializerOptions::pretty() }
+    }
+}
+
+/// Serializes `value` into a `String` using the given options.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json;
+/// use stdt::json::SerializerOptions;
+/// let options = SerializerOptions { ensure_ascii: true, ..Default::default() };
+/// assert_eq!(json::to_string_with(&json!("é😀"), options), r#""\u00e9\ud83d\ude00""#);
+/// ```
+pub fn to_string_with(value: &Value, options: SerializerOptions) -> String {
+    let mut out = String::new();
+    // Writing into a String cannot fail.
+    let _ = Serializer::new(&mut out, options).write_value(value);
+    out
+}
+
+/// Serializes `value` directly into `writer` using the given options.
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the underlying writer.
+pub fn to_writer_with<W: io::Write + ?Sized>(
+    writer: &mut W,
+    value: &Value,
+    options: SerializerOptions,
+) -> io::Result<()> {
+    let mut adapter = IoAdapter::new(writer);
+    let result = Serializer::new(&mut adapter, options).write_value(value);
+    adapter.finish(result)
+}
+
+/// Serializes `value` as compact JSON directly into `writer`.
+///
+/// No intermediate `String` is built: output is streamed to the writer as it
+/// is produced. The writer is not flushed.
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the underlying writer.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json;
+/// let mut buf = Vec::new();
+/// json::to_writer(&mut buf, &json!({"a": [1, 2]})).unwrap();
+/// assert_eq!(buf, br#"{"a":[1,2]}"#);
+/// ```
+pub fn to_writer<W: io::Write + ?Sized>(writer: &mut W, value: &Value) -> io::Result<()> {
+    to_writer_with(writer, value, SerializerOptions::default())
+}
+
+/// Serializes `value` as pretty-printed JSON (two-space indentation)
+/// directly into `writer`.
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the underlying writer.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json;
+/// let mut buf = Vec::new();
+/// json::to_writer_pretty(&mut buf, &json!({"a": [1, 2]})).unwrap();
+/// assert_eq!(String::from_utf8(buf).unwrap(), "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+/// ```
+pub fn to_writer_pretty<W: io::Write + ?Sized>(writer: &mut W, value: &Value) -> io::Result<()> {
+    to_writer_with(writer, value, SerializerOptions::pretty())
+}
+
+/// Bridges `fmt::Write` to `io::Write`, keeping the underlying I/O error
+/// (which `fmt::Error` cannot carry).
+pub(crate) struct IoAdapter<W: io::Write> {
+    pub(crate) inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoAdapter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        IoAdapter { inner, error: None }
+    }
+
+    /// Turns the outcome of a write into the I/O error that caused it, if any.
+    pub(crate) fn finish(&mut self, result: fmt::Result) -> io::Result<()> {
+        match (result, self.error.take()) {
+            (Ok(()), _) => Ok(()),
+            (Err(_), Some(e)) => Err(e),
+            (Err(_), None) => Err(io::Error::other("formatter error")),
+        }
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Writes JSON text for a `Value` into any `fmt::Write` sink.
+pub(crate) struct Serializer<W: fmt::Write> {
+    pub(crate) out: W,
+    pub(crate) options: SerializerOptions,
+    pub(crate) depth: usize,
+    /// Writing a container packed onto one line (see `line_width`).
+    inline: bool,
+    /// Column of the next character, tracked only for `line_width`.
+    column: usize,
+    /// Width of the comma that will follow the value being written.
+    trailing: usize,
+}
+
+impl<W: fmt::Write> Serializer<W> {
+    pub(crate) fn new(out: W, options: SerializerOptions) -> Self {
+        Serializer { out, options, depth: 0, inline: false, column: 0, trailing: 0 }
+    }
+
+    pub(crate) fn write_value(&mut self, value: &Value) -> fmt::Result {
+        match value {
+            Value::Null => self.out.write_str("null"),
+            Value::Bool(b) => write!(self.out, "{}", b),
             Value::Number(n) => {
                 if n.is_nan() || n.is_infinite() {
-                    write!(f, "null") // JSON standard does not support NaN or Infinity
+                    self.out.write_str("null") // JSON standard does not support NaN or Infinity
                 } else {
-                    write!(f, "{}", n)
-                }
-            },
-            Value::String(s) => {
-                write!(f, "\"")?;
-                for char in s.chars() {
-                    match char {
-                        '"' => write!(f, "\\\"")?,
-                        '\\' => write!(f, "\\\\")?,
-                        '/' => write!(f, "\\/")?,
-                        '\u{0008}' => write!(f, "\\b")?,
-                        '\u{000C}' => write!(f, "\\f")?,
-                        '\n' => write!(f, "\\n")?,
-                        '\r' => write!(f, "\\r")?,
-                        '\t' => write!(f, "\\t")?,
-                        // Handle control characters according to JSON spec
-                        c if c >= '\u{0000}' && c <= '\u{001F}' => write!(f, "\\u{:04x}", c as u32)?,
-                        c => write!(f, "{}", c)?,
-                    }
+                    write!(self.out, "{}", n)
                 }
-                write!(f, "\"")
             }
+            Value::String(s) => self.write_string(s),
             Value::Array(arr) => {
-                write!(f, "[")?;
-                let mut first = true;
-                for val in arr {
-                    if !first {
-                        write!(f, ",")?;
+                if arr.is_empty() {
+                    return self.out.write_str("[]");
+                }
+                if self.fits_on_line(value) {
+                    return self.write_inline(value);
+                }
+                self.out.write_char('[')?;
+                self.depth += 1;
+                for (i, val) in arr.iter().enumerate() {
+                    if i > 0 {
+                        self.write_comma()?;
                     }
-                    write!(f, "{}", val)?;
-                    first = false;
+                    self.write_newline()?;
+                    self.trailing = usize::from(i + 1 < arr.len());
+                    self.write_value(val)?;
                 }
-                write!(f, "]")
+                self.depth -= 1;
+                self.write_newline()?;
+                self.out.write_char(']')
             }
             Value::Object(obj) => {
-                write!(f, "{{")?;
-                let mut first = true;
-                // Note: HashMap iteration order is not guaranteed. 
-                for (key, val) in obj {
-                    if !first {
-                        write!(f, ",")?;
+                if obj.is_empty() {
+                    return self.out.write_str("{}");
+                }
+                if self.fits_on_line(value) {
+                    return self.write_inline(value);
+                }
+                self.out.write_char('{')?;
+                self.depth += 1;
+                // Keys are written in insertion order.
+                for (i, (key, val)) in obj.iter().enumerate() {
+                    if i > 0 {
+                        self.write_comma()?;
                     }
-                    // An object key is a JSON string, so we format it by wrapping in a Value::String
-                    write!(f, "{}:{}", Value::String(key.clone()), val)?;
-                    first = false;
+                    self.write_newline()?;
+                    self.write_key(key)?;
+                    self.trailing = usize::from(i + 1 < obj.len());
+                    self.write_value(val)?;
                 }
-                write!(f, "}}")
+                self.depth -= 1;
+                self.write_newline()?;
+                self.out.write_char('}')
+            }
+        }
+    }
+
+    /// Writes an object key and the `:` after it.
+    pub(crate) fn write_key(&mut self, key: &str) -> fmt::Result {
+        if self.options.line_width.is_some() {
+            let mut width = Budget(usize::MAX);
+            let _ = Serializer::new(&mut width, self.options).write_string(key);
+            self.column += usize::MAX - width.0 + 2;
+        }
+        self.write_string(key)?;
+        self.out.write_str(if self.options.pretty { ": " } else { ":" })
+    }
+
+    /// Writes the `,` between items, followed by a space on packed lines.
+    fn write_comma(&mut self) -> fmt::Result {
+        self.out.write_str(if self.inline { ", " } else { "," })
+    }
+
+    /// In pretty mode, starts a new line indented to the current depth.
+    pub(crate) fn write_newline(&mut self) -> fmt::Result {
+        if self.options.pretty && !self.inline {
+            self.out.write_char('\n')?;
+            self.column = self.depth * self.options.indent;
+            for _ in 0..self.column {
+                self.out.write_char(' ')?;
             }
         }
+        Ok(())
+    }
+
+    /// Returns `true` if `line_width` is set and the container `value`, packed
+    /// onto one line, ends within it.
+    fn fits_on_line(&self, value: &Value) -> bool {
+        let width = match self.options.line_width {
+            Some(width) if self.options.pretty && !self.inline => width,
+            _ => return false,
+        };
+        let mut room = Budget(width.saturating_sub(self.column + self.trailing));
+        let mut probe = Serializer::new(&mut room, self.options);
+        probe.inline = true;
+        probe.write_value(value).is_ok()
+    }
+
+    /// Writes the container `value` packed onto the current line.
+    fn write_inline(&mut self, value: &Value) -> fmt::Result {
+        self.inline = true;
+        let result = self.write_value(value);
+        self.inline = false;
+        result
+    }
+
+    /// Writes `s` as a quoted JSON string.
+    ///
+    /// Runs of characters that need no escaping are written as one slice, so
+    /// long plain strings cost a single `write_str` call.
+    pub(crate) fn write_string(&mut self, s: &str) -> fmt::Result {
+        self.out.write_char('"')?;
+        let bytes = s.as_bytes();
+        let mut start = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            let needs_escape = match b {
+                b'"' | b'\\' | b'/' | 0x00..=0x1f => true,
+                0x80.. => self.options.ensure_ascii,
+                _ => false,
+            };
+            if !needs_escape {
+                i += 1;
+                continue;
+            }
+            self.out.write_str(&s[start..i])?;
+            match b {
+                b'"' => self.out.write_str("\\\"")?,
+                b'\\' => self.out.write_str("\\\\")?,
+                b'/' => self.out.write_str("\\/")?,
+                0x08 => self.out.write_str("\\b")?,
+                0x0c => self.out.write_str("\\f")?,
+                b'\n' => self.out.write_str("\\n")?,
+                b'\r' => self.out.write_str("\\r")?,
+                b'\t' => self.out.write_str("\\t")?,
+                // Handle control characters according to JSON spec
+                0x00..=0x1f => write!(self.out, "\\u{:04x}", b)?,
+                _ => {
+                    // Only reached with `ensure_ascii`: `i` starts a multi-byte character.
+                    let c = s[i..].chars().next().unwrap();
+                    let mut units = [0u16; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        write!(self.out, "\\u{:04x}", unit)?;
+                    }
+                    i += c.len_utf8() - 1;
+                }
+            }
+            i += 1;
+            start = i;
+        }
+        self.out.write_str(&s[start..])?;
+        self.out.write_char('"')
     }
 }
 
 
+/// Counts down the characters written through it, failing once more than
+/// its remaining allowance has been written.
+struct Budget(usize);
+
+impl fmt::Write for Budget {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let n = s.chars().count();
+        self.0 = self.0.checked_sub(n).ok_or(fmt::Error)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use crate::json::Map;
 
     fn assert_contains_all(haystack: &str, needles: &[&str]) {
         for n in needles {
@@ -99,6 +409,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn number_formats_and_specials_to_null() {
         assert_eq!(format!("{}", Value::Number(0.0)), "0");
         assert_eq!(format!("{}", Value::Number(-42.0)), "-42");
@@ -125,6 +436,24 @@ mod tests {
         assert_eq!(out, "\"\\u0000\\u001f\"");
     }
 
+    #[test]
+    fn string_escapes_between_unescaped_runs() {
+        let s = "héllo \"wörld\"\n😀 tail";
+        assert_eq!(format!("{}", Value::from(s)), "\"héllo \\\"wörld\\\"\\n😀 tail\"");
+        assert_eq!(format!("{}", Value::from("\"\"")), r#""\"\"""#);
+
+        struct CountWrites(usize);
+        impl fmt::Write for CountWrites {
+            fn write_str(&mut self, _: &str) -> fmt::Result {
+                self.0 += 1;
+                Ok(())
+            }
+        }
+        let mut ser = Serializer::new(CountWrites(0), SerializerOptions::default());
+        ser.write_string(&"long plain text ".repeat(1000)).unwrap();
+        assert_eq!(ser.out.0, 3);
+    }
+
     #[test]
     fn array_serializes_as_expected() {
         let v = Value::Array(vec![
@@ -142,15 +471,15 @@ mod tests {
         let v = Value::Array(vec![]);
         assert_eq!(format!("{}", v), "[]");
 
-        let v = Value::Object(HashMap::new());
+        let v = Value::Object(Map::new());
         assert_eq!(format!("{}", v), "{}");
     }
 
     #[test]
     fn object_contains_all_pairs_independent_of_order() {
-        let mut m = HashMap::new();
-        m.insert("a".to_string(), Value::Number(1.0));
-        m.insert("b".to_string(), Value::String("x".into()));
+        let mut m = Map::new();
+        m.insert("a", Value::Number(1.0));
+        m.insert("b", Value::String("x".into()));
         let v = Value::Object(m);
 
         let out = format!("{}", v);
@@ -161,11 +490,96 @@ mod tests {
         assert_contains_all(&out, &["\"a\":1", "\"b\":\"x\""]);
     }
 
+    #[test]
+    fn object_round_trip_preserves_key_order() {
+        let src = r#"{"name":"app","version":2,"deps":{"z":1,"a":2}}"#;
+        let v = crate::json::from_str(src).unwrap();
+        assert_eq!(v.to_string(), src);
+    }
+
+    #[test]
+    fn to_writer_matches_display() {
+        let v = crate::json::from_str(r#"{"a":[1,"x",null],"b":{"c":true}}"#).unwrap();
+        let mut buf = Vec::new();
+        to_writer(&mut buf, &v).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), v.to_string());
+    }
+
+    #[test]
+    fn to_writer_pretty_indents_nested_values() {
+        let v = crate::json::from_str(r#"{"a":[1,{}],"b":[],"c":"x"}"#).unwrap();
+        let mut buf = Vec::new();
+        to_writer_pretty(&mut buf, &v).unwrap();
+        let expected = "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": [],\n  \"c\": \"x\"\n}";
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn to_writer_propagates_io_errors() {
+        struct Failing;
+        impl io::Write for Failing {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let err = to_writer(&mut Failing, &Value::Null).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn ensure_ascii_escapes_non_ascii_with_surrogate_pairs() {
+        let options = SerializerOptions { ensure_ascii: true, ..Default::default() };
+        let v = Value::Array(vec![Value::from("a€\u{10FFFF}"), Value::from("plain")]);
+        let out = to_string_with(&v, options);
+        assert_eq!(out, r#"["a\u20ac\udbff\udfff","plain"]"#);
+        assert!(out.is_ascii());
+        assert_eq!(crate::json::from_str("\"a\\u20ac\"").unwrap(), Value::from("a€"));
+    }
+
+    #[test]
+    fn line_width_packs_containers_that_fit() {
+        let v = crate::json::from_str(r#"[[1,2],[3,4]]"#).unwrap();
+        let out = |width| to_string_with(&v, SerializerOptions::pretty_width(width));
+        assert_eq!(out(16), "[[1, 2], [3, 4]]");
+        assert_eq!(out(9), "[\n  [1, 2],\n  [3, 4]\n]");
+        // The trailing comma counts, so only the last row fits in 8 columns.
+        assert_eq!(out(8), "[\n  [\n    1,\n    2\n  ],\n  [3, 4]\n]");
+
+        let v = crate::json::from_str(r#"{"k\u00e9y":{"a":"\u00e9"},"z":[]}"#).unwrap();
+        assert_eq!(
+            to_string_with(&v, SerializerOptions::pretty_width(20)),
+            "{\n  \"kéy\": {\"a\": \"é\"},\n  \"z\": []\n}"
+        );
+        let ascii = SerializerOptions { ensure_ascii: true, ..SerializerOptions::pretty_width(20) };
+        assert_eq!(
+            to_string_with(&v, ascii),
+            "{\n  \"k\\u00e9y\": {\n    \"a\": \"\\u00e9\"\n  },\n  \"z\": []\n}"
+        );
+    }
+
+    #[test]
+    fn alternate_display_pretty_prints() {
+        let v = crate::json::from_str(r#"{"a":[1,{}],"b":"x"}"#).unwrap();
+        assert_eq!(format!("{:#}", v), to_string_with(&v, SerializerOptions::pretty()));
+        assert_eq!(format!("{:#}", Value::Array(vec![])), "[]");
+        assert_eq!(format!("{:#}", Value::from(1)), "1");
+    }
+
+    #[test]
+    fn custom_indent_width() {
+        let v = crate::json::from_str(r#"{"a":[1]}"#).unwrap();
+        let options = SerializerOptions { indent: 4, ..SerializerOptions::pretty() };
+        assert_eq!(to_string_with(&v, options), "{\n    \"a\": [\n        1\n    ]\n}");
+    }
+
     #[test]
     fn nested_structures_render_correctly() {
-        let mut inner = HashMap::new();
+        let mut inner = Map::new();
         inner.insert(
-            "k".into(),
+            "k",
             Value::Array(vec![Value::String("€/\"".into()), Value::Number(2.0)]),
         );
 
@@ -180,8 +594,8 @@ mod tests {
 
     #[test]
     fn object_keys_are_rendered_as_json_strings() {
-        let mut m = HashMap::new();
-        m.insert("q\"w\\e".to_string(), Value::Bool(true));
+        let mut m = Map::new();
+        m.insert("q\"w\\e", Value::Bool(true));
         let v = Value::Object(m);
         let out = format!("{}", v);
 
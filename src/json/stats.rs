@@ -0,0 +1,157 @@
+//! Size and shape measurements of `json::Value` trees.
+//!
+//! `Value::stats` counts the nodes of each kind along with the nesting depth
+//! and string payload, and `Value::deep_size_estimate` approximates the
+//! memory a tree occupies. Services can use them to enforce payload budgets
+//! after parsing or to find out which documents dominate memory use.
+
+use super::value::Value;
+use std::mem::size_of;
+
+/// Node counts and other measurements of a `Value` tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    /// Number of `null` values.
+    pub nulls: usize,
+    /// Number of booleans.
+    pub bools: usize,
+    /// Number of numbers.
+    pub numbers: usize,
+    /// Number of strings, not counting object keys.
+    pub strings: usize,
+    /// Number of arrays.
+    pub arrays: usize,
+    /// Number of objects.
+    pub objects: usize,
+    /// Nesting depth: 0 for a scalar, 1 for a container of scalars, and so on.
+    /// Empty containers count as one level.
+    pub max_depth: usize,
+    /// Total UTF-8 bytes of all strings and object keys.
+    pub string_bytes: usize,
+}
+
+impl Stats {
+    /// Returns the total number of values in the tree.
+    pub fn nodes(&self) -> usize {
+        self.nulls + self.bools + self.numbers + self.strings + self.arrays + self.objects
+    }
+}
+
+impl Value {
+    /// Counts the nodes of each kind in the tree and measures its depth and
+    /// string payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let stats = json!({"id": 7, "tags": ["a", "bc"], "owner": null}).stats();
+    /// assert_eq!(stats.nodes(), 6);
+    /// assert_eq!((stats.objects, stats.arrays, stats.strings), (1, 1, 2));
+    /// assert_eq!(stats.max_depth, 2);
+    /// // Keys "id", "tags", "owner" plus values "a" and "bc".
+    /// assert_eq!(stats.string_bytes, 14);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        collect(self, 0, &mut stats);
+        stats
+    }
+
+    /// Approximates the number of bytes the tree occupies in memory,
+    /// including this `Value` itself and every heap allocation it owns.
+    ///
+    /// The estimate accounts for spare capacity in strings, vectors, and
+    /// object index tables, but not for allocator overhead, so treat it as a
+    /// lower bound useful for budgeting and comparison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let small = json!([1]).deep_size_estimate();
+    /// let large = json!([1, "a long string that needs its own allocation"]).deep_size_estimate();
+    /// assert!(large > small);
+    /// ```
+    pub fn deep_size_estimate(&self) -> usize {
+        size_of::<Value>() + heap_size(self)
+    }
+}
+
+fn collect(value: &Value, depth: usize, stats: &mut Stats) {
+    stats.max_depth = stats.max_depth.max(depth);
+    match value {
+        Value::Null => stats.nulls += 1,
+        Value::Bool(_) => stats.bools += 1,
+        Value::Number(_) => stats.numbers += 1,
+        Value::String(s) => {
+            stats.strings += 1;
+            stats.string_bytes += s.len();
+        }
+        Value::Array(arr) => {
+            stats.arrays += 1;
+            stats.max_depth = stats.max_depth.max(depth + 1);
+            for item in arr {
+                collect(item, depth + 1, stats);
+            }
+        }
+        Value::Object(obj) => {
+            stats.objects += 1;
+            stats.max_depth = stats.max_depth.max(depth + 1);
+            for (key, item) in obj {
+                stats.string_bytes += key.len();
+                collect(item, depth + 1, stats);
+            }
+        }
+    }
+}
+
+/// Bytes owned on the heap by `value`, excluding the `Value` itself.
+fn heap_size(value: &Value) -> usize {
+    match value {
+        Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+        Value::String(s) => s.capacity(),
+        Value::Array(arr) => arr.capacity() * size_of::<Value>() + arr.iter().map(heap_size).sum::<usize>(),
+        Value::Object(obj) => obj.heap_size() + obj.values().map(heap_size).sum::<usize>(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    #[test]
+    fn counts_every_kind() {
+        let v = from_str(r#"[null, true, 1, "xy", [], {"k": {"deep": [false]}}]"#).unwrap();
+        let stats = v.stats();
+        assert_eq!(
+            stats,
+            Stats {
+                nulls: 1,
+                bools: 2,
+                numbers: 1,
+                strings: 1,
+                arrays: 3,
+                objects: 2,
+                max_depth: 4,
+                string_bytes: 7,
+            }
+        );
+        assert_eq!(stats.nodes(), 10);
+        assert_eq!(Value::Null.stats().max_depth, 0);
+        assert_eq!(from_str("[]").unwrap().stats().max_depth, 1);
+    }
+
+    #[test]
+    fn size_estimate_grows_with_content() {
+        let scalar = Value::from(1).deep_size_estimate();
+        assert_eq!(scalar, size_of::<Value>());
+        let s = Value::from("hello").deep_size_estimate();
+        assert!(s >= size_of::<Value>() + 5);
+        let arr = Value::Array(vec![Value::from("hello"); 4]).deep_size_estimate();
+        assert!(arr >= size_of::<Value>() + 4 * s);
+        let obj = from_str(r#"{"key":"hello"}"#).unwrap().deep_size_estimate();
+        assert!(obj > s + 3);
+    }
+}
@@ -0,0 +1,275 @@
+//! Incremental JSON output for documents too large to hold in memory.
+//!
+//! A `Writer` emits arrays and objects piece by piece: open a container,
+//! push elements or members one at a time, then close it. Each element is
+//! written to the underlying `io::Write` as soon as it is pushed, so exporting
+//! millions of records never needs them all in a single `Value`.
+//!
+//! The output is byte-for-byte what `to_writer_with` would produce for the
+//! equivalent `Value`, including pretty printing, except that `line_width` is
+//! ignored: whether a container fits on one line is only known once it is
+//! closed, after its first items have been written.
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json;
+//! use stdt::json::stream::Writer;
+//!
+//! let mut w = Writer::new(Vec::new());
+//! w.begin_object().unwrap();
+//! w.key("rows").unwrap();
+//! w.begin_array().unwrap();
+//! for id in 0..3 {
+//!     w.value(&json!({"id": id})).unwrap();
+//! }
+//! w.end().unwrap();
+//! w.field("count", &json!(3)).unwrap();
+//! w.end().unwrap();
+//! let out = w.finish().unwrap();
+//! assert_eq!(out, br#"{"rows":[{"id":0},{"id":1},{"id":2}],"count":3}"#);
+//! ```
+
+use super::serializer::{IoAdapter, Serializer, SerializerOptions};
+use super::value::Value;
+use std::fmt::Write as _;
+use std::io;
+
+/// An open container and how many items have been written into it.
+#[derive(Debug, Clone, Copy)]
+enum Frame {
+    Array { len: usize },
+    /// `key_pending` is set between `key` and the member's value.
+    Object { len: usize, key_pending: bool },
+}
+
+/// Writes a single JSON document incrementally into an `io::Write`.
+///
+/// Calls that would produce invalid JSON, such as a value inside an object
+/// without a preceding `key`, fail with an `io::ErrorKind::InvalidInput`
+/// error and write nothing.
+pub struct Writer<W: io::Write> {
+    ser: Serializer<IoAdapter<W>>,
+    stack: Vec<Frame>,
+    /// Set once the top-level value has been started.
+    started: bool,
+}
+
+impl<W: io::Write> Writer<W> {
+    /// Creates a writer producing compact JSON.
+    pub fn new(writer: W) -> Self {
+        Writer::with_options(writer, SerializerOptions::default())
+    }
+
+    /// Creates a writer using the given serializer options, except
+    /// `line_width`, which is ignored.
+    pub fn with_options(writer: W, options: SerializerOptions) -> Self {
+        let options = SerializerOptions { line_width: None, ..options };
+        Writer { ser: Serializer::new(IoAdapter::new(writer), options), stack: Vec::new(), started: false }
+    }
+
+    /// Opens an array as the next value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value is not allowed here, or any I/O error
+    /// raised by the underlying writer.
+    pub fn begin_array(&mut self) -> io::Result<()> {
+        self.before_value()?;
+        let result = self.ser.out.write_char('[');
+        self.ser.out.finish(result)?;
+        self.ser.depth += 1;
+        self.stack.push(Frame::Array { len: 0 });
+        Ok(())
+    }
+
+    /// Opens an object as the next value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value is not allowed here, or any I/O error
+    /// raised by the underlying writer.
+    pub fn begin_object(&mut self) -> io::Result<()> {
+        self.before_value()?;
+        let result = self.ser.out.write_char('{');
+        self.ser.out.finish(result)?;
+        self.ser.depth += 1;
+        self.stack.push(Frame::Object { len: 0, key_pending: false });
+        Ok(())
+    }
+
+    /// Writes the key of the next member of the innermost object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the innermost container is not an object or is
+    /// already waiting for a value, or any I/O error raised by the writer.
+    pub fn key(&mut self, key: &str) -> io::Result<()> {
+        let len = match self.stack.last() {
+            Some(Frame::Object { len, key_pending: false }) => *len,
+            Some(Frame::Object { .. }) => return Err(misuse("key written twice without a value")),
+            _ => return Err(misuse("key written outside an object")),
+        };
+        let result = self.separator(len).and_then(|()| {
+            self.ser.write_key(key)
+        });
+        self.ser.out.finish(result)?;
+        *self.stack.last_mut().unwrap() = Frame::Object { len: len + 1, key_pending: true };
+        Ok(())
+    }
+
+    /// Writes a complete value as the next array element, the value of the
+    /// pending object member, or the whole document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value is not allowed here, or any I/O error
+    /// raised by the underlying writer.
+    pub fn value(&mut self, value: &Value) -> io::Result<()> {
+        self.before_value()?;
+        let result = self.ser.write_value(value);
+        self.ser.out.finish(result)
+    }
+
+    /// Writes a complete member into the innermost object; shorthand for
+    /// `key` followed by `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `key` and `value`.
+    pub fn field(&mut self, key: &str, value: &Value) -> io::Result<()> {
+        self.key(key)?;
+        self.value(value)
+    }
+
+    /// Closes the innermost open array or object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing is open or an object member is missing
+    /// its value, or any I/O error raised by the underlying writer.
+    pub fn end(&mut self) -> io::Result<()> {
+        let (len, close) = match self.stack.last() {
+            None => return Err(misuse("no open array or object to end")),
+            Some(Frame::Object { key_pending: true, .. }) => return Err(misuse("object member has no value")),
+            Some(Frame::Array { len }) => (*len, ']'),
+            Some(Frame::Object { len, .. }) => (*len, '}'),
+        };
+        self.ser.depth -= 1;
+        let result = if len > 0 { self.ser.write_newline() } else { Ok(()) };
+        let result = result.and_then(|()| self.ser.out.write_char(close));
+        if let Err(e) = self.ser.out.finish(result) {
+            self.ser.depth += 1;
+            return Err(e);
+        }
+        self.stack.pop();
+        Ok(())
+    }
+
+    /// Returns `true` once a complete top-level value has been written.
+    pub fn is_complete(&self) -> bool {
+        self.started && self.stack.is_empty()
+    }
+
+    /// Flushes and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is incomplete, or any I/O error
+    /// raised while flushing.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.is_complete() {
+            return Err(misuse("document is incomplete"));
+        }
+        self.ser.out.inner.flush()?;
+        Ok(self.ser.out.inner)
+    }
+
+    /// Checks that a value may be written next and writes the separator
+    /// before it, then records the value in its container.
+    fn before_value(&mut self) -> io::Result<()> {
+        match self.stack.last().copied() {
+            None if self.started => Err(misuse("document already has a top-level value")),
+            None => {
+                self.started = true;
+                Ok(())
+            }
+            Some(Frame::Object { key_pending: false, .. }) => Err(misuse("object member has no key")),
+            Some(Frame::Object { len, .. }) => {
+                *self.stack.last_mut().unwrap() = Frame::Object { len, key_pending: false };
+                Ok(())
+            }
+            Some(Frame::Array { len }) => {
+                let result = self.separator(len);
+                self.ser.out.finish(result)?;
+                *self.stack.last_mut().unwrap() = Frame::Array { len: len + 1 };
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the comma and indentation before the item at position `len`.
+    fn separator(&mut self, len: usize) -> std::fmt::Result {
+        if len > 0 {
+            self.ser.out.write_char(',')?;
+        }
+        self.ser.write_newline()
+    }
+}
+
+fn misuse(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{from_str, to_string_with};
+
+    fn stream(options: SerializerOptions) -> String {
+        let mut w = Writer::with_options(Vec::new(), options);
+        w.begin_array().unwrap();
+        w.value(&Value::from(1)).unwrap();
+        w.begin_object().unwrap();
+        w.field("a", &Value::Array(vec![])).unwrap();
+        w.key("b").unwrap();
+        w.begin_object().unwrap();
+        w.end().unwrap();
+        w.end().unwrap();
+        w.begin_array().unwrap();
+        w.end().unwrap();
+        w.end().unwrap();
+        String::from_utf8(w.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn matches_serializer_output() {
+        let v = from_str(r#"[1,{"a":[],"b":{}},[]]"#).unwrap();
+        for options in [SerializerOptions::default(), SerializerOptions::pretty()] {
+            assert_eq!(stream(options), to_string_with(&v, options));
+        }
+        // Containers are never packed, so `line_width` has no effect.
+        assert_eq!(stream(SerializerOptions::pretty_width(80)), to_string_with(&v, SerializerOptions::pretty()));
+    }
+
+    #[test]
+    fn rejects_misuse() {
+        let mut w = Writer::new(Vec::new());
+        assert!(w.key("a").is_err());
+        assert!(w.end().is_err());
+        w.begin_object().unwrap();
+        assert_eq!(w.value(&Value::Null).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        w.key("a").unwrap();
+        assert!(w.key("b").is_err());
+        assert!(w.end().is_err());
+        w.value(&Value::Null).unwrap();
+        w.end().unwrap();
+        assert!(w.value(&Value::Null).is_err());
+        assert_eq!(w.finish().unwrap(), br#"{"a":null}"#);
+
+        let mut w = Writer::new(Vec::new());
+        w.begin_array().unwrap();
+        assert!(!w.is_complete());
+        assert!(w.finish().is_err());
+    }
+}
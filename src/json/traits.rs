@@ -0,0 +1,259 @@
+//! Defines the `ToJson` and `FromJson` traits for mapping Rust types to and
+//! from `json::Value`.
+//!
+//! Implementations are written by hand. The std scalar and container types
+//! are covered here, so a struct impl only has to map its own fields; `field`
+//! reads one member and tags any error with the member's name.
+//!
+//! # Examples
+//!
+//! ```
+//! use stdt::json::{self, ConversionError, FromJson, ToJson, Value};
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct User {
+//!     name: String,
+//!     tags: Vec<String>,
+//! }
+//!
+//! impl ToJson for User {
+//!     fn to_json(&self) -> Value {
+//!         json::builder::object()
+//!             .field("name", self.name.to_json())
+//!             .field("tags", self.tags.to_json())
+//!             .build()
+//!     }
+//! }
+//!
+//! impl FromJson for User {
+//!     fn from_json(value: &Value) -> Result<Self, ConversionError> {
+//!         Ok(User { name: json::field(value, "name")?, tags: json::field(value, "tags")? })
+//!     }
+//! }
+//!
+//! let user = User::from_json_str(r#"{"name":"ada","tags":["admin"]}"#).unwrap();
+//! assert_eq!(user.tags, vec!["admin"]);
+//! assert_eq!(user.to_json_string(), r#"{"name":"ada","tags":["admin"]}"#);
+//! ```
+
+use super::convert::ConversionError;
+use super::map::Map;
+use super::parser::from_str;
+use super::value::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+/// A type that can be represented as a `json::Value`.
+pub trait ToJson {
+    /// Converts `self` into a `Value`.
+    fn to_json(&self) -> Value;
+
+    /// Converts `self` into compact JSON text.
+    fn to_json_string(&self) -> String {
+        self.to_json().to_string()
+    }
+}
+
+/// A type that can be built from a `json::Value`.
+pub trait FromJson: Sized {
+    /// Builds `Self` from `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConversionError` if `value` does not have the expected shape.
+    fn from_json(value: &Value) -> Result<Self, ConversionError>;
+
+    /// Parses `s` as JSON and builds `Self` from the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConversionError::Parse` if `s` is not valid JSON, or any other
+    /// `ConversionError` from `from_json`.
+    fn from_json_str(s: &str) -> Result<Self, ConversionError> {
+        Self::from_json(&from_str(s)?)
+    }
+}
+
+/// Reads the member `key` of an object as `T`.
+///
+/// A missing member is read as `null`, so `Option<T>` fields may be absent.
+/// Errors are reported relative to the object, e.g. at `servers[0].port`.
+///
+/// # Errors
+///
+/// Returns a `ConversionError` if `object` is not an object or the member
+/// cannot be converted.
+pub fn field<T: FromJson>(object: &Value, key: &str) -> Result<T, ConversionError> {
+    let map = object.as_object().ok_or_else(|| ConversionError::mismatch("object", object))?;
+    T::from_json(map.get(key).unwrap_or(&Value::Null)).map_err(|e| e.at_key(key))
+}
+
+impl ToJson for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl FromJson for Value {
+    fn from_json(value: &Value) -> Result<Self, ConversionError> {
+        Ok(value.clone())
+    }
+}
+
+// Macro to implement both traits for types with `From`/`TryFrom` conversions.
+macro_rules! impl_json_traits_for_scalar {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    Value::from(self.clone())
+                }
+            }
+
+            impl FromJson for $t {
+                fn from_json(value: &Value) -> Result<Self, ConversionError> {
+                    <$t>::try_from(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_json_traits_for_scalar!(bool, String, f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl ToJson for str {
+    fn to_json(&self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Value {
+        (**self).to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &Value) -> Result<Self, ConversionError> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    /// `None` becomes `null`.
+    fn to_json(&self) -> Value {
+        match self {
+            Some(v) => v.to_json(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    /// `null` becomes `None`.
+    fn from_json(value: &Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::Null => Ok(None),
+            other => T::from_json(other).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, ConversionError> {
+        let arr = value.as_array().ok_or_else(|| ConversionError::mismatch("array", value))?;
+        arr.iter()
+            .enumerate()
+            .map(|(i, v)| T::from_json(v).map_err(|e| e.at_index(i)))
+            .collect()
+    }
+}
+
+impl<T: ToJson> ToJson for HashMap<String, T> {
+    /// Keys are emitted in `HashMap` iteration order.
+    fn to_json(&self) -> Value {
+        Value::Object(self.iter().map(|(k, v)| (k.as_str(), v.to_json())).collect::<Map>())
+    }
+}
+
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &Value) -> Result<Self, ConversionError> {
+        collect_object(value)
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    /// Keys are emitted in sorted order.
+    fn to_json(&self) -> Value {
+        Value::Object(self.iter().map(|(k, v)| (k.as_str(), v.to_json())).collect::<Map>())
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: &Value) -> Result<Self, ConversionError> {
+        collect_object(value)
+    }
+}
+
+/// Converts every member of an object into a keyed collection.
+fn collect_object<T, C>(value: &Value) -> Result<C, ConversionError>
+where
+    T: FromJson,
+    C: FromIterator<(String, T)>,
+{
+    let map = value.as_object().ok_or_else(|| ConversionError::mismatch("object", value))?;
+    map.iter()
+        .map(|(k, v)| T::from_json(v).map(|t| (k.to_string(), t)).map_err(|e| e.at_key(k)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_std_containers() {
+        let mut map = BTreeMap::new();
+        map.insert("b".to_string(), vec![Some(1), None]);
+        map.insert("a".to_string(), vec![]);
+        let text = map.to_json_string();
+        assert_eq!(text, r#"{"a":[],"b":[1,null]}"#);
+        assert_eq!(BTreeMap::<String, Vec<Option<i32>>>::from_json_str(&text), Ok(map));
+        assert_eq!("hi".to_json(), Value::from("hi"));
+    }
+
+    #[test]
+    fn field_reports_member_location() {
+        let v = from_str(r#"{"port":"80","extra":null}"#).unwrap();
+        let err = field::<u16>(&v, "port").unwrap_err();
+        assert_eq!(err.path(), "port");
+        assert_eq!(field::<Option<u16>>(&v, "missing"), Ok(None));
+        assert_eq!(field::<Option<u16>>(&v, "extra"), Ok(None));
+        assert!(field::<u16>(&Value::from(1), "port").is_err());
+    }
+
+    #[test]
+    fn from_json_str_surfaces_parse_errors() {
+        assert!(matches!(Vec::<i32>::from_json_str("[1,"), Err(ConversionError::Parse(_))));
+        let err = HashMap::<String, i32>::from_json_str("[]").unwrap_err();
+        assert_eq!(err.to_string(), "Expected object, found array");
+    }
+}
@@ -4,7 +4,8 @@
 //! This file also provides a comprehensive set of `From` trait implementations
 //! to allow easy conversion from Rust primitive types into a `json::Value`.
 
-use std::collections::HashMap;
+use super::map::Map;
+use std::collections::{BTreeMap, HashMap};
 use std::iter::FromIterator;
 
 /// Represents any valid JSON value.
@@ -21,7 +22,183 @@ pub enum Value {
     /// Represents a JSON array (a sequence of values).
     Array(Vec<Value>),
     /// Represents a JSON object (a collection of key-value pairs).
-    Object(HashMap<String, Value>),
+    /// Keys keep the order in which they were inserted.
+    Object(Map),
+}
+
+impl Value {
+    /// Returns the value stored under `key` if `self` is an object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let v = json!({"port": 8080});
+    /// assert_eq!(v.get("port").and_then(|p| p.as_f64()), Some(8080.0));
+    /// assert!(v.get("host").is_none());
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object()?.get(key)
+    }
+
+    /// Mutable variant of [`Value::get`].
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_object_mut()?.get_mut(key)
+    }
+
+    /// Returns the element at `index` if `self` is an array.
+    pub fn get_index(&self, index: usize) -> Option<&Value> {
+        self.as_array()?.get(index)
+    }
+
+    /// Mutable variant of [`Value::get_index`].
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+        self.as_array_mut()?.get_mut(index)
+    }
+
+    /// Returns the JSON type name of the value (`"null"`, `"boolean"`,
+    /// `"number"`, `"string"`, `"array"`, or `"object"`).
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    /// Takes the value out, leaving `Value::Null` in its place.
+    ///
+    /// Useful for moving a subtree out of a document without cloning it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let mut doc = json!({"users": [1, 2]});
+    /// let users = doc["users"].take();
+    /// assert_eq!(users, json!([1, 2]));
+    /// assert_eq!(doc.to_string(), r#"{"users":null}"#);
+    /// ```
+    pub fn take(&mut self) -> Value {
+        std::mem::replace(self, Value::Null)
+    }
+
+    /// Returns `true` if the value is `null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Returns `true` if the value is a boolean.
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Value::Bool(_))
+    }
+
+    /// Returns `true` if the value is a number.
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Number(_))
+    }
+
+    /// Returns `true` if the value is a string.
+    pub fn is_string(&self) -> bool {
+        matches!(self, Value::String(_))
+    }
+
+    /// Returns `true` if the value is an array.
+    pub fn is_array(&self) -> bool {
+        matches!(self, Value::Array(_))
+    }
+
+    /// Returns `true` if the value is an object.
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_))
+    }
+
+    /// Returns the boolean if the value is a `Bool`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the number if the value is a `Number`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as an `i64` if it is integral and in range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json::Value;
+    /// assert_eq!(Value::Number(-3.0).as_i64(), Some(-3));
+    /// assert_eq!(Value::Number(1.5).as_i64(), None);
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) if n.fract() == 0.0 && *n >= i64::MIN as f64 && *n < i64::MAX as f64 => {
+                Some(*n as i64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number as a `u64` if it is integral, non-negative, and in range.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 && *n < u64::MAX as f64 => {
+                Some(*n as u64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the string slice if the value is a `String`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements if the value is an `Array`.
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Mutable variant of [`Value::as_array`].
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Returns the map if the value is an `Object`.
+    pub fn as_object(&self) -> Option<&Map> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Mutable variant of [`Value::as_object`].
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
 }
 
 // Macro to implement `From` for all numeric types.
@@ -69,9 +246,47 @@ impl<T: Into<Value>> From<Vec<T>> for Value {
     }
 }
 
+impl<T: Into<Value>, const N: usize> From<[T; N]> for Value {
+    /// Converts an array `[T; N]` into a `Value::Array`.
+    fn from(arr: [T; N]) -> Self {
+        Value::Array(arr.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<Value>> From<&[T]> for Value {
+    /// Converts a slice into a `Value::Array`, cloning each element.
+    fn from(arr: &[T]) -> Self {
+        Value::Array(arr.iter().cloned().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    /// Converts `Some(v)` into `v`'s `Value` and `None` into `Value::Null`.
+    fn from(opt: Option<T>) -> Self {
+        opt.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<Vec<(K, V)>> for Value {
+    /// Converts a list of key-value pairs into a `Value::Object`, keeping
+    /// their order. A repeated key keeps its first position and last value.
+    fn from(pairs: Vec<(K, V)>) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+impl From<Map> for Value {
+    /// Wraps a `Map` into a `Value::Object`.
+    fn from(map: Map) -> Self {
+        Value::Object(map)
+    }
+}
+
 impl<K: Into<String>, V: Into<Value>> From<HashMap<K, V>> for Value {
     /// Converts a `HashMap<K, V>` where `K` can be converted into a `String`
     /// and `V` into a `Value` into a `Value::Object`.
+    ///
+    /// The resulting key order follows the `HashMap` iteration order.
     fn from(map: HashMap<K, V>) -> Self {
         Value::Object(
             map.into_iter()
@@ -81,6 +296,14 @@ impl<K: Into<String>, V: Into<Value>> From<HashMap<K, V>> for Value {
     }
 }
 
+impl<K: Into<String>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    /// Converts a `BTreeMap<K, V>` into a `Value::Object` with keys in
+    /// sorted order.
+    fn from(map: BTreeMap<K, V>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
 impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
     /// Creates a `Value::Object` from an iterator of key-value pairs.
     fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
@@ -92,3 +315,56 @@ impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    #[test]
+    fn type_predicates() {
+        assert!(Value::Null.is_null());
+        assert!(Value::Bool(true).is_bool());
+        assert!(Value::Number(1.0).is_number());
+        assert!(Value::from("x").is_string());
+        assert!(Value::Array(vec![]).is_array());
+        assert!(Value::Object(Map::new()).is_object());
+        assert!(!Value::Null.is_object());
+    }
+
+    #[test]
+    fn scalar_accessors() {
+        assert_eq!(Value::Bool(false).as_bool(), Some(false));
+        assert_eq!(Value::Number(2.5).as_f64(), Some(2.5));
+        assert_eq!(Value::Number(42.0).as_u64(), Some(42));
+        assert_eq!(Value::Number(-1.0).as_u64(), None);
+        assert_eq!(Value::Number(1e300).as_i64(), None);
+        assert_eq!(Value::from("hi").as_str(), Some("hi"));
+        assert_eq!(Value::Null.as_str(), None);
+        assert_eq!(Value::from("1").as_f64(), None);
+    }
+
+    #[test]
+    fn converts_more_std_types() {
+        assert_eq!(Value::from(Some(1)), Value::from(1));
+        assert_eq!(Value::from(None::<&str>), Value::Null);
+        assert_eq!(Value::from([1, 2]), Value::from(vec![1, 2]));
+        assert_eq!(Value::from(&["a", "b"][..]), from_str(r#"["a","b"]"#).unwrap());
+        let sorted: BTreeMap<&str, Option<bool>> = [("b", None), ("a", Some(true))].into();
+        assert_eq!(Value::from(sorted).to_string(), r#"{"a":true,"b":null}"#);
+        let pairs = vec![("z", 1), ("y", 2), ("z", 3)];
+        assert_eq!(Value::from(pairs).to_string(), r#"{"z":3,"y":2}"#);
+    }
+
+    #[test]
+    fn nested_access_without_match_blocks() {
+        let mut v = from_str(r#"{"servers":[{"host":"a"},{"host":"b"}]}"#).unwrap();
+        let host = v.get("servers").and_then(|s| s.get_index(1)).and_then(|s| s.get("host"));
+        assert_eq!(host.and_then(Value::as_str), Some("b"));
+        assert!(v.get_index(0).is_none());
+
+        if let Some(arr) = v.get_mut("servers").and_then(Value::as_array_mut) {
+            arr.clear();
+        }
+        assert_eq!(v.to_string(), r#"{"servers":[]}"#);
+    }
+}
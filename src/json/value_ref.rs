@@ -0,0 +1,232 @@
+//! Defines `ValueRef`, a JSON value that borrows its strings from the input.
+//!
+//! Parsing into a `Value` allocates a `String` for every string literal and
+//! object key. `from_str_ref` instead returns slices of the input text for
+//! every string that contains no escape sequences, and only allocates for the
+//! ones that do. This makes read-only passes over large documents cheaper;
+//! call `ValueRef::into_owned` to detach a result from the input.
+
+use super::map::Map;
+use super::parser::{parse_document, Builder, ParseError, ParserOptions};
+use super::value::Value;
+use std::borrow::Cow;
+
+/// A JSON value whose strings may borrow from the text it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    /// Represents a JSON `null`.
+    Null,
+    /// Represents a JSON boolean (`true` or `false`).
+    Bool(bool),
+    /// Represents a JSON number. All numbers are stored as `f64`.
+    Number(f64),
+    /// Represents a JSON string, borrowed unless it contained escapes.
+    String(Cow<'a, str>),
+    /// Represents a JSON array (a sequence of values).
+    Array(Vec<ValueRef<'a>>),
+    /// Represents a JSON object as its members in document order.
+    /// Duplicate keys are kept; lookups see the last one, as `Value` does.
+    Object(Vec<(Cow<'a, str>, ValueRef<'a>)>),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Returns the value stored under `key` if `self` is an object.
+    pub fn get(&self, key: &str) -> Option<&ValueRef<'a>> {
+        match self {
+            ValueRef::Object(members) => members.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Returns the element at `index` if `self` is an array.
+    pub fn get_index(&self, index: usize) -> Option<&ValueRef<'a>> {
+        self.as_array()?.get(index)
+    }
+
+    /// Returns `true` if the value is `Null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, ValueRef::Null)
+    }
+
+    /// If the value is a `Bool`, returns the associated `bool`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ValueRef::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// If the value is a `Number`, returns the associated `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ValueRef::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// If the value is a `String`, returns it as a `&str`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ValueRef::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the value is a string slice of the parsed input
+    /// rather than an owned copy.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, ValueRef::String(Cow::Borrowed(_)))
+    }
+
+    /// If the value is an `Array`, returns a reference to its elements.
+    pub fn as_array(&self) -> Option<&Vec<ValueRef<'a>>> {
+        match self {
+            ValueRef::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// If the value is an `Object`, returns a reference to its members.
+    pub fn as_object(&self) -> Option<&Vec<(Cow<'a, str>, ValueRef<'a>)>> {
+        match self {
+            ValueRef::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Converts into an owned `Value`, copying any borrowed strings.
+    pub fn into_owned(self) -> Value {
+        match self {
+            ValueRef::Null => Value::Null,
+            ValueRef::Bool(b) => Value::Bool(b),
+            ValueRef::Number(n) => Value::Number(n),
+            ValueRef::String(s) => Value::String(s.into_owned()),
+            ValueRef::Array(arr) => Value::Array(arr.into_iter().map(ValueRef::into_owned).collect()),
+            ValueRef::Object(members) => {
+                let mut map = Map::with_capacity(members.len());
+                for (key, value) in members {
+                    map.insert(key, value.into_owned());
+                }
+                Value::Object(map)
+            }
+        }
+    }
+}
+
+impl<'a> From<ValueRef<'a>> for Value {
+    fn from(value: ValueRef<'a>) -> Self {
+        value.into_owned()
+    }
+}
+
+/// Builds `ValueRef`s, passing borrowed strings straight through.
+struct RefBuilder;
+
+impl<'a> Builder<'a> for RefBuilder {
+    type Value = ValueRef<'a>;
+    type Array = Vec<ValueRef<'a>>;
+    type Object = Vec<(Cow<'a, str>, ValueRef<'a>)>;
+
+    fn null(&mut self) -> ValueRef<'a> {
+        ValueRef::Null
+    }
+
+    fn bool(&mut self, b: bool) -> ValueRef<'a> {
+        ValueRef::Bool(b)
+    }
+
+    fn number(&mut self, n: f64) -> ValueRef<'a> {
+        ValueRef::Number(n)
+    }
+
+    fn string(&mut self, s: Cow<'a, str>) -> ValueRef<'a> {
+        ValueRef::String(s)
+    }
+
+    fn array_start(&mut self) -> Self::Array {
+        Vec::new()
+    }
+
+    fn array_push(&mut self, array: &mut Self::Array, value: ValueRef<'a>) {
+        array.push(value);
+    }
+
+    fn array_end(&mut self, array: Self::Array) -> ValueRef<'a> {
+        ValueRef::Array(array)
+    }
+
+    fn object_start(&mut self) -> Self::Object {
+        Vec::new()
+    }
+
+    fn object_insert(&mut self, object: &mut Self::Object, key: Cow<'a, str>, value: ValueRef<'a>) {
+        object.push((key, value));
+    }
+
+    fn object_end(&mut self, object: Self::Object) -> ValueRef<'a> {
+        ValueRef::Object(object)
+    }
+}
+
+/// Parses a JSON string slice into a `ValueRef` that borrows from `s`,
+/// using `ParserOptions::default()`.
+///
+/// # Examples
+///
+/// ```
+/// use stdt::json;
+/// let text = r#"{"name": "Ada", "motto": "line\nbreak"}"#;
+/// let doc = json::from_str_ref(text).unwrap();
+/// assert_eq!(doc.get("name").and_then(|v| v.as_str()), Some("Ada"));
+/// assert!(doc.get("name").unwrap().is_borrowed());
+/// // Escapes force a copy.
+/// assert!(!doc.get("motto").unwrap().is_borrowed());
+/// assert_eq!(doc.into_owned(), json!({"name": "Ada", "motto": "line\nbreak"}));
+/// ```
+///
+/// # Errors
+///
+/// Returns a `ParseError` if the input string is not valid JSON.
+pub fn from_str_ref(s: &str) -> Result<ValueRef<'_>, ParseError> {
+    from_str_ref_with(s, ParserOptions::default())
+}
+
+/// Parses a JSON string slice into a `ValueRef` with the given options.
+///
+/// # Errors
+///
+/// Returns a `ParseError` if the input string is not valid JSON or violates
+/// one of the limits set in `options`.
+pub fn from_str_ref_with(s: &str, options: ParserOptions) -> Result<ValueRef<'_>, ParseError> {
+    parse_document(s, options, &mut RefBuilder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    #[test]
+    fn borrows_unescaped_strings_and_keys() {
+        let text = r#"{"plain": ["a", "bA"], "esc\"aped": true}"#;
+        let doc = from_str_ref(text).unwrap();
+        let members = doc.as_object().unwrap();
+        assert!(matches!(members[0].0, Cow::Borrowed("plain")));
+        assert!(matches!(members[1].0, Cow::Owned(ref k) if k == "esc\"aped"));
+        let arr = doc.get("plain").unwrap();
+        assert!(arr.get_index(0).unwrap().is_borrowed());
+        assert_eq!(arr.get_index(1).unwrap(), &ValueRef::String(Cow::Owned("bA".into())));
+    }
+
+    #[test]
+    fn matches_owned_parser() {
+        let text = r#"{"a": [1, -2.5e3, null, {"b": false}], "a": "last", "k": "😀"}"#;
+        let doc = from_str_ref(text).unwrap();
+        assert_eq!(doc.get("a").and_then(|v| v.as_str()), Some("last"));
+        assert_eq!(doc.into_owned(), from_str(text).unwrap());
+        assert_eq!(from_str_ref("[1,]"), Err(ParseError::UnexpectedToken(']')));
+        let lenient = ParserOptions { allow_unquoted_keys: true, ..Default::default() };
+        let doc = from_str_ref_with("{key: 'x'}", ParserOptions { allow_single_quotes: true, ..lenient }).unwrap();
+        assert!(matches!(doc.as_object().unwrap()[0].0, Cow::Borrowed("key")));
+    }
+}
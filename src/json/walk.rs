@@ -0,0 +1,153 @@
+//! Traversal of `json::Value` trees without hand-written recursion.
+//!
+//! `entries` and `values` iterate over the direct children of a value.
+//! `dfs` and `bfs` visit every node of the tree, depth-first in document
+//! order or level by level, each paired with its JSON Pointer. `walk` is a
+//! callback shorthand for the depth-first traversal.
+
+use super::pointer::escape_token;
+use super::value::Value;
+use std::collections::VecDeque;
+
+impl Value {
+    /// Iterates over the members of an object. Yields nothing for any
+    /// other kind of value.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.as_object().into_iter().flat_map(|obj| obj.iter())
+    }
+
+    /// Iterates over the elements of an array or the member values of an
+    /// object. Yields nothing for a scalar.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        let elements = self.as_array().into_iter().flatten();
+        elements.chain(self.as_object().into_iter().flat_map(|obj| obj.values()))
+    }
+
+    /// Calls `f` with the JSON Pointer and value of every node in the tree,
+    /// parents before children, in document order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let doc = json!({"a": [1, {"b/c": true}]});
+    /// let mut paths = Vec::new();
+    /// doc.walk(|path, node| {
+    ///     if !node.is_array() && !node.is_object() {
+    ///         paths.push(path.to_string());
+    ///     }
+    /// });
+    /// assert_eq!(paths, ["/a/0", "/a/1/b~1c"]);
+    /// ```
+    pub fn walk<F: FnMut(&str, &Value)>(&self, mut f: F) {
+        for (path, node) in self.dfs() {
+            f(&path, node);
+        }
+    }
+
+    /// Returns a depth-first, pre-order iterator over every node in the tree
+    /// and its JSON Pointer, starting with `self` at `""`.
+    pub fn dfs(&self) -> Dfs<'_> {
+        Dfs { stack: vec![(String::new(), self)] }
+    }
+
+    /// Returns a breadth-first iterator over every node in the tree and its
+    /// JSON Pointer, starting with `self` at `""`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stdt::json;
+    /// let doc = json!({"a": {"x": 1}, "b": [2]});
+    /// let paths: Vec<String> = doc.bfs().map(|(path, _)| path).collect();
+    /// assert_eq!(paths, ["", "/a", "/b", "/a/x", "/b/0"]);
+    /// ```
+    pub fn bfs(&self) -> Bfs<'_> {
+        Bfs { queue: VecDeque::from([(String::new(), self)]) }
+    }
+}
+
+/// Calls `f` with the pointer and value of each child of `node`, in order.
+fn children<'a>(path: &str, node: &'a Value, mut f: impl FnMut(String, &'a Value)) {
+    match node {
+        Value::Array(arr) => {
+            for (i, child) in arr.iter().enumerate() {
+                f(format!("{}/{}", path, i), child);
+            }
+        }
+        Value::Object(obj) => {
+            for (key, child) in obj {
+                f(format!("{}/{}", path, escape_token(key)), child);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Depth-first iterator returned by [`Value::dfs`].
+pub struct Dfs<'a> {
+    stack: Vec<(String, &'a Value)>,
+}
+
+impl<'a> Iterator for Dfs<'a> {
+    type Item = (String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.stack.pop()?;
+        let start = self.stack.len();
+        children(&path, node, |p, child| self.stack.push((p, child)));
+        // Children were pushed in order; reverse them so the first pops first.
+        self.stack[start..].reverse();
+        Some((path, node))
+    }
+}
+
+/// Breadth-first iterator returned by [`Value::bfs`].
+pub struct Bfs<'a> {
+    queue: VecDeque<(String, &'a Value)>,
+}
+
+impl<'a> Iterator for Bfs<'a> {
+    type Item = (String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.queue.pop_front()?;
+        children(&path, node, |p, child| self.queue.push_back((p, child)));
+        Some((path, node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::from_str;
+
+    #[test]
+    fn traversal_orders_and_pointers_resolve() {
+        let doc = from_str(r#"{"a":[1,[2]],"b":{"~c":3}}"#).unwrap();
+        let dfs: Vec<String> = doc.dfs().map(|(p, _)| p).collect();
+        assert_eq!(dfs, ["", "/a", "/a/0", "/a/1", "/a/1/0", "/b", "/b/~0c"]);
+        let bfs: Vec<String> = doc.bfs().map(|(p, _)| p).collect();
+        assert_eq!(bfs, ["", "/a", "/b", "/a/0", "/a/1", "/b/~0c", "/a/1/0"]);
+        for (path, node) in doc.dfs() {
+            assert_eq!(doc.pointer(&path), Some(node));
+        }
+    }
+
+    #[test]
+    fn child_iterators() {
+        let doc = from_str(r#"{"a":1,"b":[2,3]}"#).unwrap();
+        let keys: Vec<&str> = doc.entries().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(doc.values().count(), 2);
+        assert_eq!(doc["b"].values().collect::<Vec<_>>(), [&Value::from(2), &Value::from(3)]);
+        assert_eq!(doc["b"].entries().count(), 0);
+        assert_eq!(Value::Null.values().count(), 0);
+        let mut count = 0;
+        Value::from(1).walk(|path, _| {
+            assert_eq!(path, "");
+            count += 1;
+        });
+        assert_eq!(count, 1);
+    }
+}
@@ -2,7 +2,6 @@
 ///
 /// This modules provides a collection of common utility functions and helpers
 /// that are shared across different types of development in the project.
-
 pub mod utils;
 
 
@@ -6,11 +6,44 @@
 //!
 //! ## Features
 //! - Load from the nearest `.env` above `std::env::current_dir()` (`dotenv()`)
-//! - Load from an explicit path (`dotenv_from(path)`)
+//! - Load from an explicit path (`dotenv_from(path)`), a reader
+//!   (`dotenv_from_reader(reader)`), or a string (`dotenv_from_str(s)`)
 //! - Choose whether to overwrite existing variables (`*_override()` variants)
+//! - Read variables layered over the process environment without mutating
+//!   it (`Env`)
+//! - Run a child process with the variables set for it only (`command(program)`)
+//! - Configure the file name, search depth, overwriting, and strictness with
+//!   the `Dotenv` builder, which can also reject duplicate keys and report
+//!   every parse error of a file at once
+//! - Layer several files, later ones taking precedence (`dotenv_layered(paths)`)
+//! - Load `.env` plus `.env.<profile>` for the profile in `APP_ENV`
+//!   (`dotenv_profile()`)
+//! - Parse without touching the environment (`parse_file(path)`, `parse_str(s)`),
+//!   or one entry at a time (`dotenv_iter(path)`)
+//! - Validate required variables, types, and allowed values (`EnvSchema`)
+//! - Watch a file and receive its variables again when it changes
+//!   (`watch(path, interval, callback)`)
+//! - Encrypted `secret:` values, decrypted with the key in `DOTENV_KEY`, so
+//!   secrets can be committed (`generate_key()`, `encrypt_value(value, key)`).
+//!   Every unquoted or double-quoted value starting with `secret:` is
+//!   decrypted, and fails to load without `DOTENV_KEY`; write a literal
+//!   `secret:` value in single quotes (`HINT='secret:sauce'`).
+//! - Export variables as a POSIX shell, fish, PowerShell, or Docker env file
+//!   script (`export_script(&vars, shell)`)
+//! - Write a new file (`write(path, &vars)`) or edit one in place, keeping
+//!   comments, ordering, and quoting (`EnvFile`)
 //! - Simple parser: `KEY=VALUE`, comments with `#`, optional quotes, and a
-//! small set of escape sequences (e.g., `\n`, `\t`, `\\`, `\"`, `\'`).
+//!   small set of escape sequences (e.g., `\n`, `\t`, `\\`, `\"`, `\'`).
 //! - Supports optional `export KEY=...` prefix (ignored if present).
+//! - Files written on Windows read as expected: a UTF-8 byte order mark is
+//!   skipped and lines may end with `\r\n` or `\r`. Text that is not UTF-8
+//!   is reported with its byte offset, or read as Latin-1 on request.
+//! - Quoted values may span several lines (e.g. PEM keys).
+//! - Variable expansion with `${VAR}` or `$VAR` in unquoted and double-quoted
+//!   values, from keys defined earlier in the file or the process environment
+//!   (`\$` writes a literal `$`; single-quoted values are taken literally).
+//!   `${VAR:-default}` falls back to a default and `${VAR:?message}` fails
+//!   the parse when `VAR` is unset or empty, as in a POSIX shell.
 //! 
 //! ## Examples
 //! ```no_run
@@ -27,53 +60,962 @@
 //! ```
 //!
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::env;
+use std::ffi::OsStr;
 use std::error::Error as StdError;
 use std::fmt;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{self, BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+mod aes_gcm;
 
 
 /// Load the nearest `.env` by walking upward from `current_dir()`.
 /// Returns the number of variables applied.
 pub fn dotenv() -> Result<usize, Error> {
-    let path = find_dotenv(None)?;
-    dotenv_from_impl(&path, false)
+    Dotenv::new().load()
 }
 
 
 /// Load the nearest `.env` by walking upward; **overwrite** existing vars.
 /// Returns the number of variables applyied.
 pub fn dotenv_override() -> Result<usize, Error> {
-    let path = find_dotenv(None)?;
-    dotenv_from_impl(&path, true)
+    Dotenv::new().overwrite(true).load()
 }
 
 /// Load variables from an explicit file path; do **not** overwrite
 /// existing variables.
 pub fn dotenv_from<P: AsRef<Path>>(path: P) -> Result<usize, Error> {
-    dotenv_from_impl(path.as_ref(), false)
+    Dotenv::new().path(path).load()
 }
 
 
 /// Load variables from an explicit file path; **overwrite** existing vars.
 pub fn dotenv_from_override<P: AsRef<Path>>(path: P) -> Result<usize, Error> {
-    dotenv_from_impl(path.as_ref(), true)
+    Dotenv::new().path(path).overwrite(true).load()
+}
+
+
+/// Configurable loader; the `dotenv*` functions are shortcuts for common
+/// settings.
+///
+/// By default it loads the nearest `.env` found by walking upward from
+/// `current_dir()` without limit, does not overwrite existing variables,
+/// and fails on invalid lines.
+///
+/// # Examples
+/// ```no_run
+/// use stdt::utils::dotenv::Dotenv;
+///
+/// let count = Dotenv::new()
+///     .filename(".env.test")
+///     .max_walk_depth(3)
+///     .overwrite(true)
+///     .strict(true)
+///     .load()
+///     .unwrap();
+/// println!("loaded {count} entries");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Dotenv {
+    filename: String,
+    path: Option<PathBuf>,
+    start_dir: Option<PathBuf>,
+    max_walk_depth: Option<usize>,
+    overwrite: bool,
+    policy: ParsePolicy,
+}
+
+/// How `parse_reader_with` treats problems in a file.
+#[derive(Debug, Clone, Copy)]
+struct ParsePolicy {
+    /// Invalid lines are errors; otherwise they are skipped.
+    strict: bool,
+    /// A key assigned twice in the same file is an error.
+    deny_duplicates: bool,
+    /// Report every error at once (`Error::Report`) instead of the first.
+    collect_errors: bool,
+    /// Read lines that are not valid UTF-8 as Latin-1.
+    latin1_fallback: bool,
+}
+
+impl Default for ParsePolicy {
+    fn default() -> Self {
+        ParsePolicy { strict: true, deny_duplicates: false, collect_errors: false, latin1_fallback: false }
+    }
+}
+
+impl Default for Dotenv {
+    fn default() -> Self {
+        Dotenv {
+            filename: ".env".into(),
+            path: None,
+            start_dir: None,
+            max_walk_depth: None,
+            overwrite: false,
+            policy: ParsePolicy::default(),
+        }
+    }
+}
+
+impl Dotenv {
+    /// Creates a loader with the default settings.
+    pub fn new() -> Self {
+        Dotenv::default()
+    }
+
+    /// Sets the file name to search for while walking upward (default `.env`).
+    pub fn filename(mut self, filename: &str) -> Self {
+        self.filename = filename.to_string();
+        self
+    }
+
+    /// Loads this exact file instead of searching for one.
+    pub fn path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the directory the search starts from (default `current_dir()`).
+    pub fn start_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.start_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Limits the search to `depth` parent directories; `0` only looks in
+    /// the start directory.
+    pub fn max_walk_depth(mut self, depth: usize) -> Self {
+        self.max_walk_depth = Some(depth);
+        self
+    }
+
+    /// Whether to overwrite variables that are already set (default `false`).
+    pub fn overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Whether an invalid line, or one that is not valid UTF-8, fails the load
+    /// (default `true`); when `false`, such lines are skipped, along with the
+    /// rest of a multi-line quoted value they belong to. An unterminated
+    /// quote always fails.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.policy.strict = strict;
+        self
+    }
+
+    /// Whether lines that are not valid UTF-8 are read as Latin-1 (ISO 8859-1)
+    /// instead (default `false`), for files saved by older Windows editors.
+    pub fn latin1_fallback(mut self, fallback: bool) -> Self {
+        self.policy.latin1_fallback = fallback;
+        self
+    }
+
+    /// Whether a key assigned twice in the file fails the load (default
+    /// `false`, where the last assignment wins).
+    pub fn deny_duplicates(mut self, deny: bool) -> Self {
+        self.policy.deny_duplicates = deny;
+        self
+    }
+
+    /// Whether to read the whole file and report every problem at once as
+    /// `Error::Report` (default `false`, which stops at the first one).
+    pub fn collect_errors(mut self, collect: bool) -> Self {
+        self.policy.collect_errors = collect;
+        self
+    }
+
+    /// Returns the file `load` would read.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if the search finds no file.
+    pub fn find(&self) -> Result<PathBuf, Error> {
+        match &self.path {
+            Some(path) => Ok(path.clone()),
+            None => find_dotenv(self.start_dir.clone(), &self.filename, self.max_walk_depth),
+        }
+    }
+
+    /// Finds and loads the file. Returns the number of variables applied.
+    pub fn load(&self) -> Result<usize, Error> {
+        Ok(apply_to_env(self.parse()?, self.overwrite))
+    }
+
+    /// Finds and parses the file into a `Command` running `program` with the
+    /// variables set for the child only; the process environment is left
+    /// alone. Unless `overwrite`, variables already set are inherited as is.
+    pub fn command<S: AsRef<OsStr>>(&self, program: S) -> Result<Command, Error> {
+        let mut vars = self.parse()?;
+        if !self.overwrite {
+            vars.retain(|key, _| env::var_os(key).is_none());
+        }
+        let mut command = Command::new(program);
+        command.envs(vars);
+        Ok(command)
+    }
+
+    /// Finds and parses the file into an `Env`, for reads without touching the
+    /// process environment. Unless `overwrite`, the process environment takes
+    /// precedence over the file.
+    pub fn env(&self) -> Result<Env, Error> {
+        Ok(Env { vars: self.parse()?, overwrite: self.overwrite })
+    }
+
+    fn parse(&self) -> Result<HashMap<String, String>, Error> {
+        parse_file_with(&self.find()?, &HashMap::new(), self.policy)
+    }
+}
+
+
+/// Build a `Command` running `program` with the variables of the nearest
+/// `.env` set for the child only, without the global (and `unsafe`)
+/// `std::env::set_var`. Variables already set are inherited as is; use
+/// `Dotenv::command` for other settings.
+///
+/// # Examples
+/// ```no_run
+/// use stdt::utils::dotenv::command;
+///
+/// let status = command("my-binary").unwrap()
+///     .args(["--port", "8080"])
+///     .status()
+///     .unwrap();
+/// ```
+pub fn command<S: AsRef<OsStr>>(program: S) -> Result<Command, Error> {
+    Dotenv::new().command(program)
 }
 
 
-fn dotenv_from_impl(path: &Path, overwrite: bool) -> Result<usize, Error> {
+/// Variables of a `.env` file layered with the process environment, read
+/// through `get` instead of being applied with `std::env::set_var`, which is
+/// `unsafe` and racy once other threads run.
+///
+/// As with `dotenv()`, a variable set in the process environment takes
+/// precedence over the file, unless created by a `Dotenv` loader with
+/// `overwrite(true)`. An `Env` can be shared freely between threads.
+///
+/// # Examples
+/// ```
+/// use stdt::utils::dotenv::{parse_str, Env};
+///
+/// let env = Env::from_vars(parse_str("DOTENV_DOC_PORT=8080").unwrap());
+/// assert_eq!(env.get("DOTENV_DOC_PORT").as_deref(), Some("8080"));
+/// assert!(env.get("PATH").is_some()); // From the process environment.
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Env {
+    vars: HashMap<String, String>,
+    overwrite: bool,
+}
+
+impl Env {
+    /// Loads the nearest `.env` by walking upward from `current_dir()`; use
+    /// `Dotenv::env` for other settings.
+    pub fn load() -> Result<Env, Error> {
+        Dotenv::new().env()
+    }
+
+    /// Layers already parsed variables (e.g. from `parse_file`) under the
+    /// process environment.
+    pub fn from_vars(vars: HashMap<String, String>) -> Env {
+        Env { vars, overwrite: false }
+    }
+
+    /// Returns the value of `key`, from the process environment or the file
+    /// according to their precedence. Values that are not valid Unicode in
+    /// the process environment are skipped.
+    pub fn get(&self, key: &str) -> Option<String> {
+        let from_file = || self.vars.get(key).cloned();
+        if self.overwrite {
+            from_file().or_else(|| env::var(key).ok())
+        } else {
+            env::var(key).ok().or_else(from_file)
+        }
+    }
+
+    /// Returns the variables of the file alone.
+    pub fn vars(&self) -> &HashMap<String, String> {
+        &self.vars
+    }
+}
+
+
+/// Load several files in order, e.g. `.env`, then `.env.local`, then
+/// `.env.production`; do **not** overwrite existing variables.
+///
+/// A later file takes precedence over the earlier ones, and can reference
+/// their variables. Files that do not exist are skipped. Returns, for each
+/// variable applied, the file that supplied its value.
+///
+/// # Examples
+/// ```no_run
+/// use stdt::utils::dotenv::dotenv_layered;
+///
+/// let sources = dotenv_layered(&[".env", ".env.local", ".env.production"]).unwrap();
+/// for (key, file) in &sources {
+///     println!("{key} from {}", file.display());
+/// }
+/// ```
+pub fn dotenv_layered<P: AsRef<Path>>(paths: &[P]) -> Result<HashMap<String, PathBuf>, Error> {
+    let mut merged = HashMap::new();
+    let mut sources = HashMap::new();
+    for path in paths {
+        let path = path.as_ref();
+        let parsed = match parse_file_with(path, &merged, ParsePolicy::default()) {
+            Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => continue,
+            other => other?,
+        };
+        for (key, value) in parsed {
+            sources.insert(key.clone(), path.to_path_buf());
+            merged.insert(key, value);
+        }
+    }
+
+    sources.retain(|key, _| env::var_os(key).is_none());
+    apply_to_env(merged, false);
+    Ok(sources)
+}
+
+/// The variable naming the active profile for `dotenv_profile()`.
+pub const PROFILE_VAR: &str = "APP_ENV";
+
+/// Load the nearest `.env` by walking upward, then `.env.<profile>` next to
+/// it, where the profile is read from `APP_ENV` (e.g. `APP_ENV=staging` loads
+/// `.env.staging`); do **not** overwrite existing variables.
+///
+/// The profile file takes precedence over `.env` and is skipped if it does
+/// not exist. Without a profile, only `.env` is loaded. Returns, as
+/// `dotenv_layered` does, the file that supplied each variable applied.
+///
+/// # Examples
+/// ```no_run
+/// use stdt::utils::dotenv::dotenv_profile;
+///
+/// // With APP_ENV=staging, loads .env and then .env.staging.
+/// let sources = dotenv_profile().unwrap();
+/// println!("loaded {} entries", sources.len());
+/// ```
+pub fn dotenv_profile() -> Result<HashMap<String, PathBuf>, Error> {
+    let profile = env::var(PROFILE_VAR).ok();
+    dotenv_profile_impl(None, profile.as_deref())
+}
+
+/// Load variables from any buffered reader (stdin, a bundled asset, a
+/// decrypted buffer); do **not** overwrite existing variables.
+///
+/// # Examples
+/// ```no_run
+/// use std::io;
+/// use stdt::utils::dotenv::dotenv_from_reader;
+///
+/// let count = dotenv_from_reader(io::stdin().lock()).unwrap();
+/// println!("loaded {count} entries");
+/// ```
+pub fn dotenv_from_reader<R: BufRead>(reader: R) -> Result<usize, Error> {
+    let parsed = parse_reader(reader)?;
+    Ok(apply_to_env(parsed, false))
+}
+
+/// Load variables from `.env` content in a string, such as one embedded with
+/// `include_str!`; do **not** overwrite existing variables.
+pub fn dotenv_from_str(s: &str) -> Result<usize, Error> {
+    dotenv_from_reader(s.as_bytes())
+}
+
+/// Parse a `.env` file and return its variables, without touching the
+/// process environment.
+///
+/// References to variables not defined in the file are still read from the
+/// environment.
+///
+/// # Examples
+/// ```no_run
+/// use stdt::utils::dotenv::parse_file;
+///
+/// let vars = parse_file("config/dev.env").unwrap();
+/// println!("{:?}", vars.get("DATABASE_URL"));
+/// ```
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<HashMap<String, String>, Error> {
+    parse_file_with(path.as_ref(), &HashMap::new(), ParsePolicy::default())
+}
+
+/// Same as `parse_file`, with `base` visible to variable references.
+fn parse_file_with(path: &Path, base: &HashMap<String, String>, policy: ParsePolicy) -> Result<HashMap<String, String>, Error> {
     let file = File::open(path).map_err(|e| Error::Io {
         path: path.to_path_buf(),
         source: e,
     })?;
 
-    let reader = BufReader::new(file);
-    let parsed = parse_reader(reader)?;
-    let applied = apply_to_env(parsed, overwrite);
-    Ok(applied)
+    parse_reader_with(BufReader::new(file), base, policy).map_err(|e| with_path(e, path))
+}
+
+/// Parse `.env` content from a string and return its variables, without
+/// touching the process environment.
+///
+/// # Examples
+/// ```
+/// use stdt::utils::dotenv::parse_str;
+///
+/// let vars = parse_str("HOST=localhost\nURL=http://${HOST}:8080").unwrap();
+/// assert_eq!(vars["URL"], "http://localhost:8080");
+/// ```
+pub fn parse_str(s: &str) -> Result<HashMap<String, String>, Error> {
+    parse_reader(s.as_bytes())
+}
+
+
+/// Read the file at `path` lazily, yielding one variable per entry, so they
+/// can be filtered or transformed before being applied.
+///
+/// References expand as in `parse_file`, against every earlier entry. An
+/// invalid line yields an `Error::Parse` and reading goes on; a read error
+/// ends the iteration.
+///
+/// # Examples
+/// ```no_run
+/// use std::collections::HashMap;
+/// use stdt::utils::dotenv::dotenv_iter;
+///
+/// let vars: HashMap<String, String> = dotenv_iter(".env")
+///     .unwrap()
+///     .filter_map(Result::ok)
+///     .filter(|(key, _)| key.starts_with("MYAPP_"))
+///     .collect();
+/// ```
+pub fn dotenv_iter<P: AsRef<Path>>(path: P) -> Result<DotenvIter, Error> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| Error::Io { path: path.to_path_buf(), source: e })?;
+    let entries = Entries::new(BufReader::new(file), ParsePolicy::default());
+    Ok(DotenvIter { entries, path: path.to_path_buf(), scope: HashMap::new() })
+}
+
+/// Iterator over the variables of a file, created by `dotenv_iter`.
+#[derive(Debug)]
+pub struct DotenvIter {
+    entries: Entries<BufReader<File>>,
+    path: PathBuf,
+    scope: HashMap<String, String>,
+}
+
+impl Iterator for DotenvIter {
+    type Item = Result<(String, String), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (line, entry) = match self.entries.next()? {
+                Ok(item) => item,
+                Err(e) => return Some(Err(with_path(e, &self.path))),
+            };
+            match parse_line_with(&entry, &self.scope) {
+                Line::Comment | Line::Blank => {}
+                Line::Pair { key, value } => {
+                    self.scope.insert(key.clone(), value.clone());
+                    return Some(Ok((key, value)));
+                }
+                Line::Err(msg) => return Some(Err(Error::Parse { path: Some(self.path.clone()), line, msg })),
+            }
+        }
+    }
+}
+
+/// Watch the file at `path` on a background thread, checking every `interval`
+/// whether its modification time or size changed; on a change, `callback`
+/// receives the newly parsed variables (or the error reading them).
+///
+/// The environment is left alone: apply what you need from the callback.
+/// Watching stops when the returned `Watcher` is stopped or dropped.
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use stdt::utils::dotenv::watch;
+///
+/// let watcher = watch(".env", Duration::from_secs(1), |vars| match vars {
+///     Ok(vars) => println!("reloaded {} entries", vars.len()),
+///     Err(e) => eprintln!("{e}"),
+/// });
+/// // ...
+/// watcher.stop();
+/// ```
+pub fn watch<P, F>(path: P, interval: Duration, mut callback: F) -> Watcher
+where
+    P: AsRef<Path>,
+    F: FnMut(Result<HashMap<String, String>, Error>) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let (stop, stopped) = mpsc::channel::<()>();
+    let handle = thread::spawn(move || {
+        let stamp = |path: &Path| fs::metadata(path).ok().map(|m| (m.modified().ok(), m.len()));
+        let mut last = stamp(&path);
+        // Both a stop request and a dropped `Watcher` end the loop.
+        while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            let current = stamp(&path);
+            if current != last {
+                last = current;
+                callback(parse_file(&path));
+            }
+        }
+    });
+    Watcher { stop: Some(stop), handle: Some(handle) }
+}
+
+/// Handle to a background watch started by `watch`.
+#[derive(Debug)]
+pub struct Watcher {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Watcher {
+    /// Stops watching and waits for the background thread to finish.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+
+/// The variable holding the key that decrypts `secret:` values.
+pub const KEY_VAR: &str = "DOTENV_KEY";
+
+const SECRET_PREFIX: &str = "secret:";
+
+/// Generate a random key for `secret:` values: 64 hex digits (AES-256),
+/// to be kept out of the repository and provided as `DOTENV_KEY`.
+///
+/// # Errors
+///
+/// Returns `Error::Secret` if the system random source (`/dev/urandom`)
+/// is unavailable.
+pub fn generate_key() -> Result<String, Error> {
+    let key: [u8; aes_gcm::KEY_LEN] = random_bytes()?;
+    Ok(key.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Encrypt `value` with `key` (64 hex digits) into a `secret:` value, which
+/// can be committed in a `.env` file and is decrypted while loading it when
+/// `DOTENV_KEY` holds the same key. The value must be written unquoted or in
+/// double quotes: single-quoted values are never decrypted.
+///
+/// Values are sealed with AES-256-GCM under a random nonce, so encrypting
+/// the same value twice gives different results.
+///
+/// # Errors
+///
+/// Returns `Error::Secret` if the key is malformed or the system random
+/// source (`/dev/urandom`) is unavailable.
+///
+/// # Examples
+/// ```no_run
+/// use stdt::utils::dotenv::{encrypt_value, generate_key};
+///
+/// let key = generate_key().unwrap(); // Store it as DOTENV_KEY.
+/// let line = format!("API_TOKEN={}", encrypt_value("s3cret", &key).unwrap());
+/// assert!(line.starts_with("API_TOKEN=secret:"));
+/// ```
+pub fn encrypt_value(value: &str, key: &str) -> Result<String, Error> {
+    let key = parse_key(key).map_err(|msg| Error::Secret { msg })?;
+    let nonce: [u8; aes_gcm::NONCE_LEN] = random_bytes()?;
+    let mut payload = nonce.to_vec();
+    payload.extend(aes_gcm::seal(&key, &nonce, value.as_bytes()));
+    Ok(format!("{}{}", SECRET_PREFIX, base64_encode(&payload)))
+}
+
+/// Decrypts the payload of a `secret:` value with the key in `DOTENV_KEY`.
+fn decrypt_secret(payload: &str) -> Result<String, String> {
+    let key = env::var(KEY_VAR).map_err(|_| format!("secret value needs {} to be set", KEY_VAR))?;
+    let key = parse_key(&key)?;
+    let sealed = base64_decode(payload).ok_or("malformed secret value")?;
+    if sealed.len() < aes_gcm::NONCE_LEN + aes_gcm::TAG_LEN {
+        return Err("malformed secret value".into());
+    }
+    let (nonce, sealed) = sealed.split_at(aes_gcm::NONCE_LEN);
+    let nonce = nonce.try_into().expect("split at the nonce length");
+    let plain = aes_gcm::open(&key, nonce, sealed).ok_or("cannot decrypt secret value: wrong key or altered data")?;
+    String::from_utf8(plain).map_err(|_| "secret value is not UTF-8".into())
+}
+
+fn parse_key(key: &str) -> Result<[u8; aes_gcm::KEY_LEN], String> {
+    let key = key.trim();
+    if key.len() != aes_gcm::KEY_LEN * 2 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("{} must be {} hex digits", KEY_VAR, aes_gcm::KEY_LEN * 2));
+    }
+    let mut out = [0u8; aes_gcm::KEY_LEN];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&key[2 * i..2 * i + 2], 16).expect("checked hex digits");
+    }
+    Ok(out)
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], Error> {
+    use std::io::Read;
+
+    let mut out = [0u8; N];
+    File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut out))
+        .map_err(|e| Error::Secret { msg: format!("no system random source: {}", e) })?;
+    Ok(out)
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding (RFC 4648).
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(4) { return None; }
+    let mut out = Vec::with_capacity(s.len() / 4 * 3);
+    for chunk in s.as_bytes().chunks(4) {
+        let pad = chunk.iter().rev().take_while(|b| **b == b'=').count();
+        if pad > 2 { return None; }
+        let mut n = 0u32;
+        for (i, b) in chunk[..4 - pad].iter().enumerate() {
+            let digit = BASE64.iter().position(|c| c == b)? as u32;
+            n |= digit << (18 - 6 * i);
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..4 - pad]);
+    }
+    Some(out)
+}
+
+
+/// Output formats of `export_script`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// `export KEY='value'`, for sh, bash, and zsh.
+    Posix,
+    /// `set -gx KEY 'value'`.
+    Fish,
+    /// `$env:KEY = 'value'`.
+    PowerShell,
+    /// `KEY=value`, for `docker run --env-file`, which takes values literally.
+    DockerEnv,
+}
+
+/// Render `vars` as a script setting them in `shell`, one variable per line
+/// in key order, with values quoted so they are taken literally.
+///
+/// # Errors
+///
+/// Returns `Error::Export` for a key that is not a valid variable name, a
+/// value containing a NUL character, or, for `Shell::DockerEnv`, a value
+/// spanning several lines.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use stdt::utils::dotenv::{export_script, Shell};
+///
+/// let vars = HashMap::from([("GREETING".to_string(), "it's $HOME".to_string())]);
+/// assert_eq!(export_script(&vars, Shell::Posix).unwrap(), "export GREETING='it'\\''s $HOME'\n");
+/// assert_eq!(export_script(&vars, Shell::PowerShell).unwrap(), "$env:GREETING = 'it''s $HOME'\n");
+/// ```
+pub fn export_script(vars: &HashMap<String, String>, shell: Shell) -> Result<String, Error> {
+    let mut keys: Vec<&String> = vars.keys().collect();
+    keys.sort();
+
+    let mut out = String::new();
+    for key in keys {
+        let value = &vars[key];
+        let fail = |msg: &str| Err(Error::Export { key: key.clone(), msg: msg.into() });
+        if !is_valid_key(key) {
+            return fail("invalid variable name");
+        }
+        if value.contains('\0') {
+            return fail("value contains a NUL character");
+        }
+        let line = match shell {
+            Shell::Posix => format!("export {}='{}'", key, value.replace('\'', "'\\''")),
+            Shell::Fish => format!("set -gx {} '{}'", key, value.replace('\\', "\\\\").replace('\'', "\\'")),
+            Shell::PowerShell => format!("$env:{} = '{}'", key, value.replace('\'', "''")),
+            Shell::DockerEnv if value.contains(['\n', '\r']) => return fail("env files cannot hold several lines"),
+            Shell::DockerEnv => format!("{}={}", key, value),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+
+/// Write `vars` to a new `.env` file at `path`, one `KEY=value` per line in
+/// key order, quoting values where needed. An existing file is replaced; use
+/// `EnvFile` to update one while keeping its comments.
+///
+/// # Examples
+/// ```no_run
+/// use std::collections::HashMap;
+/// use stdt::utils::dotenv::write;
+///
+/// let vars = HashMap::from([("GREETING".to_string(), "hello world".to_string())]);
+/// write(".env", &vars).unwrap(); // GREETING="hello world"
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, vars: &HashMap<String, String>) -> Result<(), Error> {
+    let mut keys: Vec<&String> = vars.keys().collect();
+    keys.sort();
+
+    let mut contents = String::new();
+    for key in keys {
+        contents.push_str(&format!("{}={}\n", key, quote_value(&vars[key], Quote::None)));
+    }
+    write_file(path.as_ref(), &contents)
+}
+
+/// An editable `.env` file that keeps its comments, blank lines, ordering,
+/// and quoting style.
+///
+/// # Examples
+/// ```no_run
+/// use stdt::utils::dotenv::EnvFile;
+///
+/// let mut file = EnvFile::open(".env").unwrap();
+/// file.set("PORT", "8081").unwrap();
+/// file.remove("LEGACY_FLAG");
+/// file.save(".env").unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EnvFile {
+    entries: Vec<Entry>,
+    /// The file started with a UTF-8 byte order mark.
+    bom: bool,
+    /// Lines end with `\r\n` rather than `\n`.
+    crlf: bool,
+}
+
+/// One entry of an `EnvFile`: a comment, a blank line, or a (possibly
+/// multi-line) assignment, kept as written.
+#[derive(Debug, Clone)]
+struct Entry {
+    text: String,
+    key: Option<String>,
+}
+
+/// The quoting style of a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+impl EnvFile {
+    /// Reads the file at `path` for editing.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| Error::Io { path: path.to_path_buf(), source: e })?;
+        EnvFile::parse(&contents).map_err(|e| with_path(e, path))
+    }
+
+    /// Reads `.env` content from a string for editing.
+    ///
+    /// # Examples
+    /// ```
+    /// use stdt::utils::dotenv::EnvFile;
+    ///
+    /// let mut file = EnvFile::parse("# Server\nHOST='localhost'\nPORT=8080 # default\n").unwrap();
+    /// file.set("HOST", "example.com").unwrap();
+    /// file.set("PORT", "80").unwrap();
+    /// file.set("DEBUG", "true").unwrap();
+    /// assert_eq!(file.to_string(), "# Server\nHOST='example.com'\nPORT=80 # default\nDEBUG=true\n");
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let bom = s.starts_with('\u{feff}');
+        let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+        let crlf = s.contains("\r\n");
+        let mut entries = Vec::new();
+        let mut entry = String::new();
+        let mut entry_line = 0usize;
+
+        // Lines end with `\n`, `\r\n`, or a lone `\r`.
+        let lines = s.split_terminator('\n').flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'));
+        for (i, line) in lines.enumerate() {
+            if entry.is_empty() {
+                entry_line = i + 1;
+            } else {
+                entry.push('\n');
+            }
+            entry.push_str(line);
+            if has_open_quote(&entry) { continue; }
+
+            let trimmed = entry.trim();
+            let key = if trimmed.is_empty() || trimmed.starts_with('#') {
+                None
+            } else {
+                match entry_key(trimmed) {
+                    Ok(key) => Some(key.to_string()),
+                    Err(msg) => return Err(Error::Parse { path: None, line: entry_line, msg: msg.into() }),
+                }
+            };
+            entries.push(Entry { text: std::mem::take(&mut entry), key });
+        }
+
+        if !entry.is_empty() {
+            return Err(Error::Parse { path: None, line: entry_line, msg: "unterminated quoted value".into() });
+        }
+        Ok(EnvFile { entries, bom, crlf })
+    }
+
+    /// Sets `key` to `value`. An existing assignment is rewritten in place
+    /// with its quoting style (switching to double quotes if the value needs
+    /// them); a new key is appended at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidKey` if `key` is not a valid variable name.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        if !is_valid_key(key) {
+            return Err(Error::InvalidKey { key: key.to_string() });
+        }
+        let Some(index) = self.entries.iter().rposition(|e| e.key.as_deref() == Some(key)) else {
+            self.entries.push(Entry { text: format!("{}={}", key, quote_value(value, Quote::None)), key: Some(key.to_string()) });
+            return Ok(());
+        };
+
+        let text = &self.entries[index].text;
+        let eq = text.find('=').expect("assignments contain '='");
+        let value_start = eq + 1 + (text[eq + 1..].len() - text[eq + 1..].trim_start().len());
+        let (quote, suffix) = split_value(&text[value_start..]);
+        let quoted = quote_value(value, quote);
+
+        // A comment after an unquoted value would end up inside the quotes,
+        // so it moves to its own entry above the assignment.
+        if quote == Quote::None && quoted.starts_with('"') && !suffix.trim().is_empty() {
+            let comment = Entry { text: suffix.trim().to_string(), key: None };
+            self.entries[index].text = format!("{}{}", &text[..value_start], quoted);
+            self.entries.insert(index, comment);
+        } else {
+            self.entries[index].text = format!("{}{}{}", &text[..value_start], quoted, suffix);
+        }
+        Ok(())
+    }
+
+    /// Removes every assignment of `key`. Returns `false` if there was none.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.key.as_deref() != Some(key));
+        self.entries.len() != before
+    }
+
+    /// Writes the file to `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        write_file(path.as_ref(), &self.to_string())
+    }
+}
+
+impl fmt::Display for EnvFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.bom {
+            f.write_str("\u{feff}")?;
+        }
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        for entry in &self.entries {
+            write!(f, "{}{}", entry.text.replace('\n', newline), newline)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the key of an assignment line.
+fn entry_key(line: &str) -> Result<&str, &'static str> {
+    let line = line.strip_prefix("export ")
+        .or_else(|| line.strip_prefix("export\t"))
+        .unwrap_or(line);
+    let (key, _) = line.split_once('=').ok_or("missing '='")?;
+    let key = key.trim();
+    if !is_valid_key(key) { return Err("invalid key"); }
+    Ok(key)
+}
+
+/// Splits a raw value into its quoting style and whatever follows the value
+/// (a closing-quote suffix or an inline comment, with its spacing).
+fn split_value(raw: &str) -> (Quote, &str) {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let end = rest.find('\'').map_or(raw.len(), |i| i + 2);
+        return (Quote::Single, &raw[end..]);
+    }
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => { chars.next(); }
+                '"' => return (Quote::Double, &raw[i + 2..]),
+                _ => {}
+            }
+        }
+        return (Quote::Double, "");
+    }
+    let value = strip_inline_comment_if_unquoted(raw);
+    (Quote::None, &raw[value.trim_end().len()..])
+}
+
+/// Renders `value` in the `preferred` style, or in double quotes if it
+/// cannot be written that way.
+fn quote_value(value: &str, preferred: Quote) -> String {
+    let bare = !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || "#\"'$\\".contains(c));
+    match preferred {
+        Quote::None if bare || value.is_empty() => value.to_string(),
+        Quote::Single if !value.contains('\'') => format!("'{}'", value),
+        _ => {
+            let mut out = String::from('"');
+            for c in value.chars() {
+                match c {
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    '\0' => out.push_str("\\0"),
+                    '"' | '\\' | '$' => { out.push('\\'); out.push(c); }
+                    _ => out.push(c),
+                }
+            }
+            out.push('"');
+            out
+        }
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path`, then renames it
+/// over `path`, so readers never see a half-written file.
+fn write_file(path: &Path, contents: &str) -> Result<(), Error> {
+    let io_err = |e| Error::Io { path: path.to_path_buf(), source: e };
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents).map_err(io_err)?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        io_err(e)
+    })
 }
 
 
@@ -86,6 +1028,15 @@ pub enum Error {
     Parse { path: Option<PathBuf>, line: usize, msg: String },
     /// `.env` file not found while walking up from a directory.
     NotFound { start_dir: PathBuf },
+    /// Every syntactic error in a file, as `(line, msg)` pairs, when the
+    /// `Dotenv` loader collects them.
+    Report { path: Option<PathBuf>, errors: Vec<(usize, String)> },
+    /// A key for `secret:` values could not be made or used.
+    Secret { msg: String },
+    /// A variable cannot be written in the requested `Shell` format.
+    Export { key: String, msg: String },
+    /// `EnvFile::set` was given a key that is not a valid variable name.
+    InvalidKey { key: String },
 }
 
 impl fmt::Display for Error {
@@ -102,6 +1053,23 @@ impl fmt::Display for Error {
             }
 
             Error::NotFound { start_dir } => write!(f, ".env not found (start: {})", start_dir.display()),
+
+            Error::Report { path, errors } => {
+                match path {
+                    Some(p) => write!(f, "{} parse errors in {}", errors.len(), p.display())?,
+                    None => write!(f, "{} parse errors", errors.len())?,
+                }
+                for (i, (line, msg)) in errors.iter().enumerate() {
+                    write!(f, "{} line {}: {}", if i == 0 { ":" } else { ";" }, line, msg)?;
+                }
+                Ok(())
+            }
+
+            Error::Secret { msg } => write!(f, "secret error: {}", msg),
+
+            Error::Export { key, msg } => write!(f, "cannot export {}: {}", key, msg),
+
+            Error::InvalidKey { key } => write!(f, "invalid variable name: {:?}", key),
         }
     }
 }
@@ -116,47 +1084,410 @@ impl StdError for Error {
     }
 }
 
-fn find_dotenv(start: Option<PathBuf>) -> Result<PathBuf, Error> {
-    let mut dir = start.unwrap_or_else(|| env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+/// The type a variable declared in an `EnvSchema` must parse as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarType {
+    /// Any value.
+    String,
+    /// A signed integer (`i64`).
+    Integer,
+    /// A floating-point number (`f64`).
+    Float,
+    /// `true`/`false`, `1`/`0`, `yes`/`no`, or `on`/`off` (case-insensitive).
+    Bool,
+}
+
+impl VarType {
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            VarType::String => true,
+            VarType::Integer => value.parse::<i64>().is_ok(),
+            VarType::Float => value.parse::<f64>().is_ok(),
+            VarType::Bool => ["true", "false", "1", "0", "yes", "no", "on", "off"]
+                .iter()
+                .any(|b| b.eq_ignore_ascii_case(value)),
+        }
+    }
+}
+
+impl fmt::Display for VarType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VarType::String => "string",
+            VarType::Integer => "integer",
+            VarType::Float => "float",
+            VarType::Bool => "bool",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Declares the variables an application expects, to check them all at once
+/// after loading instead of failing at first use.
+///
+/// # Examples
+/// ```
+/// use stdt::utils::dotenv::{EnvSchema, VarType};
+///
+/// let schema = EnvSchema::new()
+///     .required("DATABASE_URL", VarType::String)
+///     .required("PORT", VarType::Integer)
+///     .optional("LOG_LEVEL", VarType::String)
+///     .one_of("LOG_LEVEL", &["debug", "info", "warn", "error"]);
+///
+/// if let Err(errors) = schema.validate() {
+///     for e in &errors {
+///         eprintln!("{e}");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EnvSchema {
+    vars: Vec<VarSpec>,
+}
+
+#[derive(Debug, Clone)]
+struct VarSpec {
+    key: String,
+    required: bool,
+    ty: VarType,
+    allowed: Option<Vec<String>>,
+}
+
+/// A variable that failed `EnvSchema::validate()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// A required variable is unset or empty.
+    Missing { key: String },
+    /// The value does not parse as the declared type.
+    Invalid { key: String, value: String, expected: VarType },
+    /// The value is not one of the allowed values.
+    NotAllowed { key: String, value: String, allowed: Vec<String> },
+}
+
+impl EnvSchema {
+    /// Creates an empty schema.
+    pub fn new() -> Self {
+        EnvSchema::default()
+    }
+
+    /// Declares a variable that must be set, non-empty, and of type `ty`.
+    pub fn required(self, key: &str, ty: VarType) -> Self {
+        self.declare(key, true, ty)
+    }
+
+    /// Declares a variable that, when set and non-empty, must be of type `ty`.
+    pub fn optional(self, key: &str, ty: VarType) -> Self {
+        self.declare(key, false, ty)
+    }
+
+    /// Restricts `key` to the given values. An undeclared key is declared
+    /// as an optional string.
+    pub fn one_of(mut self, key: &str, allowed: &[&str]) -> Self {
+        if !self.vars.iter().any(|v| v.key == key) {
+            self = self.optional(key, VarType::String);
+        }
+        let spec = self.vars.iter_mut().find(|v| v.key == key).expect("declared above");
+        spec.allowed = Some(allowed.iter().map(|a| a.to_string()).collect());
+        self
+    }
+
+    /// Checks the process environment against the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns every missing or invalid variable, in declaration order.
+    pub fn validate(&self) -> Result<(), Vec<SchemaError>> {
+        self.validate_with(|key| env::var_os(key).map(|v| v.to_string_lossy().into_owned()))
+    }
+
+    /// Checks a parsed variable map (e.g. from `parse_file`) against the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns every missing or invalid variable, in declaration order.
+    pub fn validate_map(&self, vars: &HashMap<String, String>) -> Result<(), Vec<SchemaError>> {
+        self.validate_with(|key| vars.get(key).cloned())
+    }
+
+    // --- Internal Helpers ---
+
+    fn declare(mut self, key: &str, required: bool, ty: VarType) -> Self {
+        match self.vars.iter_mut().find(|v| v.key == key) {
+            Some(spec) => {
+                spec.required = required;
+                spec.ty = ty;
+            }
+            None => self.vars.push(VarSpec { key: key.to_string(), required, ty, allowed: None }),
+        }
+        self
+    }
+
+    fn validate_with(&self, get: impl Fn(&str) -> Option<String>) -> Result<(), Vec<SchemaError>> {
+        let mut errors = Vec::new();
+        for spec in &self.vars {
+            let value = match get(&spec.key).filter(|v| !v.is_empty()) {
+                Some(value) => value,
+                None if spec.required => {
+                    errors.push(SchemaError::Missing { key: spec.key.clone() });
+                    continue;
+                }
+                None => continue,
+            };
+            if !spec.ty.accepts(&value) {
+                errors.push(SchemaError::Invalid { key: spec.key.clone(), value, expected: spec.ty });
+            } else if let Some(allowed) = &spec.allowed
+                && !allowed.contains(&value)
+            {
+                errors.push(SchemaError::NotAllowed { key: spec.key.clone(), value, allowed: allowed.clone() });
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Missing { key } => write!(f, "missing required variable {}", key),
+            SchemaError::Invalid { key, value, expected } => {
+                write!(f, "invalid {}={:?}: expected {}", key, value, expected)
+            }
+            SchemaError::NotAllowed { key, value, allowed } => {
+                write!(f, "invalid {}={:?}: expected one of {}", key, value, allowed.join(", "))
+            }
+        }
+    }
+}
+
+impl StdError for SchemaError {}
+
+fn dotenv_profile_impl(start: Option<PathBuf>, profile: Option<&str>) -> Result<HashMap<String, PathBuf>, Error> {
+    let base = find_dotenv(start, ".env", None)?;
+    match profile.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(profile) => {
+            let layer = base.with_file_name(format!(".env.{profile}"));
+            dotenv_layered(&[base, layer])
+        }
+        None => dotenv_layered(&[base]),
+    }
+}
+
+/// Looks for `filename` in `start` (default `current_dir()`) and up to
+/// `max_depth` of its parents (all of them if `None`).
+fn find_dotenv(start: Option<PathBuf>, filename: &str, max_depth: Option<usize>) -> Result<PathBuf, Error> {
+    let start = start.unwrap_or_else(|| env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+    let mut dir = start.clone();
+    let mut depth = 0usize;
     loop {
-        let candidate = dir.join(".env");
+        let candidate = dir.join(filename);
         
         if candidate.is_file() {
             return Ok(candidate);
         }
 
-        if !dir.pop() {
-            return Err(Error::NotFound { start_dir: env::current_dir().unwrap_or_else(|_| PathBuf::from(".")) });
+        if max_depth.is_some_and(|max| depth >= max) || !dir.pop() {
+            return Err(Error::NotFound { start_dir: start });
         }
+        depth += 1;
     }
 }
 
-fn parse_reader<R: BufRead>(mut reader: R) -> Result<HashMap<String, String>, Error> {
-    let mut buf = String::new();
-    let mut map = HashMap::new();
-    let mut line_no = 0usize;
+fn parse_reader<R: BufRead>(reader: R) -> Result<HashMap<String, String>, Error> {
+    parse_reader_with(reader, &HashMap::new(), ParsePolicy::default())
+}
 
-    
-    loop {
-        buf.clear();
-        let bytes = reader.read_line(&mut buf).map_err(|e| Error::Io { path: PathBuf::from("<reader>"), source: e })?;
+/// Parses entries from `reader`. References resolve to the keys defined so
+/// far, then to `base` (e.g. earlier files), then to the process environment.
+fn parse_reader_with<R: BufRead>(reader: R, base: &HashMap<String, String>, policy: ParsePolicy) -> Result<HashMap<String, String>, Error> {
+    let mut map = HashMap::new();
+    let mut scope = base.clone();
+    // Problems found so far, when `policy.collect_errors`.
+    let mut errors = Vec::new();
 
-        if bytes == 0 { break; }
-        line_no += 1;
+    for item in Entries::new(reader, policy) {
+        let (entry_line, entry) = match item {
+            Ok(item) => item,
+            Err(Error::Parse { line, msg, .. }) if policy.collect_errors => {
+                errors.push((line, msg));
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
 
-        let trimmed = buf.trim_end_matches(['\n', '\r']);
-        
-        if trimmed.trim().is_empty() { continue; }
-        match parse_line(trimmed) {
-            Line::Comment | Line::Blank => {}
-            Line::Pair { key, value } => { map.insert(key, value); }
-            Line::Err(msg) => return Err(Error::Parse { path: None, line: line_no, msg }),
+        let problem = match parse_line_with(&entry, &scope) {
+            Line::Comment | Line::Blank => None,
+            Line::Pair { key, .. } if policy.deny_duplicates && map.contains_key(&key) => {
+                Some(format!("duplicate key {}", key))
+            }
+            Line::Pair { key, value } => {
+                scope.insert(key.clone(), value.clone());
+                map.insert(key, value);
+                None
+            }
+            Line::Err(msg) if policy.strict => Some(msg),
+            Line::Err(_) => None,
+        };
+        if let Some(msg) = problem {
+            if !policy.collect_errors {
+                return Err(Error::Parse { path: None, line: entry_line, msg });
+            }
+            errors.push((entry_line, msg));
         }
-    }   
+    }
 
+    if !errors.is_empty() {
+        return Err(Error::Report { path: None, errors });
+    }
     Ok(map)
 }
 
+/// Reads the entries of a `.env` source with the line each starts on,
+/// skipping blank lines and joining the lines of multi-line quoted values.
+///
+/// A leading UTF-8 byte order mark is dropped, and lines may end with `\n`,
+/// `\r\n`, or a lone `\r`.
+#[derive(Debug)]
+struct Entries<R> {
+    reader: R,
+    policy: ParsePolicy,
+    line_no: usize,
+    /// Byte offset in the source of the next chunk read.
+    offset: usize,
+    /// Lines read but not returned yet, with their byte offsets: a chunk up to
+    /// `\n` holds several lines when they end with a lone `\r`.
+    pending: VecDeque<(usize, Vec<u8>)>,
+    done: bool,
+}
+
+impl<R: BufRead> Entries<R> {
+    fn new(reader: R, policy: ParsePolicy) -> Self {
+        Entries { reader, policy, line_no: 0, offset: 0, pending: VecDeque::new(), done: false }
+    }
+
+    /// Returns the next raw line and its byte offset, without its line ending.
+    fn next_line(&mut self) -> Option<io::Result<(usize, Vec<u8>)>> {
+        if let Some(line) = self.pending.pop_front() {
+            return Some(Ok(line));
+        }
+        let mut chunk = Vec::new();
+        match self.reader.read_until(b'\n', &mut chunk) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        let mut start = self.offset;
+        self.offset += chunk.len();
+
+        let mut bytes = &chunk[..];
+        if start == 0 && bytes.starts_with(UTF8_BOM) {
+            bytes = &bytes[UTF8_BOM.len()..];
+            start += UTF8_BOM.len();
+        }
+        let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+        let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+        for line in bytes.split(|b| *b == b'\r') {
+            self.pending.push_back((start, line.to_vec()));
+            start += line.len() + 1;
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+impl<R: BufRead> Iterator for Entries<R> {
+    type Item = Result<(usize, String), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done { return None; }
+        let mut entry = String::new();
+        let mut entry_line = 0usize;
+        // Set when a line of the entry is not valid UTF-8 and errors are not
+        // strict: the whole entry is read, then dropped.
+        let mut undecodable = false;
+
+        loop {
+            let (offset, bytes) = match self.next_line() {
+                None => {
+                    self.done = true;
+                    if entry.is_empty() { return None; }
+                    let msg = "unterminated quoted value".into();
+                    return Some(Err(Error::Parse { path: None, line: entry_line, msg }));
+                }
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(Error::Io { path: PathBuf::from("<reader>"), source: e }));
+                }
+            };
+            self.line_no += 1;
+
+            let line = match String::from_utf8(bytes) {
+                Ok(line) => line,
+                Err(e) if self.policy.latin1_fallback => e.into_bytes().into_iter().map(char::from).collect(),
+                Err(e) if !self.policy.strict => {
+                    undecodable = true;
+                    String::from_utf8_lossy(e.as_bytes()).into_owned()
+                }
+                Err(e) => {
+                    let msg = format!("invalid UTF-8 at byte offset {}", offset + e.utf8_error().valid_up_to());
+                    return Some(Err(Error::Parse { path: None, line: self.line_no, msg }));
+                }
+            };
+
+            // A quoted value continues on the next lines up to its closing quote.
+            if entry.is_empty() {
+                if line.trim().is_empty() { continue; }
+                entry_line = self.line_no;
+            } else {
+                entry.push('\n');
+            }
+            entry.push_str(&line);
+            if has_open_quote(&entry) {
+                continue;
+            }
+            if !undecodable {
+                return Some(Ok((entry_line, entry)));
+            }
+            entry.clear();
+            undecodable = false;
+        }
+    }
+}
+
+/// Sets the path of a parse or read error to `path`.
+fn with_path(e: Error, path: &Path) -> Error {
+    match e {
+        Error::Parse { line, msg, .. } => Error::Parse { path: Some(path.to_path_buf()), line, msg },
+        Error::Report { errors, .. } => Error::Report { path: Some(path.to_path_buf()), errors },
+        Error::Io { source, .. } => Error::Io { path: path.to_path_buf(), source },
+        other => other,
+    }
+}
+
+/// Returns `true` if the value of `entry` opens a quote that is not closed yet.
+fn has_open_quote(entry: &str) -> bool {
+    if entry.trim_start().starts_with('#') {
+        return false;
+    }
+    let Some((_, value)) = entry.split_once('=') else { return false };
+    let value = value.trim_start();
+    if let Some(rest) = value.strip_prefix('\'') {
+        return !rest.contains('\'');
+    }
+    let Some(rest) = value.strip_prefix('"') else { return false };
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => { chars.next(); }
+            '"' => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum Line {
     Comment,
@@ -165,14 +1496,19 @@ enum Line {
     Err(String),
 }
 
+#[cfg(test)]
 fn parse_line(s: &str) -> Line {
+    parse_line_with(s, &HashMap::new())
+}
+
+/// Parses one line; `vars` holds the keys defined earlier, for expansion.
+fn parse_line_with(s: &str, vars: &HashMap<String, String>) -> Line {
     let s = s.trim();
     if s.is_empty() { return Line::Blank; }
     if s.starts_with('#') { return Line::Comment; }
 
     let s = s.strip_prefix("export ")
              .or_else(|| s.strip_prefix("export\t"))
-             .map(|t| t)
              .unwrap_or(s);
 
     let mut in_single = false;
@@ -212,7 +1548,12 @@ fn parse_line(s: &str) -> Line {
         val
     };
 
-    let value = unquote_and_unescape(val.trim());
+    // Single-quoted values are literal, so they can hold a plain `secret:` text.
+    let literal = val.trim().starts_with('\'');
+    let value = unquote_and_unescape(val.trim(), vars).and_then(|v| match v.strip_prefix(SECRET_PREFIX) {
+        Some(payload) if !literal => decrypt_secret(payload),
+        _ => Ok(v),
+    });
     match value {
         Ok(v) => Line::Pair { key, value: v },
         Err(msg) => Line::Err(msg),
@@ -230,7 +1571,7 @@ fn is_valid_key(s: &str) -> bool {
 }
 
 
-fn unquote_and_unescape(raw: &str) -> Result<String, String> {
+fn unquote_and_unescape(raw: &str, vars: &HashMap<String, String>) -> Result<String, String> {
     let raw = raw.trim();
 
     if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
@@ -238,30 +1579,97 @@ fn unquote_and_unescape(raw: &str) -> Result<String, String> {
     }
 
     if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
-        let s = &raw[1..raw.len() - 1];
-        let mut out = String::with_capacity(s.len());
-        let mut chars = s.chars();
-        while let Some(c) = chars.next() {
-            if c == '\\' {
-                match chars.next() {
-                    Some('n') => out.push('\n'),
-                    Some('r') => out.push('\r'),
-                    Some('t') => out.push('\t'),
-                    Some('0') => out.push('\0'),
-                    Some('"') => out.push('"'),
-                    Some('\'') => out.push('\''),
-                    Some('\\') => out.push('\\'),
-                    Some(other) => { out.push('\\'); out.push(other); } 
-                    None => out.push('\\'),
-                }
-            } else {
-                out.push(c);
+        return expand_value(&raw[1..raw.len() - 1], true, vars);
+    }
+    expand_value(raw, false, vars)
+}
+
+/// Processes escapes (all of them when `quoted`, otherwise only `\$`) and
+/// variable references in a value.
+fn expand_value(s: &str, quoted: bool, vars: &HashMap<String, String>) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' => {
+                let rest = chars.as_str();
+                let (value, consumed) = expand_reference(rest, quoted, vars)?;
+                out.push_str(&value);
+                chars = rest[consumed..].chars();
             }
+            // Unquoted values keep their backslashes, except before `$`.
+            '\\' if !quoted => match chars.clone().next() {
+                Some('$') => { chars.next(); out.push('$'); }
+                _ => out.push('\\'),
+            },
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some('t') => out.push('\t'),
+                Some('0') => out.push('\0'),
+                Some('"') => out.push('"'),
+                Some('\'') => out.push('\''),
+                Some('\\') => out.push('\\'),
+                Some('$') => out.push('$'),
+                Some(other) => { out.push('\\'); out.push(other); }
+                None => out.push('\\'),
+            },
+            _ => out.push(c),
         }
-        return Ok(out);
     }
+    Ok(out)
+}
 
-    Ok(raw.to_string())
+/// Expands the variable reference at the start of `s`, just after a `$`:
+/// `NAME`, `{NAME}`, `{NAME:-default}` (used when unset or empty), or
+/// `{NAME:?message}` (an error when unset or empty). Returns the value and
+/// the number of bytes used. A `$` not followed by a name is kept as is.
+/// Defaults are expanded like the value around them.
+fn expand_reference(s: &str, quoted: bool, vars: &HashMap<String, String>) -> Result<(String, usize), String> {
+    if let Some(body) = s.strip_prefix('{') {
+        // Find the matching brace, as a default may hold references itself.
+        let mut depth = 0usize;
+        let end = body.char_indices()
+            .find(|&(_, c)| match c {
+                '{' => { depth += 1; false }
+                '}' if depth == 0 => true,
+                '}' => { depth -= 1; false }
+                _ => false,
+            })
+            .map(|(i, _)| i)
+            .ok_or("unterminated '${'")?;
+        let body = &body[..end];
+        let name_len = body.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(body.len());
+        let (name, modifier) = body.split_at(name_len);
+        if !is_valid_key(name) {
+            return Err(format!("invalid variable name '{}'", name));
+        }
+
+        let value = lookup_var(name, vars);
+        let value = match modifier {
+            "" => value,
+            _ if !value.is_empty() && (modifier.starts_with(":-") || modifier.starts_with(":?")) => value,
+            _ if modifier.starts_with(":-") => expand_value(&modifier[2..], quoted, vars)?,
+            ":?" => return Err(format!("required variable '{}' is not set", name)),
+            _ if modifier.starts_with(":?") => return Err(format!("{}: {}", name, &modifier[2..])),
+            _ => return Err(format!("invalid expansion '${{{}}}'", body)),
+        };
+        return Ok((value, end + 2));
+    }
+
+    let len = s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(s.len());
+    if !is_valid_key(&s[..len]) {
+        return Ok(("$".into(), 0));
+    }
+    Ok((lookup_var(&s[..len], vars), len))
+}
+
+/// Looks a variable up in the keys defined so far, then in the process
+/// environment. Unset variables expand to an empty string, as in a shell.
+fn lookup_var(name: &str, vars: &HashMap<String, String>) -> String {
+    vars.get(name).cloned()
+        .or_else(|| env::var(name).ok())
+        .unwrap_or_default()
 }
 
 fn strip_inline_comment_if_unquoted(val: &str) -> String {
@@ -361,6 +1769,573 @@ mod tests {
         assert_eq!(parse_line("X=\"quote: \\\"\"").unwrap_pair().1, "quote: \"");
     }
 
+    #[test]
+    fn variable_expansion() {
+        let _lock = lock();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::set_var("DOTENV_TEST_HOST", "example.com");
+            env::remove_var("DOTENV_TEST_UNSET");
+        }
+
+        let map = parse_reader("PORT=8080\n\
+            URL=http://$DOTENV_TEST_HOST:${PORT}/\n\
+            QUOTED=\"${PORT}\\t$PORT\"\n\
+            LITERAL='${PORT}'\n\
+            ESCAPED=\\$PORT \"\\${PORT}\"\n\
+            MISSING=[$DOTENV_TEST_UNSET]\n\
+            PRICE=$5 costs$\n".as_bytes()).unwrap();
+        assert_eq!(map["URL"], "http://example.com:8080/");
+        assert_eq!(map["QUOTED"], "8080\t8080");
+        assert_eq!(map["LITERAL"], "${PORT}");
+        assert_eq!(map["ESCAPED"], "$PORT \"${PORT}\"");
+        assert_eq!(map["MISSING"], "[]");
+        assert_eq!(map["PRICE"], "$5 costs$");
+
+        assert!(parse_reader("A=${PORT".as_bytes()).is_err());
+        assert!(parse_reader("A=${1X}".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn default_and_required_expansion() {
+        let _lock = lock();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::remove_var("DOTENV_TEST_UNSET");
+        }
+
+        let map = parse_reader("EMPTY=\n\
+            NAME=app\n\
+            A=${DOTENV_TEST_UNSET:-fallback value}\n\
+            B=${EMPTY:-${NAME}-${DOTENV_TEST_UNSET:-x}}\n\
+            C=${NAME:-unused}\n\
+            D=\"${EMPTY:-tab\\there}\"\n\
+            E=${NAME:?must be set}\n\
+            F=${EMPTY:-a\\tb}\n".as_bytes()).unwrap();
+        assert_eq!(map["A"], "fallback value");
+        assert_eq!(map["B"], "app-x");
+        assert_eq!(map["C"], "app");
+        assert_eq!(map["D"], "tab\there");
+        assert_eq!(map["E"], "app");
+        assert_eq!(map["F"], "a\\tb");
+
+        let err = parse_reader("X=1\nY=${DOTENV_TEST_UNSET:?set it in .env}\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "parse error at line 2: DOTENV_TEST_UNSET: set it in .env");
+        let err = parse_reader("Y=${DOTENV_TEST_UNSET:?}".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("required variable 'DOTENV_TEST_UNSET'"));
+        assert!(parse_reader("Y=${NAME-x}".as_bytes()).is_err());
+        assert!(parse_reader("Y=${NAME:-${A}".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn multi_line_quoted_values() {
+        let src = "KEY=\"-----BEGIN KEY-----\r\nabc\\\"def\n\n  ghi\n-----END KEY-----\"\n\
+                   SINGLE='one\ntwo'\n\
+                   # a comment with a \" quote\n\
+                   NEXT=1\n";
+        let map = parse_reader(src.as_bytes()).unwrap();
+        assert_eq!(map["KEY"], "-----BEGIN KEY-----\nabc\"def\n\n  ghi\n-----END KEY-----");
+        assert_eq!(map["SINGLE"], "one\ntwo");
+        assert_eq!(map["NEXT"], "1");
+
+        let err = parse_reader("A=1\nB=\"open\nC=2\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "parse error at line 2: unterminated quoted value");
+    }
+
+    #[test]
+    fn parse_only_leaves_env_untouched() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let file = dir.path().join("parse.env");
+        fs::write(&file, "DOTENV_TEST_PARSED=1\nOTHER=\"a\"\n").unwrap();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::remove_var("DOTENV_TEST_PARSED");
+        }
+
+        let map = parse_file(&file).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["DOTENV_TEST_PARSED"], "1");
+        assert!(env::var("DOTENV_TEST_PARSED").is_err());
+        assert_eq!(parse_str("OTHER=\"a\"").unwrap()["OTHER"], "a");
+
+        fs::write(&file, "OK=1\nbroken\n").unwrap();
+        let err = parse_file(&file).unwrap_err().to_string();
+        assert_eq!(err, format!("parse error in {} at line 2: missing '='", file.display()));
+        assert!(matches!(parse_file(dir.path().join("missing.env")), Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn load_from_reader_and_str() {
+        let _lock = lock();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::remove_var("DOTENV_TEST_READER");
+            env::set_var("DOTENV_TEST_STR", "pre");
+        }
+
+        let reader = io::BufReader::new("DOTENV_TEST_READER=from reader\n".as_bytes());
+        assert_eq!(dotenv_from_reader(reader).unwrap(), 1);
+        assert_eq!(env::var("DOTENV_TEST_READER").unwrap(), "from reader");
+
+        assert_eq!(dotenv_from_str("DOTENV_TEST_STR=new\nDOTENV_TEST_READER=again").unwrap(), 0);
+        assert_eq!(env::var("DOTENV_TEST_STR").unwrap(), "pre");
+        assert!(dotenv_from_str("=").is_err());
+    }
+
+    #[test]
+    fn layered_files_and_sources() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let base = dir.path().join(".env");
+        let local = dir.path().join(".env.local");
+        fs::write(&base, "DOTENV_TEST_L1=base\nDOTENV_TEST_L2=base\nDOTENV_TEST_L3=base\n").unwrap();
+        fs::write(&local, "DOTENV_TEST_L2=local+${DOTENV_TEST_L1}\nDOTENV_TEST_L3=local\n").unwrap();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::remove_var("DOTENV_TEST_L1");
+            env::remove_var("DOTENV_TEST_L2");
+            env::set_var("DOTENV_TEST_L3", "pre");
+        }
+
+        let missing = dir.path().join(".env.production");
+        let sources = dotenv_layered(&[&base, &local, &missing]).unwrap();
+        assert_eq!(env::var("DOTENV_TEST_L1").unwrap(), "base");
+        assert_eq!(env::var("DOTENV_TEST_L2").unwrap(), "local+base");
+        assert_eq!(env::var("DOTENV_TEST_L3").unwrap(), "pre");
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources["DOTENV_TEST_L1"], base);
+        assert_eq!(sources["DOTENV_TEST_L2"], local);
+
+        fs::write(&local, "broken\n").unwrap();
+        assert!(matches!(dotenv_layered(&[&base, &local]), Err(Error::Parse { line: 1, .. })));
+    }
+
+    #[test]
+    fn profile_layers_over_base() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let nested = dir.path().join("src");
+        fs::create_dir(&nested).unwrap();
+        fs::write(dir.path().join(".env"), "DOTENV_TEST_P1=base\nDOTENV_TEST_P2=base\n").unwrap();
+        fs::write(dir.path().join(".env.staging"), "DOTENV_TEST_P2=staging\n").unwrap();
+        let clear = || {
+            // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+            unsafe {
+                env::remove_var("DOTENV_TEST_P1");
+                env::remove_var("DOTENV_TEST_P2");
+            }
+        };
+
+        clear();
+        let sources = dotenv_profile_impl(Some(nested.clone()), Some("staging")).unwrap();
+        assert_eq!(env::var("DOTENV_TEST_P1").unwrap(), "base");
+        assert_eq!(env::var("DOTENV_TEST_P2").unwrap(), "staging");
+        assert_eq!(sources["DOTENV_TEST_P2"], dir.path().join(".env.staging"));
+
+        clear();
+        dotenv_profile_impl(Some(nested.clone()), None).unwrap();
+        assert_eq!(env::var("DOTENV_TEST_P2").unwrap(), "base");
+
+        // A profile without its own file still loads `.env`.
+        clear();
+        dotenv_profile_impl(Some(nested), Some("production")).unwrap();
+        assert_eq!(env::var("DOTENV_TEST_P2").unwrap(), "base");
+        clear();
+    }
+
+    #[test]
+    fn schema_reports_all_errors() {
+        let schema = EnvSchema::new()
+            .required("HOST", VarType::String)
+            .required("PORT", VarType::Integer)
+            .required("TOKEN", VarType::String)
+            .optional("DEBUG", VarType::Bool)
+            .optional("RATIO", VarType::Float)
+            .one_of("MODE", &["dev", "prod"]);
+
+        let vars = parse_str("HOST=localhost\nPORT=80\nTOKEN=x\nDEBUG=Yes\nRATIO=0.5\nMODE=prod").unwrap();
+        assert_eq!(schema.validate_map(&vars), Ok(()));
+        assert_eq!(schema.validate_map(&parse_str("HOST=h\nPORT=1\nTOKEN=t").unwrap()), Ok(()));
+
+        let vars = parse_str("PORT=eighty\nTOKEN=\nDEBUG=maybe\nMODE=test").unwrap();
+        let errors = schema.validate_map(&vars).unwrap_err();
+        assert_eq!(errors, [
+            SchemaError::Missing { key: "HOST".into() },
+            SchemaError::Invalid { key: "PORT".into(), value: "eighty".into(), expected: VarType::Integer },
+            SchemaError::Missing { key: "TOKEN".into() },
+            SchemaError::Invalid { key: "DEBUG".into(), value: "maybe".into(), expected: VarType::Bool },
+            SchemaError::NotAllowed { key: "MODE".into(), value: "test".into(), allowed: vec!["dev".into(), "prod".into()] },
+        ]);
+        assert_eq!(errors[1].to_string(), "invalid PORT=\"eighty\": expected integer");
+        assert_eq!(errors[4].to_string(), "invalid MODE=\"test\": expected one of dev, prod");
+    }
+
+    #[test]
+    fn schema_validates_environment() {
+        let _lock = lock();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::set_var("DOTENV_TEST_S1", "42");
+            env::remove_var("DOTENV_TEST_S2");
+        }
+        let schema = EnvSchema::new().required("DOTENV_TEST_S1", VarType::Integer);
+        assert_eq!(schema.validate(), Ok(()));
+        let schema = schema.required("DOTENV_TEST_S2", VarType::String);
+        assert_eq!(schema.validate(), Err(vec![SchemaError::Missing { key: "DOTENV_TEST_S2".into() }]));
+    }
+
+    #[test]
+    fn env_file_preserves_layout() {
+        let src = "# Database\nexport DB_HOST = \"db\"  \nDB_PASS='s3cret'\n\nPORT=80 # http\nKEY=\"line1\nline2\"\n";
+        let mut file = EnvFile::parse(src).unwrap();
+        assert_eq!(file.to_string(), src);
+
+        file.set("DB_HOST", "db.internal").unwrap();
+        file.set("DB_PASS", "it's").unwrap();
+        file.set("PORT", "8080 8443").unwrap();
+        file.set("KEY", "a\nb$c").unwrap();
+        file.set("NEW", "").unwrap();
+        assert!(!file.remove("MISSING"));
+        let out = file.to_string();
+        assert_eq!(out, "# Database\nexport DB_HOST = \"db.internal\"  \nDB_PASS=\"it's\"\n\n# http\nPORT=\"8080 8443\"\nKEY=\"a\\nb\\$c\"\nNEW=\n");
+
+        let vars = parse_str(&out).unwrap();
+        assert_eq!(vars["DB_PASS"], "it's");
+        assert_eq!(vars["PORT"], "8080 8443");
+        assert_eq!(vars["KEY"], "a\nb$c");
+
+        assert!(file.remove("KEY"));
+        assert!(!file.to_string().contains("KEY"));
+        assert!(matches!(EnvFile::parse("A=1\nbad line\n"), Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(EnvFile::parse("A=\"open\n"), Err(Error::Parse { line: 1, .. })));
+    }
+
+    #[test]
+    fn env_file_repeated_edits_keep_moved_comments() {
+        let mut file = EnvFile::parse("PORT=8080 # default=1\n").unwrap();
+        file.set("PORT", "a b").unwrap();
+        file.set("PORT", "x").unwrap();
+        assert_eq!(file.to_string(), "# default=1\nPORT=\"x\"\n");
+        assert_eq!(parse_str(&file.to_string()).unwrap()["PORT"], "x");
+
+        assert!(matches!(file.set("BAD KEY", "1"), Err(Error::InvalidKey { .. })));
+        assert!(matches!(file.set("", "1"), Err(Error::InvalidKey { .. })));
+        assert_eq!(file.to_string(), "# default=1\nPORT=\"x\"\n");
+    }
+
+    #[test]
+    fn write_and_edit_files() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let path = dir.path().join(".env");
+        let vars = HashMap::from([
+            ("B".to_string(), "two words".to_string()),
+            ("A".to_string(), "1".to_string()),
+        ]);
+        write(&path, &vars).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "A=1\nB=\"two words\"\n");
+        assert_eq!(parse_file(&path).unwrap(), vars);
+
+        let mut file = EnvFile::open(&path).unwrap();
+        file.set("A", "2").unwrap();
+        file.save(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "A=2\nB=\"two words\"\n");
+        assert!(!dir.path().join(".env.tmp").exists());
+    }
+
+    #[test]
+    fn builder_options() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let nested = dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.path().join(".env.test"), "DOTENV_TEST_B1=found\nnot a pair\nDOTENV_TEST_B2=ok\n").unwrap();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::remove_var("DOTENV_TEST_B2");
+            env::set_var("DOTENV_TEST_B1", "pre");
+        }
+
+        let builder = Dotenv::new().filename(".env.test").start_dir(&nested);
+        assert!(matches!(builder.clone().max_walk_depth(1).find(), Err(Error::NotFound { .. })));
+        assert_eq!(builder.clone().max_walk_depth(2).find().unwrap(), dir.path().join(".env.test"));
+        assert!(matches!(builder.clone().load(), Err(Error::Parse { line: 2, .. })));
+
+        assert_eq!(builder.clone().strict(false).load().unwrap(), 1);
+        assert_eq!(env::var("DOTENV_TEST_B1").unwrap(), "pre");
+        assert_eq!(env::var("DOTENV_TEST_B2").unwrap(), "ok");
+        assert_eq!(builder.strict(false).overwrite(true).load().unwrap(), 2);
+        assert_eq!(env::var("DOTENV_TEST_B1").unwrap(), "found");
+    }
+
+    #[test]
+    fn duplicates_and_error_report() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let path = dir.path().join(".env");
+        fs::write(&path, "DOTENV_TEST_D=1\nbad\nDOTENV_TEST_D=2\n9X=3\nDOTENV_TEST_E=\"open\n").unwrap();
+
+        let builder = Dotenv::new().path(&path).deny_duplicates(true);
+        assert!(matches!(builder.clone().strict(false).load(), Err(Error::Parse { line: 3, .. })));
+
+        let err = builder.clone().collect_errors(true).load().unwrap_err();
+        let Error::Report { path: Some(p), errors } = &err else { panic!("{err:?}") };
+        assert_eq!(p, &path);
+        let lines: Vec<usize> = errors.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [2, 3, 4, 5]);
+        assert_eq!(errors[1].1, "duplicate key DOTENV_TEST_D");
+        assert!(err.to_string().starts_with(&format!("4 parse errors in {}: line 2: missing '='; line 3:", path.display())));
+
+        fs::write(&path, "DOTENV_TEST_D=1\nDOTENV_TEST_D=2\n").unwrap();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::remove_var("DOTENV_TEST_D"); }
+        assert_eq!(Dotenv::new().path(&path).collect_errors(true).load().unwrap(), 1);
+        assert_eq!(env::var("DOTENV_TEST_D").unwrap(), "2");
+    }
+
+    #[test]
+    fn watch_reports_changes() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let path = dir.path().join(".env");
+        fs::write(&path, "A=1\n").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = watch(&path, Duration::from_millis(5), move |vars| { let _ = tx.send(vars); });
+        thread::sleep(Duration::from_millis(20));
+        assert!(rx.try_recv().is_err());
+
+        fs::write(&path, "A=2\nB=3\n").unwrap();
+        let vars = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert_eq!(vars["A"], "2");
+        assert_eq!(vars["B"], "3");
+
+        fs::remove_file(&path).unwrap();
+        let err = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap_err();
+        assert!(matches!(err, Error::Io { .. }));
+
+        watcher.stop();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_err());
+    }
+
+    #[test]
+    fn iter_yields_entries_lazily() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let path = dir.path().join(".env");
+        fs::write(&path, "# app\nMYAPP_HOST=h\nOTHER=x\nbad\nMYAPP_URL=\"${MYAPP_HOST}:${OTHER}\n\"\n").unwrap();
+
+        let items: Vec<_> = dotenv_iter(&path).unwrap().collect();
+        assert_eq!(items.len(), 4);
+        assert!(matches!(&items[2], Err(Error::Parse { path: Some(p), line: 4, .. }) if p == &path));
+
+        let vars: Vec<(String, String)> = dotenv_iter(&path).unwrap()
+            .filter_map(Result::ok)
+            .filter(|(key, _)| key.starts_with("MYAPP_"))
+            .collect();
+        assert_eq!(vars, [("MYAPP_HOST".into(), "h".into()), ("MYAPP_URL".into(), "h:x\n".into())]);
+
+        assert!(matches!(dotenv_iter(dir.path().join("missing")), Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn secret_values() {
+        let _lock = lock();
+        let key = generate_key().unwrap();
+        assert_eq!(key.len(), 64);
+        let first = encrypt_value("p@ss word\n", &key).unwrap();
+        let second = encrypt_value("p@ss word\n", &key).unwrap();
+        assert!(first.starts_with("secret:"));
+        assert_ne!(first, second);
+        assert!(matches!(encrypt_value("x", "abc"), Err(Error::Secret { .. })));
+
+        let src = format!("A={}\nB=\"{}\"\nC='secret:sauce'", first, second);
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::remove_var(KEY_VAR); }
+        assert!(matches!(parse_str(&src), Err(Error::Parse { line: 1, .. })));
+
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::set_var(KEY_VAR, &key); }
+        let vars = parse_str(&src).unwrap();
+        assert_eq!(vars["A"], "p@ss word\n");
+        assert_eq!(vars["B"], "p@ss word\n");
+        assert_eq!(vars["C"], "secret:sauce");
+
+        let mut tampered = first.clone().into_bytes();
+        let mid = tampered.len() / 2;
+        tampered[mid] = if tampered[mid] == b'A' { b'B' } else { b'A' };
+        assert!(parse_str(&format!("A={}", String::from_utf8(tampered).unwrap())).is_err());
+        assert!(parse_str("A=secret:not*base64").is_err());
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::remove_var(KEY_VAR); }
+        assert_eq!(parse_str("HINT='secret:sauce'").unwrap()["HINT"], "secret:sauce");
+        assert_eq!(parse_str(&format!("A='{}'", first)).unwrap()["A"], first);
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::set_var(KEY_VAR, &key); }
+
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::set_var(KEY_VAR, generate_key().unwrap()); }
+        let err = parse_str(&format!("A={}", first)).unwrap_err();
+        assert!(err.to_string().contains("wrong key"));
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::remove_var(KEY_VAR); }
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        for (raw, encoded) in [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foobar", "Zm9vYmFy")] {
+            assert_eq!(base64_encode(raw.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), raw.as_bytes());
+        }
+        assert_eq!(base64_decode("Zg="), None);
+        assert_eq!(base64_decode("Z==="), None);
+    }
+
+    #[test]
+    fn command_sets_child_env_only() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let path = dir.path().join(".env");
+        fs::write(&path, "DOTENV_TEST_C1=file\nDOTENV_TEST_C2=file\n").unwrap();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::remove_var("DOTENV_TEST_C1");
+            env::set_var("DOTENV_TEST_C2", "pre");
+        }
+
+        let child_env = |command: &Command| -> HashMap<String, String> {
+            command.get_envs()
+                .filter_map(|(k, v)| Some((k.to_str()?.to_string(), v?.to_str()?.to_string())))
+                .collect()
+        };
+        let command = Dotenv::new().path(&path).command("true").unwrap();
+        assert_eq!(command.get_program(), "true");
+        assert_eq!(child_env(&command), HashMap::from([("DOTENV_TEST_C1".into(), "file".into())]));
+        assert!(env::var_os("DOTENV_TEST_C1").is_none());
+
+        let command = Dotenv::new().path(&path).overwrite(true).command("true").unwrap();
+        assert_eq!(child_env(&command)["DOTENV_TEST_C2"], "file");
+        assert_eq!(env::var("DOTENV_TEST_C2").unwrap(), "pre");
+
+        #[cfg(unix)]
+        {
+            let output = Dotenv::new().path(&path).command("sh").unwrap()
+                .args(["-c", "printf %s \"$DOTENV_TEST_C1-$DOTENV_TEST_C2\""])
+                .output()
+                .unwrap();
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), "file-pre");
+        }
+    }
+
+    #[test]
+    fn export_quotes_for_each_shell() {
+        let vars = HashMap::from([
+            ("A".to_string(), "plain".to_string()),
+            ("B".to_string(), "it's a \\ $x".to_string()),
+        ]);
+        assert_eq!(export_script(&vars, Shell::Posix).unwrap(), "export A='plain'\nexport B='it'\\''s a \\ $x'\n");
+        assert_eq!(export_script(&vars, Shell::Fish).unwrap(), "set -gx A 'plain'\nset -gx B 'it\\'s a \\\\ $x'\n");
+        assert_eq!(export_script(&vars, Shell::PowerShell).unwrap(), "$env:A = 'plain'\n$env:B = 'it''s a \\ $x'\n");
+        assert_eq!(export_script(&vars, Shell::DockerEnv).unwrap(), "A=plain\nB=it's a \\ $x\n");
+
+        let multi = HashMap::from([("PEM".to_string(), "a\nb".to_string())]);
+        assert_eq!(export_script(&multi, Shell::Posix).unwrap(), "export PEM='a\nb'\n");
+        assert!(matches!(export_script(&multi, Shell::DockerEnv), Err(Error::Export { .. })));
+        let bad_key = HashMap::from([("1A".to_string(), String::new())]);
+        assert!(export_script(&bad_key, Shell::Posix).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn export_posix_round_trips_through_sh() {
+        let value = "it's \"quoted\" $HOME `cmd` \\n\nnext";
+        let vars = HashMap::from([("DOTENV_TEST_X".to_string(), value.to_string())]);
+        let script = export_script(&vars, Shell::Posix).unwrap() + "printf %s \"$DOTENV_TEST_X\"";
+        let output = Command::new("sh").args(["-c", &script]).output().unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), value);
+    }
+
+    #[test]
+    fn env_layers_file_and_process() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let path = dir.path().join(".env");
+        fs::write(&path, "DOTENV_TEST_V1=file\nDOTENV_TEST_V2=file\n").unwrap();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe {
+            env::remove_var("DOTENV_TEST_V1");
+            env::set_var("DOTENV_TEST_V2", "pre");
+            env::set_var("DOTENV_TEST_V3", "process");
+        }
+
+        let vars = Dotenv::new().path(&path).env().unwrap();
+        assert_eq!(vars.get("DOTENV_TEST_V1").unwrap(), "file");
+        assert_eq!(vars.get("DOTENV_TEST_V2").unwrap(), "pre");
+        assert_eq!(vars.get("DOTENV_TEST_V3").unwrap(), "process");
+        assert_eq!(vars.get("DOTENV_TEST_V4"), None);
+        assert_eq!(vars.vars().len(), 2);
+        assert!(env::var_os("DOTENV_TEST_V1").is_none());
+
+        let vars = Dotenv::new().path(&path).overwrite(true).env().unwrap();
+        assert_eq!(vars.get("DOTENV_TEST_V2").unwrap(), "file");
+        assert_eq!(vars.get("DOTENV_TEST_V3").unwrap(), "process");
+
+        let shared = std::sync::Arc::new(vars);
+        let reader = std::sync::Arc::clone(&shared);
+        assert_eq!(thread::spawn(move || reader.get("DOTENV_TEST_V1")).join().unwrap().unwrap(), "file");
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::remove_var("DOTENV_TEST_V3"); }
+    }
+
+    #[test]
+    fn bom_and_line_endings() {
+        let vars = parse_str("\u{feff}A=1\r\nB=\"x\r\ny\"\r\n\r\nC=3\rD=4\r").unwrap();
+        assert_eq!(vars["A"], "1");
+        assert_eq!(vars["B"], "x\ny");
+        assert_eq!(vars["C"], "3");
+        assert_eq!(vars["D"], "4");
+
+        let err = parse_str("\u{feff}A=1\rbad\r\nC=3").unwrap_err();
+        assert!(matches!(err, Error::Parse { line: 2, .. }));
+
+        let src = "\u{feff}# c\r\nA=1\r\nB=\"x\r\ny\"\r\n";
+        let mut file = EnvFile::parse(src).unwrap();
+        assert_eq!(file.to_string(), src);
+        file.set("A", "2").unwrap();
+        assert_eq!(file.to_string(), "\u{feff}# c\r\nA=2\r\nB=\"x\r\ny\"\r\n");
+        assert_eq!(EnvFile::parse("A=1\rB=2\r").unwrap().to_string(), "A=1\nB=2\n");
+    }
+
+    #[test]
+    fn invalid_encoding() {
+        let _lock = lock();
+        let dir = TempDir::new();
+        let path = dir.path().join(".env");
+        // "CAFÉ" in Latin-1 on the second line; the `É` follows a 3-byte BOM,
+        // 5 bytes of the first line, and 17 of its own line.
+        fs::write(&path, b"\xEF\xBB\xBFA=1\r\nDOTENV_TEST_N=CAF\xC9\r\n").unwrap();
+
+        let err = Dotenv::new().path(&path).load().unwrap_err();
+        let Error::Parse { line: 2, msg, .. } = &err else { panic!("{err:?}") };
+        assert_eq!(msg, "invalid UTF-8 at byte offset 25");
+
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::remove_var("DOTENV_TEST_N"); }
+        let vars = Dotenv::new().path(&path).strict(false).env().unwrap();
+        assert_eq!(vars.vars().len(), 1);
+        let vars = Dotenv::new().path(&path).latin1_fallback(true).env().unwrap();
+        assert_eq!(vars.get("DOTENV_TEST_N").unwrap(), "CAFÉ");
+
+        // A bad line inside a quoted value drops the whole entry, not just that line.
+        fs::write(&path, b"A=1\nDOTENV_TEST_M=\"x\nCAF\xC9\ny\"\nC=3\n").unwrap();
+        // SAFETY: Tests touching the environment hold `TEST_LOCK`.
+        unsafe { env::remove_var("DOTENV_TEST_M"); }
+        let vars = Dotenv::new().path(&path).strict(false).env().unwrap();
+        assert_eq!(vars.get("DOTENV_TEST_M"), None);
+        assert_eq!(vars.vars().len(), 2);
+    }
+
     #[test]
     fn apply_respects_overwrite() {
         let _lock = lock(); // <--- ACQUISISCE IL LOCK
@@ -0,0 +1,237 @@
+//! AES-256-GCM (FIPS 197, NIST SP 800-38D) with 96-bit nonces, for the
+//! `secret:` values of `.env` files.
+//!
+//! This is a compact table-based implementation: it is correct but not
+//! hardened against timing side channels, which is acceptable for decrypting
+//! configuration at startup but not for a network-facing service.
+
+/// Length of a key in bytes.
+pub(super) const KEY_LEN: usize = 32;
+/// Length of a nonce in bytes.
+pub(super) const NONCE_LEN: usize = 12;
+/// Length of an authentication tag in bytes.
+pub(super) const TAG_LEN: usize = 16;
+
+const ROUNDS: usize = 14;
+const SBOX: [u8; 256] = sbox();
+
+/// Encrypts `plaintext`, returning the ciphertext followed by the tag.
+pub(super) fn seal(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], plaintext: &[u8]) -> Vec<u8> {
+    let round_keys = expand_key(key);
+    let mut out = plaintext.to_vec();
+    ctr(&round_keys, nonce, &mut out);
+    let tag = tag(&round_keys, nonce, &out);
+    out.extend_from_slice(&tag);
+    out
+}
+
+/// Checks the tag of `sealed` (ciphertext followed by tag) and decrypts it.
+/// Returns `None` if the data was not sealed with this key and nonce.
+pub(super) fn open(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], sealed: &[u8]) -> Option<Vec<u8>> {
+    let split = sealed.len().checked_sub(TAG_LEN)?;
+    let (ciphertext, expected) = sealed.split_at(split);
+    let round_keys = expand_key(key);
+    // Compare without an early exit, so the time taken leaks nothing.
+    let diff = tag(&round_keys, nonce, ciphertext).iter().zip(expected).fold(0, |acc, (a, b)| acc | (a ^ b));
+    if diff != 0 {
+        return None;
+    }
+    let mut out = ciphertext.to_vec();
+    ctr(&round_keys, nonce, &mut out);
+    Some(out)
+}
+
+// --- Internal Helpers ---
+
+type RoundKeys = [[u8; 16]; ROUNDS + 1];
+
+/// XORs `data` with the key stream starting at counter 2 (counter 1 masks the tag).
+fn ctr(round_keys: &RoundKeys, nonce: &[u8; NONCE_LEN], data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(16).enumerate() {
+        let stream = encrypt_block(round_keys, counter_block(nonce, i as u32 + 2));
+        for (byte, s) in chunk.iter_mut().zip(stream) {
+            *byte ^= s;
+        }
+    }
+}
+
+/// Computes the GCM tag of `ciphertext`, without additional data.
+fn tag(round_keys: &RoundKeys, nonce: &[u8; NONCE_LEN], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+    let h = u128::from_be_bytes(encrypt_block(round_keys, [0; 16]));
+    let mut x = 0u128;
+    for chunk in ciphertext.chunks(16) {
+        let mut block = [0u8; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        x = gf_mul(x ^ u128::from_be_bytes(block), h);
+    }
+    x = gf_mul(x ^ (ciphertext.len() as u128 * 8), h);
+    let mask = u128::from_be_bytes(encrypt_block(round_keys, counter_block(nonce, 1)));
+    (x ^ mask).to_be_bytes()
+}
+
+fn counter_block(nonce: &[u8; NONCE_LEN], counter: u32) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..NONCE_LEN].copy_from_slice(nonce);
+    block[NONCE_LEN..].copy_from_slice(&counter.to_be_bytes());
+    block
+}
+
+/// Multiplies in GF(2^128) with GCM's bit order (SP 800-38D, algorithm 1).
+fn gf_mul(x: u128, y: u128) -> u128 {
+    let mut z = 0u128;
+    let mut v = y;
+    for i in 0..128 {
+        if (x >> (127 - i)) & 1 == 1 {
+            z ^= v;
+        }
+        v = if v & 1 == 1 { (v >> 1) ^ (0xe1 << 120) } else { v >> 1 };
+    }
+    z
+}
+
+fn expand_key(key: &[u8; KEY_LEN]) -> RoundKeys {
+    let mut words = [[0u8; 4]; 4 * (ROUNDS + 1)];
+    for (i, word) in key.chunks(4).enumerate() {
+        words[i].copy_from_slice(word);
+    }
+    let mut rcon = 1u8;
+    for i in 8..words.len() {
+        let mut t = words[i - 1];
+        if i % 8 == 0 {
+            t = [SBOX[t[1] as usize] ^ rcon, SBOX[t[2] as usize], SBOX[t[3] as usize], SBOX[t[0] as usize]];
+            rcon = xtime(rcon);
+        } else if i % 8 == 4 {
+            t = t.map(|b| SBOX[b as usize]);
+        }
+        for j in 0..4 {
+            words[i][j] = words[i - 8][j] ^ t[j];
+        }
+    }
+
+    let mut round_keys = [[0u8; 16]; ROUNDS + 1];
+    for (i, round_key) in round_keys.iter_mut().enumerate() {
+        for j in 0..4 {
+            round_key[4 * j..4 * j + 4].copy_from_slice(&words[4 * i + j]);
+        }
+    }
+    round_keys
+}
+
+/// Encrypts one block; byte `r + 4c` of the state is row `r`, column `c`.
+fn encrypt_block(round_keys: &RoundKeys, block: [u8; 16]) -> [u8; 16] {
+    let mut state = block;
+    add_round_key(&mut state, &round_keys[0]);
+    for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+        // SubBytes and ShiftRows: row `r` moves `r` columns to the left.
+        let prev = state;
+        for c in 0..4 {
+            for r in 0..4 {
+                state[r + 4 * c] = SBOX[prev[r + 4 * ((c + r) % 4)] as usize];
+            }
+        }
+        if round != ROUNDS {
+            for column in state.chunks_mut(4) {
+                let [a, b, c, d] = [column[0], column[1], column[2], column[3]];
+                let all = a ^ b ^ c ^ d;
+                column[0] ^= all ^ xtime(a ^ b);
+                column[1] ^= all ^ xtime(b ^ c);
+                column[2] ^= all ^ xtime(c ^ d);
+                column[3] ^= all ^ xtime(d ^ a);
+            }
+        }
+        add_round_key(&mut state, round_key);
+    }
+    state
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (s, k) in state.iter_mut().zip(round_key) {
+        *s ^= k;
+    }
+}
+
+/// Multiplies by `x` in AES's GF(2^8).
+const fn xtime(b: u8) -> u8 {
+    (b << 1) ^ if b & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+/// Builds the S-box: the multiplicative inverse followed by the affine map.
+const fn sbox() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut x = 0;
+    while x < 256 {
+        // The inverse is x^254; 0 maps to 0.
+        let mut inv = 1u8;
+        let mut i = 0;
+        while i < 254 {
+            inv = gf8_mul(inv, x as u8);
+            i += 1;
+        }
+        table[x] = inv ^ inv.rotate_left(1) ^ inv.rotate_left(2) ^ inv.rotate_left(3) ^ inv.rotate_left(4) ^ 0x63;
+        x += 1;
+    }
+    table
+}
+
+const fn gf8_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn block_cipher() {
+        // FIPS 197, appendix C.3.
+        let key: [u8; 32] = hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").try_into().unwrap();
+        let block = hex("00112233445566778899aabbccddeeff").try_into().unwrap();
+        assert_eq!(encrypt_block(&expand_key(&key), block).to_vec(), hex("8ea2b7ca516745bfeafc49904b496089"));
+        assert_eq!(SBOX[0], 0x63);
+        assert_eq!(SBOX[0x53], 0xed);
+    }
+
+    #[test]
+    fn gcm_vectors() {
+        // Test cases 13 to 15 of the GCM specification (McGrew and Viega).
+        let zero = [0u8; 32];
+        assert_eq!(seal(&zero, &[0; 12], &[]), hex("530f8afbc74536b9a963b4f1c4cb738b"));
+        assert_eq!(seal(&zero, &[0; 12], &[0; 16]), hex("cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919"));
+
+        let key = hex("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308").try_into().unwrap();
+        let nonce = hex("cafebabefacedbaddecaf888").try_into().unwrap();
+        let plaintext = hex("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+                             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255");
+        let sealed = seal(&key, &nonce, &plaintext);
+        assert_eq!(sealed, hex("522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+                                8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662898015ad\
+                                b094dac5d93471bdec1a502270e3cc6c"));
+        assert_eq!(open(&key, &nonce, &sealed), Some(plaintext));
+    }
+
+    #[test]
+    fn open_rejects_tampering() {
+        let key = [7u8; 32];
+        let nonce = [9u8; 12];
+        let mut sealed = seal(&key, &nonce, b"hunter2");
+        assert_eq!(open(&key, &nonce, &sealed).unwrap(), b"hunter2");
+        assert_eq!(open(&[8u8; 32], &nonce, &sealed), None);
+        sealed[0] ^= 1;
+        assert_eq!(open(&key, &nonce, &sealed), None);
+        assert_eq!(open(&key, &nonce, &sealed[..TAG_LEN - 1]), None);
+    }
+}
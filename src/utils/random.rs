@@ -1,7 +1,9 @@
 //! utils/random.rs
 //!
 //! Minimal, **non-cryptographic** pseudo-random utilities.
-//! Generates integers, decimals, and random choices.
+//! Generates integers, decimals, random choices, shuffles, and samples. The free functions use
+//! a per-thread generator, seeded from the clock once and then advanced on
+//! each call; create an [`Rng`] with a seed to get reproducible sequences.
 //!
 //! # Examples
 //! ```
@@ -19,7 +21,9 @@
 //! ```
 
 
+use std::cell::RefCell;
 use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::HashSet;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -53,19 +57,196 @@ fn prng(state: &mut u64) -> u64 {
     x.wrapping_mul(0x2545_F491_4F6C_DD1D)
 }
 
-fn generator_u128(thread_id: u64, ts_ns: u128) -> u128 {
-    let mut h: u64 = 0x9E37_79B9_7F4A_7C15;
-    mixer(&mut h, thread_id);
-    mixer(&mut h, (ts_ns >> 64) as u64);
-    mixer(&mut h, ts_ns as u64);
-    let mut state = if h == 0 { 1 } else { h };
+/// A seedable pseudo-random generator (xorshift64*).
+///
+/// The same seed always gives the same sequence, so simulations and tests
+/// can be reproduced. Not cryptographically secure.
+///
+/// # Examples
+/// ```
+/// use stdt::utils::random::Rng;
+/// let mut a = Rng::with_seed(42);
+/// let mut b = Rng::with_seed(42);
+/// assert_eq!(a.integer_in(1, 6), b.integer_in(1, 6));
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator seeded from the clock and the current thread.
+    pub fn new() -> Self {
+        let ts_ns = now_ns();
+        let mut h: u64 = 0x9E37_79B9_7F4A_7C15;
+        mixer(&mut h, thread_id());
+        mixer(&mut h, (ts_ns >> 64) as u64);
+        mixer(&mut h, ts_ns as u64);
+        Rng::from_state(h)
+    }
 
-    // Extend to 16 byte
-    let mut bytes = [0u8; 16];
-    bytes[..8].copy_from_slice(&prng(&mut state).to_be_bytes());
-    bytes[8..].copy_from_slice(&prng(&mut state).to_be_bytes());
+    /// Creates a generator whose sequence is determined by `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut h: u64 = 0x9E37_79B9_7F4A_7C15;
+        mixer(&mut h, seed);
+        Rng::from_state(h)
+    }
+
+    /// Returns the next random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        prng(&mut self.state)
+    }
+
+    /// Returns the next random `u128`, made of two `u64`.
+    pub fn next_u128(&mut self) -> u128 {
+        let high = self.next_u64() as u128;
+        high << 64 | self.next_u64() as u128
+    }
+
+    /// Returns a random `i128` uniformly in the **inclusive** range `[min, max]`.
+    ///
+    /// Panics if `min > max`.
+    pub fn integer_in(&mut self, min: i128, max: i128) -> i128 {
+        assert!(min <= max, "min must be <= max");
+
+        if min == max {
+            return min;
+        }
 
-    u128::from_be_bytes(bytes)
+        let seed = self.next_u128();
+        if min == i128::MIN && max == i128::MAX {
+            return seed as i128;
+        }
+
+        let start = (min as u128) ^ SIGN_MASK;
+        let end = (max as u128) ^ SIGN_MASK;
+        let width = (end - start) + 1;
+
+        let r = start + (seed % width);
+        (r ^ SIGN_MASK) as i128
+    }
+
+    /// Returns a random `f64` uniformly in the **inclusive** range `[start, end]` (within FP error).
+    ///
+    /// Panics if `start > end`.
+    pub fn decimal_in(&mut self, start: f64, end: f64) -> f64 {
+        assert!(start <= end, "start must be <= end");
+        if start == end {
+            return start;
+        }
+
+        let mant: u64 = self.next_u64() >> (64 - 53);
+        let unit: f64 = (mant as f64) * (1.0 / ((1u64 << 53) as f64));
+
+        start + (end - start) * unit
+    }
+
+    /// Returns a random reference to an element of `slice`, or `None` if empty.
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            return None;
+        }
+        let idx = self.integer_in(0, (slice.len() - 1) as i128) as usize;
+        slice.get(idx)
+    }
+
+    /// Returns a random item from any iterable by collecting it into a `Vec`.
+    ///
+    /// Returns `None` if the iterator yields no items.
+    pub fn choose_iter<I>(&mut self, iter: I) -> Option<I::Item>
+    where I: IntoIterator,
+    {
+        let v: Vec<I::Item> = iter.into_iter().collect();
+        if v.is_empty() {
+            return None;
+        }
+        let idx = self.integer_in(0, (v.len() - 1) as i128) as usize;
+        v.into_iter().nth(idx)
+    }
+
+    /// Shuffles `slice` in place, every order being equally likely (Fisher–Yates).
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        self.partial_shuffle(slice, slice.len());
+    }
+
+    /// Moves `k` randomly chosen elements, in random order, to the front of
+    /// `slice` and returns them; the rest of the slice is left in some order.
+    /// With `k` past the length, the whole slice is shuffled.
+    pub fn partial_shuffle<'a, T>(&mut self, slice: &'a mut [T], k: usize) -> &'a mut [T] {
+        let k = k.min(slice.len());
+        for i in 0..k {
+            let j = i + self.below(slice.len() - i);
+            slice.swap(i, j);
+        }
+        &mut slice[..k]
+    }
+
+    /// Returns `k` distinct elements of `slice` (all of them if `k` is past its
+    /// length). Every subset of `k` elements is equally likely, and so is
+    /// every order of the result.
+    pub fn sample<'a, T>(&mut self, slice: &'a [T], k: usize) -> Vec<&'a T> {
+        self.sample_indices(slice.len(), k).into_iter().map(|i| &slice[i]).collect()
+    }
+
+    /// Returns `k` distinct indices in `0..len` (all of them if `k` is past
+    /// `len`), with the same guarantees as [`Rng::sample`].
+    pub fn sample_indices(&mut self, len: usize, k: usize) -> Vec<usize> {
+        let k = k.min(len);
+        if k.saturating_mul(4) >= len {
+            let mut indices: Vec<usize> = (0..len).collect();
+            self.partial_shuffle(&mut indices, k);
+            indices.truncate(k);
+            return indices;
+        }
+
+        // Floyd's algorithm needs memory for the `k` picks only, not all of `len`.
+        let mut chosen = HashSet::with_capacity(k);
+        let mut indices = Vec::with_capacity(k);
+        for j in len - k..len {
+            let t = self.below(j + 1);
+            let pick = if chosen.insert(t) { t } else { j };
+            chosen.insert(pick);
+            indices.push(pick);
+        }
+        self.shuffle(&mut indices);
+        indices
+    }
+
+    // --- Internal Helpers ---
+
+    /// Returns a value uniformly in `0..n`, for `n > 0`, without the bias of a
+    /// plain modulo: draws in the incomplete last block of `n` are rejected.
+    fn below(&mut self, n: usize) -> usize {
+        let n = n as u64;
+        let limit = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < limit {
+                return (x % n) as usize;
+            }
+        }
+    }
+
+    fn from_state(h: u64) -> Self {
+        // xorshift never leaves the all-zero state.
+        Rng { state: if h == 0 { 1 } else { h } }
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Rng::new()
+    }
+}
+
+thread_local! {
+    static THREAD_RNG: RefCell<Rng> = RefCell::new(Rng::new());
+}
+
+/// Runs `f` with the generator of the current thread.
+fn with_thread_rng<R>(f: impl FnOnce(&mut Rng) -> R) -> R {
+    THREAD_RNG.with_borrow_mut(f)
 }
 
 /// Returns a random `i128` uniformly in the **inclusive** range `[min, max]`.
@@ -79,24 +260,7 @@ fn generator_u128(thread_id: u64, ts_ns: u128) -> u128 {
 /// assert!((-2..=2).contains(&x));
 /// ```
 pub fn integer_in(min: i128, max: i128) -> i128{
-    assert!(min <= max, "min must be <= max");
-
-    if min == max {
-        return min;
-    }
-    
-    let seed = generator_u128(thread_id(), now_ns());
-    if min == i128::MIN && max == i128::MAX {
-        return seed as i128;
-    }
-
-    let start = (min as u128) ^ SIGN_MASK;
-    let end = (max as u128) ^ SIGN_MASK;
-    let width = (end - start) + 1;
-
-    
-    let r = start + (seed % width);
-    (r ^ SIGN_MASK) as i128
+    with_thread_rng(|rng| rng.integer_in(min, max))
 }
 
 /// Returns a random `f64` uniformly in the **inclusive** range `[start, end]` (within FP error).
@@ -110,17 +274,7 @@ pub fn integer_in(min: i128, max: i128) -> i128{
 /// assert!(x >= 0.0 - f64::EPSILON && x <= 1.0 + f64::EPSILON);
 /// ```
 pub fn decimal_in(start: f64, end: f64) -> f64{
-    assert!(start <= end, "start must be <= end");
-    if start == end {
-        return start;
-    }
-
-    let seed = generator_u128(thread_id(), now_ns());
-
-    let mant: u64 = (seed >> (128 - 53)) as u64;
-    let unit: f64 = (mant as f64) * (1.0 / ((1u64 << 53) as f64)); 
-
-    start + (end - start) * unit
+    with_thread_rng(|rng| rng.decimal_in(start, end))
 }
 
 /// Returns a random reference to an element of `slice`, or `None` if empty.
@@ -133,11 +287,7 @@ pub fn decimal_in(start: f64, end: f64) -> f64{
 /// assert!(pick.is_some() && xs.contains(pick.unwrap()));
 /// ```
 pub fn choose<T>(slice: &[T]) -> Option<&T>{
-    if slice.is_empty() {
-        return None;
-    }
-    let idx = integer_in(0, (slice.len() - 1) as i128) as usize;
-    slice.get(idx)
+    with_thread_rng(|rng| rng.choose(slice))
 }
 
 /// Returns a random item from any iterable by collecting it into a `Vec`.
@@ -154,12 +304,66 @@ pub fn choose<T>(slice: &[T]) -> Option<&T>{
 pub fn choose_iter<I>(iter: I) -> Option<I::Item>
 where I: IntoIterator,
 {
-    let v: Vec<I::Item> = iter.into_iter().collect();
-    if v.is_empty() {
-        return None;
-    }
-    let idx = integer_in(0, (v.len() - 1) as i128) as usize;
-    v.into_iter().nth(idx)
+    // Collect before borrowing the generator: the iterator may itself call
+    // the functions of this module.
+    let items: Vec<I::Item> = iter.into_iter().collect();
+    with_thread_rng(|rng| rng.choose_iter(items))
+}
+
+/// Shuffles `slice` in place, every order being equally likely (Fisher–Yates).
+///
+/// # Examples
+/// ```
+/// use stdt::utils::random::shuffle;
+/// let mut xs = [1, 2, 3, 4, 5];
+/// shuffle(&mut xs);
+/// xs.sort();
+/// assert_eq!(xs, [1, 2, 3, 4, 5]);
+/// ```
+pub fn shuffle<T>(slice: &mut [T]) {
+    with_thread_rng(|rng| rng.shuffle(slice))
+}
+
+/// Moves `k` randomly chosen elements, in random order, to the front of
+/// `slice` and returns them, without shuffling the rest.
+///
+/// # Examples
+/// ```
+/// use stdt::utils::random::partial_shuffle;
+/// let mut xs = [1, 2, 3, 4, 5];
+/// let picked = partial_shuffle(&mut xs, 2);
+/// assert_eq!(picked.len(), 2);
+/// ```
+pub fn partial_shuffle<T>(slice: &mut [T], k: usize) -> &mut [T] {
+    with_thread_rng(|rng| rng.partial_shuffle(slice, k))
+}
+
+/// Returns `k` distinct elements of `slice` (all of them if `k` is past its
+/// length), every subset being equally likely.
+///
+/// # Examples
+/// ```
+/// use stdt::utils::random::sample;
+/// let xs = [10, 20, 30, 40];
+/// let picked = sample(&xs, 2);
+/// assert_eq!(picked.len(), 2);
+/// assert_ne!(picked[0], picked[1]);
+/// ```
+pub fn sample<T>(slice: &[T], k: usize) -> Vec<&T> {
+    with_thread_rng(|rng| rng.sample(slice, k))
+}
+
+/// Returns `k` distinct indices in `0..len` (all of them if `k` is past
+/// `len`), every subset being equally likely.
+///
+/// # Examples
+/// ```
+/// use stdt::utils::random::sample_indices;
+/// let picked = sample_indices(1_000_000, 3);
+/// assert!(picked.iter().all(|i| *i < 1_000_000));
+/// ```
+pub fn sample_indices(len: usize, k: usize) -> Vec<usize> {
+    with_thread_rng(|rng| rng.sample_indices(len, k))
 }
 
 #[cfg(test)]
@@ -200,11 +404,12 @@ mod tests {
     fn decimal_in_respects_range() {
         for _ in 0..1_000 {
             let x = decimal_in(-0.1, 0.1);
-            assert!(x >= -0.1 - f64::EPSILON && x <= 0.1 + f64::EPSILON);
+            assert!((-0.1 - f64::EPSILON..=0.1 + f64::EPSILON).contains(&x));
         }
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn decimal_in_returns_exact_when_bounds_equal() {
         let x = decimal_in(3.14, 3.14);
         assert_eq!(x, 3.14);
@@ -231,6 +436,117 @@ mod tests {
         assert!(choose_iter(v).is_none());
     }
 
+    #[test]
+    fn choose_iter_accepts_iterators_that_draw_random_numbers() {
+        let pick = choose_iter((0..3).map(|_| integer_in(0, 9))).unwrap();
+        assert!((0..=9).contains(&pick));
+    }
+
+    #[test]
+    fn thread_rng_advances_between_calls() {
+        // Reseeding on each call from the clock repeated values in tight loops.
+        let values: std::collections::HashSet<i128> = (0..1_000).map(|_| integer_in(0, u64::MAX as i128)).collect();
+        assert_eq!(values.len(), 1_000);
+
+        let other = std::thread::spawn(|| integer_in(i128::MIN, i128::MAX)).join().unwrap();
+        assert_ne!(other, integer_in(i128::MIN, i128::MAX));
+    }
+
+    #[test]
+    fn seeded_rng_is_reproducible() {
+        let mut a = Rng::with_seed(7);
+        let mut b = Rng::with_seed(7);
+        let xs: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let ys: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        assert_eq!(xs, ys);
+        assert_ne!(Rng::with_seed(8).next_u64(), xs[0]);
+
+        let mut rng = Rng::with_seed(0);
+        for _ in 0..1_000 {
+            assert!((-3..=3).contains(&rng.integer_in(-3, 3)));
+            assert!((0.0..=1.0).contains(&rng.decimal_in(0.0, 1.0)));
+        }
+        assert_eq!(rng.choose_iter(Vec::<u8>::new()), None);
+        assert_eq!(Rng::with_seed(1).clone().choose(&[5]), Some(&5));
+    }
+
+    #[test]
+    fn shuffle_is_uniform_permutation() {
+        let mut rng = Rng::with_seed(3);
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..6_000 {
+            let mut xs = [0, 1, 2];
+            rng.shuffle(&mut xs);
+            *counts.entry(xs).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        assert!(counts.values().all(|n| (850..1_150).contains(n)), "{:?}", counts);
+
+        let mut xs: Vec<u32> = (0..100).collect();
+        shuffle(&mut xs);
+        assert_ne!(xs, (0..100).collect::<Vec<_>>());
+        xs.sort();
+        assert_eq!(xs, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn partial_shuffle_picks_prefix() {
+        let mut rng = Rng::with_seed(5);
+        let mut firsts = [0; 5];
+        for _ in 0..5_000 {
+            let mut xs = [0, 1, 2, 3, 4];
+            let picked = rng.partial_shuffle(&mut xs, 2);
+            assert_eq!(picked.len(), 2);
+            assert_ne!(picked[0], picked[1]);
+            firsts[picked[0]] += 1;
+            xs.sort();
+            assert_eq!(xs, [0, 1, 2, 3, 4]);
+        }
+        assert!(firsts.iter().all(|n| (850..1_150).contains(n)), "{:?}", firsts);
+
+        let mut xs = [1, 2];
+        assert_eq!(partial_shuffle(&mut xs, 5).len(), 2);
+        assert!(partial_shuffle(&mut [0u8; 0], 1).is_empty());
+    }
+
+    #[test]
+    fn sample_subsets_are_uniform() {
+        let mut rng = Rng::with_seed(11);
+        let xs = ['a', 'b', 'c', 'd', 'e'];
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..10_000 {
+            let mut picked = rng.sample(&xs, 2);
+            assert_ne!(picked[0], picked[1]);
+            picked.sort();
+            *counts.entry(picked).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 10);
+        assert!(counts.values().all(|n| (850..1_150).contains(n)), "{:?}", counts);
+
+        assert_eq!(sample(&xs, 9).len(), 5);
+        assert!(sample(&xs, 0).is_empty());
+    }
+
+    #[test]
+    fn sample_indices_sparse() {
+        // Few picks from many indices take the path that does not list them all.
+        let mut rng = Rng::with_seed(13);
+        let mut counts = [0; 20];
+        for _ in 0..10_000 {
+            let picked = rng.sample_indices(20, 2);
+            assert_eq!(picked.len(), 2);
+            assert_ne!(picked[0], picked[1]);
+            for i in picked {
+                counts[i] += 1;
+            }
+        }
+        assert!(counts.iter().all(|n| (850..1_150).contains(n)), "{:?}", counts);
+
+        let picked = sample_indices(usize::MAX, 3);
+        assert_eq!(picked.len(), 3);
+        assert!(sample_indices(0, 3).is_empty());
+    }
+
     #[test]
     fn choose_iter_returns_from_iterable() {
         let v = vec!["a", "b", "c"];
@@ -32,7 +32,6 @@ use std::any::type_name;
 /// assert_eq!(type_of(&text), "&str");
 /// assert!(type_of(&vec).contains("Vec<u8>"));
 /// ```
-
 pub fn type_of<T>(_: &T) -> &'static str {
     type_name::<T>()
 }
@@ -54,7 +53,6 @@ pub fn type_of<T>(_: &T) -> &'static str {
 /// assert_eq!(type_of_short(&num), "u32");
 /// assert_eq!(type_of_short(&text), "&str");
 /// ```
-
 pub fn type_of_short<T>(value: &T) -> String {
     type_of(value)
         .rsplit("::")
@@ -68,6 +66,7 @@ mod tests {
     use super::{type_of, type_of_short};
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn primitive_types() {
         let int_val = 123i32;
         let float_val = 3.14f64;